@@ -15,7 +15,8 @@ use crate::pages::{
     conv::{base64::Base64Page, timestamp::TimestampPage},
     demo::DemoPage,
     devel::{
-        cert::CertPage, crypto::CryptoPage, hash::HashPage, json::JsonPage, qrcode::QrcodePage, random::RandomPage,
+        cert::CertPage, crypto::CryptoPage, hash::HashPage, json::JsonPage, jwt::JwtPage, qrcode::QrcodePage,
+        random::RandomPage,
     },
     home::HomePage,
     manual::{code::CodePage, custom::CustomPage},
@@ -117,6 +118,16 @@ static VIEWS: Lazy<IndexMap<&'static str, View>> = Lazy::new(|| {
             build: JsonPage::build,
         },
     );
+    m.insert(
+        "/devel/jwt",
+        View {
+            key: "/devel/jwt",
+            icon: "icons/jwt.svg",
+            title: "JWT",
+            group: Some("开发工具"),
+            build: JwtPage::build,
+        },
+    );
     m.insert(
         "/devel/random",
         View {
@@ -10,7 +10,7 @@ use crate::{
 };
 
 pub struct DemoPage {
-    subs: Vec<Subscription>,
+    _subs: Vec<Subscription>,
     test_focus: Entity<InputState>,
     code_editor: Entity<InputState>,
 }
@@ -36,7 +36,7 @@ impl DemoPage {
             let subs = vec![cx.subscribe_in(&test_focus, window, Self::on_input_event)];
 
             Self {
-                subs,
+                _subs: subs,
                 test_focus,
                 code_editor,
             }
@@ -1,17 +1,38 @@
+pub mod connect;
+pub mod csr;
+pub mod inspect;
+pub mod issue;
+pub mod parse;
+#[cfg(test)]
+mod testing;
+pub mod verify;
+
+use std::{fmt, fs, io, path::Path};
+
+use base64::{Engine, engine::general_purpose};
 use gpui::*;
-use gpui_component::input::InputState;
-use openssl::x509::X509;
+use gpui_component::{WindowExt, input::InputState, radio::RadioGroup};
 
 use crate::{
     MainView,
-    comps::{card, label, page, textarea},
+    comps::{card, label, page},
 };
+use csr::CsrPanel;
+use inspect::InspectPanel;
+use issue::IssuePanel;
+
+#[derive(Clone, Copy, PartialEq)]
+enum CertTab {
+    Inspect,
+    Issue,
+    Csr,
+}
 
 pub struct CertPage {
-    input: Entity<InputState>,
-    output: Entity<InputState>,
-    last_input: SharedString,
-    updating: bool,
+    tab: CertTab,
+    inspect: Entity<InspectPanel>,
+    issue: Entity<IssuePanel>,
+    csr: Entity<CsrPanel>,
 }
 
 impl CertPage {
@@ -19,73 +40,138 @@ impl CertPage {
         window: &mut Window,
         cx: &mut Context<MainView>,
     ) -> AnyView {
-        AnyView::from(cx.new(|cx| {
-            let input = cx.new(|cx| InputState::new(window, cx).multi_line(true));
-            let output = cx.new(|cx| InputState::new(window, cx).multi_line(true));
-
-            Self {
-                input,
-                output,
-                last_input: SharedString::default(),
-                updating: false,
-            }
+        AnyView::from(cx.new(|cx| Self {
+            tab: CertTab::Inspect,
+            inspect: cx.new(|cx| InspectPanel::new(window, cx)),
+            issue: cx.new(|cx| IssuePanel::new(window, cx)),
+            csr: cx.new(|cx| CsrPanel::new(window, cx)),
         }))
     }
 }
 
+fn field_row(
+    name: &'static str,
+    value: String,
+) -> Div {
+    div()
+        .flex()
+        .gap_4()
+        .py_1()
+        .border_b_1()
+        .border_color(rgb(0x404040))
+        .text_sm()
+        .child(div().w_24().flex_none().text_color(rgb(0x808080)).child(name))
+        .child(div().flex_1().min_w_0().child(value))
+}
+
+fn options<T: PartialEq + fmt::Display>(
+    id: &'static str,
+    all: &[T],
+    selected: &T,
+) -> RadioGroup {
+    all.iter().fold(
+        RadioGroup::horizontal(id).selected_index(all.iter().position(|item| item == selected)),
+        |group, item| group.child(item.to_string()),
+    )
+}
+
+/// 由通用名生成文件名，通配符等不适合作为文件名的字符替换为 `_`
+fn file_stem(common_name: &str) -> String {
+    common_name
+        .trim()
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || c == '-' || c == '.' {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect()
+}
+
+/// 读取文件内容，文本文件原样返回，二进制文件（DER、P7B、PFX）转为 Base64
+fn read_text(path: &Path) -> io::Result<String> {
+    let data = fs::read(path)?;
+    Ok(String::from_utf8(data).unwrap_or_else(|e| general_purpose::STANDARD.encode(e.into_bytes())))
+}
+
+/// 选择一个文件，将内容（二进制文件转为 Base64）填入 `input`
+fn open_file<T: 'static>(
+    input: Entity<InputState>,
+    prompt: &'static str,
+    window: &mut Window,
+    cx: &mut Context<T>,
+) {
+    let rx = cx.prompt_for_paths(PathPromptOptions {
+        files: true,
+        multiple: false,
+        directories: false,
+        prompt: Some(prompt.into()),
+    });
+    cx.spawn_in(window, async move |_this, cx| {
+        if let Ok(Ok(Some(mut paths))) = rx.await
+            && let Some(path) = paths.pop()
+        {
+            let result = cx.background_executor().spawn(async move { read_text(&path) }).await;
+            let _ = cx.update(|window, cx| match result {
+                Ok(text) => input.update(cx, |state, cx| state.set_value(text, window, cx)),
+                Err(e) => window.push_notification(format!("读取文件失败: {}", e), cx),
+            });
+        }
+    })
+    .detach();
+}
+
 impl Render for CertPage {
     fn render(
         &mut self,
-        window: &mut Window,
+        _window: &mut Window,
         cx: &mut Context<Self>,
     ) -> impl IntoElement {
-        if !self.updating {
-            let in_val = self.input.read(cx).value();
-            if in_val != self.last_input {
-                self.updating = true;
-
-                let pem = in_val.to_string();
-                cx.spawn_in(window, async move |this, cx| {
-                    let out_text = cx
-                        .background_executor()
-                        .spawn(async move {
-                            if pem.trim().is_empty() {
-                                return String::new();
-                            }
-                            X509::from_pem(pem.as_bytes())
-                                .and_then(|cert| cert.to_text())
-                                .map(|bytes| String::from_utf8_lossy(&bytes).into_owned())
-                                .unwrap_or_else(|e| format!("解析失败: {}", e))
-                        })
-                        .await;
-
-                    let _ = cx.update(|window, cx| {
-                        let _ = this.update(cx, |this, cx| {
-                            this.output.update(cx, |state, cx2| {
-                                state.set_value(out_text, window, cx2);
-                            });
-                            this.last_input = this.input.read(cx).value();
-                            this.updating = false;
-                        });
-                    });
-                })
-                .detach();
-            }
-        }
+        let panel = match self.tab {
+            CertTab::Inspect => AnyView::from(self.inspect.clone()),
+            CertTab::Issue => AnyView::from(self.issue.clone()),
+            CertTab::Csr => AnyView::from(self.csr.clone()),
+        };
 
         page()
             .size_full()
             .child(
-                card()
-                    .h_64()
-                    .child(label("证书"))
-                    .child(textarea(&self.input, |input| input)),
-            )
-            .child(
-                card()
-                    .flex_1()
-                    .child(label("解析结果"))
-                    .child(textarea(&self.output, |input| input)),
+                card().child(
+                    div().flex().gap_4().items_center().child(label("功能")).child(
+                        RadioGroup::horizontal("cert-tab")
+                            .selected_index(Some(match self.tab {
+                                CertTab::Inspect => 0,
+                                CertTab::Issue => 1,
+                                CertTab::Csr => 2,
+                            }))
+                            .child("证书解析")
+                            .child("签发证书")
+                            .child("CSR")
+                            .on_click(cx.listener(|this, index: &usize, _window, cx| {
+                                this.tab = match *index {
+                                    1 => CertTab::Issue,
+                                    2 => CertTab::Csr,
+                                    _ => CertTab::Inspect,
+                                };
+                                cx.notify();
+                            })),
+                    ),
+                ),
             )
+            .child(div().flex_1().flex().flex_col().min_h_0().child(panel))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::file_stem;
+
+    #[test]
+    fn file_stem_replaces_unsafe_chars() {
+        assert_eq!(file_stem(" *.example.com "), "_.example.com");
+        assert_eq!(file_stem("My CA/1"), "My_CA_1");
+        assert_eq!(file_stem("测试-ca"), "__-ca");
     }
 }
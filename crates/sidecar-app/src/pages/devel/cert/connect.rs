@@ -0,0 +1,326 @@
+use std::{
+    net::{IpAddr, TcpStream, ToSocketAddrs},
+    time::Duration,
+};
+
+use openssl::{
+    error::ErrorStack,
+    hash::MessageDigest,
+    ocsp::{OcspBasicResponseRef, OcspCertId, OcspCertStatus, OcspFlag, OcspResponse, OcspResponseStatus},
+    ssl::{SslConnector, SslMethod, SslRef, SslVerifyMode, StatusType},
+    stack::Stack,
+    x509::{X509, X509VerifyResult, store::X509StoreBuilder},
+};
+
+/// 连接和握手的超时时间
+const TIMEOUT: Duration = Duration::from_secs(10);
+
+/// 默认端口
+const DEFAULT_PORT: u16 = 443;
+
+/// 检查 OCSP 响应有效期时允许的时钟偏差，单位为秒
+const OCSP_LEEWAY: u32 = 300;
+
+/// 客户端声明支持的 ALPN 协议
+const ALPN_PROTOCOLS: &[u8] = b"\x02h2\x08http/1.1";
+
+/// TLS 握手后获取到的会话信息和证书链
+pub struct TlsInfo {
+    pub host: String,
+    pub peer: String,
+    pub sni: Option<String>,
+    pub protocol: String,
+    pub cipher: String,
+    pub alpn: Option<String>,
+    pub ocsp: String,
+    pub verify_result: String,
+    pub chain: Vec<X509>,
+}
+
+impl TlsInfo {
+    /// 以 (名称, 值) 形式列出会话信息
+    pub fn fields(&self) -> Vec<(&'static str, String)> {
+        vec![
+            ("服务器", self.peer.clone()),
+            ("SNI", self.sni.clone().unwrap_or_else(|| "未发送".to_string())),
+            ("协议版本", self.protocol.clone()),
+            ("加密套件", self.cipher.clone()),
+            ("ALPN", self.alpn.clone().unwrap_or_else(|| "未协商".to_string())),
+            ("OCSP 装订", self.ocsp.clone()),
+            ("默认验证", self.verify_result.clone()),
+            ("证书数量", self.chain.len().to_string()),
+        ]
+    }
+}
+
+/// 拆分 `host:port`，支持 `[::1]:443` 形式的 IPv6 地址，省略端口时使用 443
+pub fn parse_target(target: &str) -> Result<(String, u16), String> {
+    let target = target.trim();
+    let target = target
+        .strip_prefix("https://")
+        .unwrap_or(target)
+        .split('/')
+        .next()
+        .unwrap_or_default();
+    if target.is_empty() {
+        return Err("请输入服务器地址".to_string());
+    }
+
+    let (host, port) = if let Some(rest) = target.strip_prefix('[') {
+        let (host, rest) = rest.split_once(']').ok_or("IPv6 地址缺少 ]")?;
+        (host, rest.strip_prefix(':'))
+    } else if target.matches(':').count() > 1 {
+        // 没有方括号的 IPv6 地址，不能再带端口
+        (target, None)
+    } else {
+        match target.split_once(':') {
+            Some((host, port)) => (host, Some(port)),
+            None => (target, None),
+        }
+    };
+    let port = match port {
+        Some(port) => port.parse().map_err(|_| format!("端口格式错误: {}", port))?,
+        None => DEFAULT_PORT,
+    };
+    Ok((host.to_string(), port))
+}
+
+/// 连接服务器完成 TLS 握手，返回服务器出示的证书链和协商结果
+///
+/// 握手时不校验证书，以便查看过期、自签名等有问题的证书；OpenSSL 默认验证的结果单独记录在
+/// `verify_result` 中。
+pub fn fetch(
+    target: &str,
+    sni: &str,
+) -> Result<TlsInfo, String> {
+    let (host, port) = parse_target(target)?;
+    let addrs: Vec<_> = (host.as_str(), port)
+        .to_socket_addrs()
+        .map_err(|e| format!("无法解析 {}: {}", host, e))?
+        .collect();
+    let mut connected = Err(format!("无法解析 {}", host));
+    for addr in &addrs {
+        connected = TcpStream::connect_timeout(addr, TIMEOUT)
+            .map(|stream| (stream, *addr))
+            .map_err(|e| format!("无法连接 {}: {}", addr, e));
+        if connected.is_ok() {
+            break;
+        }
+    }
+    let (stream, peer) = connected?;
+    stream
+        .set_read_timeout(Some(TIMEOUT))
+        .and_then(|_| stream.set_write_timeout(Some(TIMEOUT)))
+        .map_err(|e| e.to_string())?;
+
+    // 未指定 SNI 时使用域名，IP 地址不发送 SNI
+    let sni = match sni.trim() {
+        "" if host.parse::<IpAddr>().is_ok() => None,
+        "" => Some(host.clone()),
+        sni => Some(sni.to_string()),
+    };
+
+    let mut builder = SslConnector::builder(SslMethod::tls_client()).map_err(|e| e.to_string())?;
+    builder.set_verify(SslVerifyMode::NONE);
+    builder.set_alpn_protos(ALPN_PROTOCOLS).map_err(|e| e.to_string())?;
+    let mut config = builder.build().configure().map_err(|e| e.to_string())?;
+    config.set_verify_hostname(false);
+    config.set_use_server_name_indication(sni.is_some());
+    config.set_status_type(StatusType::OCSP).map_err(|e| e.to_string())?;
+
+    let stream = config
+        .connect(sni.as_deref().unwrap_or(&host), stream)
+        .map_err(|e| format!("TLS 握手失败: {}", e))?;
+    let ssl = stream.ssl();
+
+    let chain: Vec<X509> = match ssl.peer_cert_chain() {
+        Some(chain) => chain.iter().map(|cert| cert.to_owned()).collect(),
+        None => ssl.peer_certificate().into_iter().collect(),
+    };
+    if chain.is_empty() {
+        return Err("服务器没有出示证书".to_string());
+    }
+
+    let cipher = ssl
+        .current_cipher()
+        .map(|cipher| format!("{}（{} 位）", cipher.name(), cipher.bits().secret))
+        .unwrap_or_default();
+    let verify_result = match ssl.verify_result() {
+        X509VerifyResult::OK => "通过".to_string(),
+        result => result.error_string().to_string(),
+    };
+
+    Ok(TlsInfo {
+        host,
+        peer: peer.to_string(),
+        sni,
+        protocol: ssl.version_str().to_string(),
+        cipher,
+        alpn: ssl
+            .selected_alpn_protocol()
+            .map(|alpn| String::from_utf8_lossy(alpn).into_owned()),
+        ocsp: ocsp_status(ssl, &chain),
+        verify_result,
+        chain,
+    })
+}
+
+/// 解析服务器装订的 OCSP 响应，并查找终端证书的状态
+fn ocsp_status(
+    ssl: &SslRef,
+    chain: &[X509],
+) -> String {
+    let Some(der) = ssl.ocsp_status() else {
+        return "未装订".to_string();
+    };
+    let response = match OcspResponse::from_der(der) {
+        Ok(response) => response,
+        Err(e) => return format!("已装订，但无法解析: {}", e),
+    };
+    if response.status() != OcspResponseStatus::SUCCESSFUL {
+        return format!("已装订，响应状态 {}", response.status().as_raw());
+    }
+
+    let basic = match response.basic() {
+        Ok(basic) => basic,
+        Err(e) => return format!("已装订，但无法解析: {}", e),
+    };
+    let (Some(leaf), Some(issuer)) = (chain.first(), chain.get(1)) else {
+        return "已装订，未找到终端证书的状态".to_string();
+    };
+    let Some(status) = OcspCertId::from_cert(MessageDigest::sha1(), leaf, issuer)
+        .ok()
+        .and_then(|id| basic.find_status(&id))
+    else {
+        return "已装订，未找到终端证书的状态".to_string();
+    };
+
+    let text = match status.status {
+        OcspCertStatus::GOOD => "证书状态正常",
+        OcspCertStatus::REVOKED => "证书已吊销",
+        _ => "证书状态未知",
+    };
+    // 响应需要由系统信任的颁发者或其授权的响应者签名，且在有效期内，否则只作参考
+    if let Err(e) = verify_ocsp(&basic, chain) {
+        return format!("已装订，{}（响应签名未验证: {}）", text, e);
+    }
+    if status.check_validity(OCSP_LEEWAY, None).is_err() {
+        return format!("已装订，{}（响应不在有效期内）", text);
+    }
+    format!("已装订，{}", text)
+}
+
+/// 用系统信任库验证 OCSP 响应的签名，服务器出示的证书链作为中间证书
+fn verify_ocsp(
+    basic: &OcspBasicResponseRef,
+    chain: &[X509],
+) -> Result<(), ErrorStack> {
+    let mut certs = Stack::new()?;
+    for cert in chain {
+        certs.push(cert.clone())?;
+    }
+    let mut store = X509StoreBuilder::new()?;
+    store.set_default_paths()?;
+    basic.verify(&certs, &store.build(), OcspFlag::empty())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        io::Read,
+        net::TcpListener,
+        sync::{Arc, Mutex},
+        thread,
+    };
+
+    use openssl::ssl::{AlpnError, NameType, SslAcceptor, select_next_proto};
+
+    use super::*;
+    use crate::pages::devel::cert::testing::{key, now, self_signed};
+
+    /// 在 127.0.0.1 上提供一次 TLS 握手，返回监听地址、服务器证书和服务器收到的 SNI
+    fn serve() -> (String, X509, Arc<Mutex<Option<String>>>) {
+        let key = key();
+        let cert = self_signed("localhost", &key, now() - 3600, now() + 86400);
+        let mut acceptor = SslAcceptor::mozilla_intermediate_v5(SslMethod::tls()).unwrap();
+        acceptor.set_private_key(&key).unwrap();
+        acceptor.set_certificate(&cert).unwrap();
+        acceptor
+            .set_alpn_select_callback(|_, client| select_next_proto(b"\x08http/1.1", client).ok_or(AlpnError::NOACK));
+        let sni = Arc::new(Mutex::new(None));
+        let received = sni.clone();
+        acceptor.set_servername_callback(move |ssl, _| {
+            *received.lock().unwrap() = ssl.servername(NameType::HOST_NAME).map(str::to_string);
+            Ok(())
+        });
+        let acceptor = acceptor.build();
+
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+        thread::spawn(move || {
+            let (stream, _) = listener.accept().unwrap();
+            // 保持连接直到客户端断开
+            if let Ok(mut stream) = acceptor.accept(stream) {
+                let _ = stream.read(&mut [0u8; 1]);
+            }
+        });
+        (addr.to_string(), cert, sni)
+    }
+
+    #[test]
+    fn fetch_from_local_server() {
+        let (addr, cert, sni) = serve();
+        let info = fetch(&addr, "").unwrap();
+
+        assert_eq!(info.host, "127.0.0.1");
+        assert_eq!(info.peer, addr);
+        // IP 地址不发送 SNI
+        assert_eq!(info.sni, None);
+        assert_eq!(*sni.lock().unwrap(), None);
+        assert_eq!(info.protocol, "TLSv1.3");
+        assert_eq!(info.alpn.as_deref(), Some("http/1.1"));
+        assert_eq!(info.ocsp, "未装订");
+        assert_ne!(info.verify_result, "通过");
+        assert_eq!(info.chain.len(), 1);
+        assert_eq!(info.chain[0].to_der().unwrap(), cert.to_der().unwrap());
+    }
+
+    #[test]
+    fn fetch_sends_custom_sni() {
+        let (addr, _, sni) = serve();
+        let info = fetch(&format!("https://{}/path", addr), "example.test").unwrap();
+        assert_eq!(info.sni.as_deref(), Some("example.test"));
+        assert_eq!(sni.lock().unwrap().as_deref(), Some("example.test"));
+        assert_eq!(info.chain.len(), 1);
+    }
+
+    #[test]
+    fn fetch_reports_connection_errors() {
+        let port = TcpListener::bind("127.0.0.1:0").unwrap().local_addr().unwrap().port();
+        let Err(error) = fetch(&format!("127.0.0.1:{}", port), "") else {
+            panic!("connected to a closed port");
+        };
+        assert!(error.starts_with("无法连接"), "{}", error);
+    }
+
+    #[test]
+    fn parse_targets() {
+        assert_eq!(parse_target("[::1]:443"), Ok(("::1".to_string(), 443)));
+        assert_eq!(parse_target("[::1]:8443"), Ok(("::1".to_string(), 8443)));
+        assert_eq!(parse_target("[::1]"), Ok(("::1".to_string(), 443)));
+        assert_eq!(parse_target("2001:db8::1"), Ok(("2001:db8::1".to_string(), 443)));
+        assert_eq!(parse_target("example.com"), Ok(("example.com".to_string(), 443)));
+        assert_eq!(parse_target("example.com:8443"), Ok(("example.com".to_string(), 8443)));
+        assert_eq!(
+            parse_target(" https://example.com/a/b "),
+            Ok(("example.com".to_string(), 443))
+        );
+        assert_eq!(parse_target("https://[::1]:8443/"), Ok(("::1".to_string(), 8443)));
+
+        assert!(parse_target("").is_err());
+        assert!(parse_target("https://").is_err());
+        assert!(parse_target("[::1").is_err());
+        assert!(parse_target("example.com:https").is_err());
+        assert!(parse_target("example.com:70000").is_err());
+    }
+}
@@ -0,0 +1,652 @@
+use std::{fmt, fs};
+
+use base64::{Engine, engine::general_purpose};
+use gpui::*;
+use gpui_component::{
+    ActiveTheme, WindowExt,
+    input::{InputEvent, InputState},
+};
+use openssl::{
+    error::ErrorStack,
+    pkey::{PKey, Private},
+    stack::Stack,
+    x509::{X509NameRef, X509Req, X509ReqBuilder},
+};
+use x509_parser::{
+    certification_request::X509CertificationRequest,
+    cri_attributes::ParsedCriAttribute,
+    objects::{oid_registry, oid2sn},
+    prelude::FromDer,
+};
+
+use super::{
+    field_row, file_stem,
+    issue::{Subject, digest_for, parse_sans, san_extension},
+    open_file, options,
+    parse::{alt_names, extension_names, format_name, key_description},
+};
+use crate::{
+    comps::{button, card, label, textarea},
+    pages::{
+        devel::crypto::asymmetric::{KeyType, parse_private},
+        utils::strip_str,
+    },
+};
+
+/// 读取 PEM 或 Base64 编码的 DER 格式的 CSR
+pub fn load_csr(text: &str) -> Result<X509Req, String> {
+    let text = text.trim();
+    if text.contains("-----BEGIN") {
+        return X509Req::from_pem(text.as_bytes()).map_err(|e| format!("PEM 解析失败: {}", e));
+    }
+    let der = general_purpose::STANDARD
+        .decode(strip_str(text))
+        .map_err(|_| "无法识别的格式，请输入 PEM 或 Base64 编码的 DER 内容".to_string())?;
+    X509Req::from_der(&der).map_err(|e| format!("DER 解析失败: {}", e))
+}
+
+/// CSR 的结构化信息
+pub struct CsrInfo {
+    pub subject: String,
+    pub key: String,
+    pub signature_algorithm: String,
+    pub signature_valid: bool,
+    pub sans: Vec<String>,
+    pub extensions: Vec<String>,
+}
+
+impl CsrInfo {
+    pub fn new(req: &X509Req) -> Result<CsrInfo, String> {
+        let der = req.to_der().map_err(|e| e.to_string())?;
+        let (_, parsed) = X509CertificationRequest::from_der(&der).map_err(|e| format!("CSR 解析失败: {}", e))?;
+        // 扩展放在 extensionRequest 属性中
+        let extensions = parsed
+            .certification_request_info
+            .iter_attributes()
+            .find_map(|attr| match attr.parsed_attribute() {
+                ParsedCriAttribute::ExtensionRequest(request) => Some(request.extensions.as_slice()),
+                _ => None,
+            })
+            .unwrap_or_default();
+        let algorithm = &parsed.signature_algorithm.algorithm;
+        let public_key = req.public_key().map_err(|e| format!("无法读取公钥: {}", e))?;
+
+        Ok(CsrInfo {
+            subject: format_name(req.subject_name()),
+            key: key_description(&public_key),
+            signature_algorithm: match oid2sn(algorithm, oid_registry()) {
+                Ok(name) => name.to_string(),
+                Err(_) => algorithm.to_id_string(),
+            },
+            signature_valid: req.verify(&public_key).unwrap_or(false),
+            sans: alt_names(extensions),
+            extensions: extension_names(extensions),
+        })
+    }
+
+    /// 以 (名称, 值) 形式列出所有字段
+    pub fn fields(&self) -> Vec<(&'static str, String)> {
+        let mut fields = vec![
+            ("主题", self.subject.clone()),
+            ("公钥", self.key.clone()),
+            ("签名算法", self.signature_algorithm.clone()),
+        ];
+        if !self.sans.is_empty() {
+            fields.push(("备用名称", self.sans.join("\n")));
+        }
+        if !self.extensions.is_empty() {
+            fields.push(("扩展", self.extensions.join("\n")));
+        }
+        fields
+    }
+}
+
+/// 使用指定私钥生成 CSR，备用名称为空时不添加扩展
+pub fn create(
+    subject: &Subject,
+    sans: &[String],
+    key: &PKey<Private>,
+) -> Result<X509Req, String> {
+    let name = subject.name()?;
+    build_req(&name, sans, key).map_err(|e| format!("生成 CSR 失败: {}", e))
+}
+
+fn build_req(
+    name: &X509NameRef,
+    sans: &[String],
+    key: &PKey<Private>,
+) -> Result<X509Req, ErrorStack> {
+    let mut builder = X509ReqBuilder::new()?;
+    builder.set_version(0)?;
+    builder.set_subject_name(name)?;
+    builder.set_pubkey(key)?;
+    let alt_names = san_extension(sans, &builder.x509v3_context(None))?;
+    if let Some(alt_names) = alt_names {
+        let mut extensions = Stack::new()?;
+        extensions.push(alt_names)?;
+        builder.add_extensions(&extensions)?;
+    }
+    builder.sign(key, digest_for(key))?;
+    Ok(builder.build())
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum CsrMode {
+    Decode,
+    Create,
+}
+
+impl CsrMode {
+    const ALL: [CsrMode; 2] = [CsrMode::Decode, CsrMode::Create];
+}
+
+impl fmt::Display for CsrMode {
+    fn fmt(
+        &self,
+        f: &mut fmt::Formatter<'_>,
+    ) -> fmt::Result {
+        let name = match self {
+            CsrMode::Decode => "解析 CSR",
+            CsrMode::Create => "生成 CSR",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+/// CSR 使用的私钥来源
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum KeySource {
+    Generate,
+    Import,
+}
+
+impl KeySource {
+    const ALL: [KeySource; 2] = [KeySource::Generate, KeySource::Import];
+}
+
+impl fmt::Display for KeySource {
+    fn fmt(
+        &self,
+        f: &mut fmt::Formatter<'_>,
+    ) -> fmt::Result {
+        let name = match self {
+            KeySource::Generate => "生成新密钥",
+            KeySource::Import => "导入私钥",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+pub struct CsrPanel {
+    mode: CsrMode,
+    input: Entity<InputState>,
+    info: Result<Option<CsrInfo>, String>,
+    common_name: Entity<InputState>,
+    organization: Entity<InputState>,
+    organizational_unit: Entity<InputState>,
+    locality: Entity<InputState>,
+    province: Entity<InputState>,
+    country: Entity<InputState>,
+    sans: Entity<InputState>,
+    key_source: KeySource,
+    key_type: KeyType,
+    key_input: Entity<InputState>,
+    generating: bool,
+    key_output: Entity<InputState>,
+    csr_output: Entity<InputState>,
+    _subs: Vec<Subscription>,
+}
+
+impl CsrPanel {
+    pub fn new(
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Self {
+        let input = cx.new(|cx| {
+            InputState::new(window, cx)
+                .multi_line(true)
+                .placeholder("粘贴 PEM 格式的 CSR，或 Base64 编码的 DER 内容")
+        });
+        let common_name = cx.new(|cx| InputState::new(window, cx).placeholder("例如 www.example.com"));
+        let organization = cx.new(|cx| InputState::new(window, cx));
+        let organizational_unit = cx.new(|cx| InputState::new(window, cx));
+        let locality = cx.new(|cx| InputState::new(window, cx));
+        let province = cx.new(|cx| InputState::new(window, cx));
+        let country = cx.new(|cx| InputState::new(window, cx).placeholder("CN"));
+        let sans = cx.new(|cx| {
+            InputState::new(window, cx).placeholder("以逗号或换行分隔，例如 example.com, *.example.com, 127.0.0.1")
+        });
+        let key_input = cx.new(|cx| {
+            InputState::new(window, cx)
+                .multi_line(true)
+                .placeholder("粘贴 PEM 或 Base64 编码的 DER 私钥")
+        });
+        let key_output = cx.new(|cx| InputState::new(window, cx).multi_line(true));
+        let csr_output = cx.new(|cx| InputState::new(window, cx).multi_line(true));
+        let subs = vec![cx.subscribe_in(&input, window, Self::on_input_event)];
+
+        Self {
+            mode: CsrMode::Decode,
+            input,
+            info: Ok(None),
+            common_name,
+            organization,
+            organizational_unit,
+            locality,
+            province,
+            country,
+            sans,
+            key_source: KeySource::Generate,
+            key_type: KeyType::Rsa2048,
+            key_input,
+            generating: false,
+            key_output,
+            csr_output,
+            _subs: subs,
+        }
+    }
+
+    fn on_input_event(
+        &mut self,
+        state: &Entity<InputState>,
+        ev: &InputEvent,
+        _window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        if let InputEvent::Change = ev {
+            let text = state.read(cx).value().to_string();
+            self.info = if text.trim().is_empty() {
+                Ok(None)
+            } else {
+                load_csr(&text).and_then(|req| CsrInfo::new(&req)).map(Some)
+            };
+            cx.notify();
+        }
+    }
+
+    fn generate(
+        &mut self,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        if self.generating {
+            return;
+        }
+        let sans = match parse_sans(&self.sans.read(cx).value()) {
+            Ok(sans) => sans,
+            Err(e) => {
+                window.push_notification(e, cx);
+                return;
+            }
+        };
+        let subject = Subject {
+            common_name: self.common_name.read(cx).value().to_string(),
+            organization: self.organization.read(cx).value().to_string(),
+            organizational_unit: self.organizational_unit.read(cx).value().to_string(),
+            locality: self.locality.read(cx).value().to_string(),
+            province: self.province.read(cx).value().to_string(),
+            country: self.country.read(cx).value().to_string(),
+        };
+        let imported = match self.key_source {
+            KeySource::Generate => None,
+            KeySource::Import => Some(self.key_input.read(cx).value().to_string()),
+        };
+        let key_type = self.key_type;
+        self.generating = true;
+        cx.notify();
+
+        cx.spawn_in(window, async move |this, cx| {
+            let result = cx
+                .background_executor()
+                .spawn(async move {
+                    let key = match &imported {
+                        Some(text) if text.trim().is_empty() => return Err("请填写私钥".to_string()),
+                        Some(text) => parse_private(text)?,
+                        None => key_type.generate()?,
+                    };
+                    let req = create(&subject, &sans, &key)?;
+                    let csr = req
+                        .to_pem()
+                        .map(|pem| String::from_utf8_lossy(&pem).into_owned())
+                        .map_err(|e| format!("导出 CSR 失败: {}", e))?;
+                    // 导入的私钥已由用户保管，只输出新生成的私钥
+                    let key = match imported {
+                        Some(_) => String::new(),
+                        None => key
+                            .private_key_to_pem_pkcs8()
+                            .map(|pem| String::from_utf8_lossy(&pem).into_owned())
+                            .map_err(|e| format!("导出私钥失败: {}", e))?,
+                    };
+                    Ok::<_, String>((key, csr))
+                })
+                .await;
+
+            let _ = cx.update(|window, cx| {
+                let _ = this.update(cx, |this, cx| {
+                    this.generating = false;
+                    match result {
+                        Ok((key, csr)) => {
+                            this.key_output.update(cx, |state, cx| state.set_value(key, window, cx));
+                            this.csr_output.update(cx, |state, cx| state.set_value(csr, window, cx));
+                        }
+                        Err(e) => window.push_notification(e, cx),
+                    }
+                    cx.notify();
+                });
+            });
+        })
+        .detach();
+    }
+
+    fn save(
+        &mut self,
+        state: Entity<InputState>,
+        extension: &str,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let data = state.read(cx).value().to_string();
+        if data.is_empty() {
+            window.push_notification("没有可保存的内容", cx);
+            return;
+        }
+
+        let stem = file_stem(&self.common_name.read(cx).value());
+        let name = format!("{}.{}", stem, extension);
+        let directory = std::env::home_dir().unwrap_or_default();
+        let rx = cx.prompt_for_new_path(&directory, Some(&name));
+        cx.spawn_in(window, async move |_this, cx| {
+            if let Ok(Ok(Some(path))) = rx.await {
+                let message = match fs::write(&path, data) {
+                    Ok(_) => format!("已保存到 {}", path.display()),
+                    Err(e) => format!("保存失败: {}", e),
+                };
+                let _ = cx.update(|window, cx| {
+                    window.push_notification(message, cx);
+                });
+            }
+        })
+        .detach();
+    }
+
+    fn render_decode(
+        &mut self,
+        cx: &mut Context<Self>,
+    ) -> Vec<AnyElement> {
+        let input =
+            card()
+                .h_48()
+                .child(
+                    div()
+                        .flex()
+                        .gap_4()
+                        .items_center()
+                        .child(label("CSR"))
+                        .child(div().flex_1())
+                        .child(button(cx, "csr-open").label("打开文件").on_click(cx.listener(
+                            |this, _ev, window, cx| {
+                                open_file(this.input.clone(), "选择 CSR 文件", window, cx);
+                            },
+                        ))),
+                )
+                .child(textarea(&self.input, |input| input.font_family("monospace")));
+
+        let result = match &self.info {
+            Ok(Some(info)) => card()
+                .child(
+                    div().flex().gap_4().items_center().child(label("请求信息")).child(
+                        div()
+                            .text_sm()
+                            .text_color(if info.signature_valid {
+                                cx.theme().success
+                            } else {
+                                cx.theme().danger
+                            })
+                            .child(if info.signature_valid {
+                                "签名有效"
+                            } else {
+                                "签名无效"
+                            }),
+                    ),
+                )
+                .children(info.fields().into_iter().map(|(name, value)| field_row(name, value))),
+            Ok(None) => div(),
+            Err(err) => div()
+                .px_4()
+                .py_2()
+                .rounded_lg()
+                .bg(cx.theme().danger)
+                .text_sm()
+                .text_color(cx.theme().danger_foreground)
+                .child(format!("解析失败：{}", err)),
+        };
+
+        vec![
+            input.into_any_element(),
+            div()
+                .id("csr-info")
+                .flex_1()
+                .min_h_0()
+                .overflow_y_scroll()
+                .child(result)
+                .into_any_element(),
+        ]
+    }
+
+    fn render_create(
+        &mut self,
+        cx: &mut Context<Self>,
+    ) -> Vec<AnyElement> {
+        let key = match self.key_source {
+            KeySource::Generate => div().flex().gap_4().items_center().child(label("密钥类型")).child(
+                options("csr-key-type", &KeyType::ALL, &self.key_type).on_click(cx.listener(
+                    |this, index: &usize, _window, cx| {
+                        this.key_type = KeyType::ALL[*index];
+                        cx.notify();
+                    },
+                )),
+            ),
+            KeySource::Import => div()
+                .h_24()
+                .flex()
+                .child(textarea(&self.key_input, |input| input.font_family("monospace"))),
+        };
+
+        let form = card()
+            .child(
+                div()
+                    .flex()
+                    .gap_4()
+                    .items_center()
+                    .child(label("通用名称"))
+                    .child(textarea(&self.common_name, |input| input))
+                    .child(label("组织"))
+                    .child(textarea(&self.organization, |input| input))
+                    .child(label("部门"))
+                    .child(textarea(&self.organizational_unit, |input| input)),
+            )
+            .child(
+                div()
+                    .flex()
+                    .gap_4()
+                    .items_center()
+                    .child(label("城市"))
+                    .child(textarea(&self.locality, |input| input))
+                    .child(label("省份"))
+                    .child(textarea(&self.province, |input| input))
+                    .child(label("国家"))
+                    .child(div().w_16().child(textarea(&self.country, |input| input))),
+            )
+            .child(
+                div()
+                    .flex()
+                    .gap_4()
+                    .items_center()
+                    .child(label("备用名称"))
+                    .child(textarea(&self.sans, |input| input)),
+            )
+            .child(div().flex().gap_4().items_center().child(label("私钥")).child(
+                options("csr-key-source", &KeySource::ALL, &self.key_source).on_click(cx.listener(
+                    |this, index: &usize, _window, cx| {
+                        this.key_source = KeySource::ALL[*index];
+                        cx.notify();
+                    },
+                )),
+            ))
+            .child(key)
+            .child(
+                div().flex().gap_4().items_center().child(div().flex_1()).child(
+                    button(cx, "csr-generate")
+                        .label(if self.generating { "生成中..." } else { "生成" })
+                        .on_click(cx.listener(|this, _ev, window, cx| {
+                            this.generate(window, cx);
+                        })),
+                ),
+            );
+
+        let output = card()
+            .flex_1()
+            .child(
+                div()
+                    .flex()
+                    .gap_4()
+                    .items_center()
+                    .child(label("私钥 / CSR"))
+                    .child(div().flex_1())
+                    .child(button(cx, "csr-save-key").label("保存私钥").on_click(cx.listener(
+                        |this, _ev, window, cx| {
+                            this.save(this.key_output.clone(), "key", window, cx);
+                        },
+                    )))
+                    .child(button(cx, "csr-save-csr").label("保存 CSR").on_click(cx.listener(
+                        |this, _ev, window, cx| {
+                            this.save(this.csr_output.clone(), "csr", window, cx);
+                        },
+                    ))),
+            )
+            .child(
+                div()
+                    .flex_1()
+                    .flex()
+                    .gap_4()
+                    .min_h_0()
+                    .child(textarea(&self.key_output, |input| input.font_family("monospace")))
+                    .child(textarea(&self.csr_output, |input| input.font_family("monospace"))),
+            );
+
+        vec![form.into_any_element(), output.into_any_element()]
+    }
+}
+
+impl Render for CsrPanel {
+    fn render(
+        &mut self,
+        _window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> impl IntoElement {
+        let content = match self.mode {
+            CsrMode::Decode => self.render_decode(cx),
+            CsrMode::Create => self.render_create(cx),
+        };
+
+        div()
+            .flex_1()
+            .flex()
+            .flex_col()
+            .gap_4()
+            .min_h_0()
+            .child(
+                card().child(div().flex().gap_4().items_center().child(label("操作")).child(
+                    options("csr-mode", &CsrMode::ALL, &self.mode).on_click(cx.listener(
+                        |this, index: &usize, _window, cx| {
+                            this.mode = CsrMode::ALL[*index];
+                            cx.notify();
+                        },
+                    )),
+                )),
+            )
+            .children(content)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use base64::{Engine, engine::general_purpose};
+
+    use super::{CsrInfo, create, load_csr};
+    use crate::pages::devel::{
+        cert::issue::{Subject, parse_sans},
+        crypto::asymmetric::KeyType,
+    };
+
+    fn subject() -> Subject {
+        Subject {
+            common_name: "*.example.com".to_string(),
+            organization: "Sidecar".to_string(),
+            organizational_unit: "Dev".to_string(),
+            locality: "Hangzhou".to_string(),
+            province: "Zhejiang".to_string(),
+            country: "cn".to_string(),
+        }
+    }
+
+    /// 生成 CSR 并导出为 DER
+    fn csr_der(key_type: KeyType) -> Vec<u8> {
+        let key = key_type.generate().unwrap();
+        let sans = parse_sans("*.example.com, example.com\n192.168.1.1").unwrap();
+        create(&subject(), &sans, &key).unwrap().to_der().unwrap()
+    }
+
+    #[test]
+    fn round_trip() {
+        for key_type in [KeyType::Rsa2048, KeyType::P256, KeyType::Ed25519] {
+            let key = key_type.generate().unwrap();
+            let sans = parse_sans("*.example.com, example.com\n192.168.1.1").unwrap();
+            let pem = create(&subject(), &sans, &key).unwrap().to_pem().unwrap();
+
+            let req = load_csr(&String::from_utf8(pem).unwrap()).unwrap();
+            let info = CsrInfo::new(&req).unwrap();
+            assert_eq!(
+                info.subject, "C=CN, ST=Zhejiang, L=Hangzhou, O=Sidecar, OU=Dev, CN=*.example.com",
+                "{}",
+                key_type
+            );
+            assert_eq!(
+                info.sans,
+                ["DNS: *.example.com", "DNS: example.com", "IP: 192.168.1.1"],
+                "{}",
+                key_type
+            );
+            assert!(info.signature_valid, "{}", key_type);
+        }
+    }
+
+    #[test]
+    fn tampered_signature_is_invalid() {
+        for key_type in [KeyType::Rsa2048, KeyType::P256, KeyType::Ed25519] {
+            let mut der = csr_der(key_type);
+            // 签名位于 DER 末尾
+            *der.last_mut().unwrap() ^= 0x01;
+            let req = load_csr(&general_purpose::STANDARD.encode(&der)).unwrap();
+            assert!(!CsrInfo::new(&req).unwrap().signature_valid, "{}", key_type);
+        }
+    }
+
+    #[test]
+    fn accepts_base64_der() {
+        let der = csr_der(KeyType::P256);
+        // 允许 Base64 中夹杂换行
+        let text = general_purpose::STANDARD
+            .encode(&der)
+            .as_bytes()
+            .chunks(64)
+            .map(|line| std::str::from_utf8(line).unwrap())
+            .collect::<Vec<_>>()
+            .join("\n");
+
+        let req = load_csr(&text).unwrap();
+        assert_eq!(req.to_der().unwrap(), der);
+        assert!(CsrInfo::new(&req).unwrap().signature_valid);
+        assert!(load_csr("not a csr").is_err());
+    }
+}
@@ -0,0 +1,459 @@
+use std::fmt;
+
+use gpui::*;
+use gpui_component::{
+    ActiveTheme, WindowExt,
+    input::{InputEvent, InputState},
+};
+
+use super::{
+    connect::{TlsInfo, fetch},
+    field_row, open_file, options,
+    parse::{CertInfo, Validity, load_certs},
+    verify::{Check, Purpose, TrustSource, verify_chain},
+};
+use crate::comps::{button, card, label, textarea};
+
+/// 证书来源：手动输入或从服务器获取
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum CertMode {
+    Parse,
+    Connect,
+}
+
+impl CertMode {
+    const ALL: [CertMode; 2] = [CertMode::Parse, CertMode::Connect];
+}
+
+impl fmt::Display for CertMode {
+    fn fmt(
+        &self,
+        f: &mut fmt::Formatter<'_>,
+    ) -> fmt::Result {
+        let name = match self {
+            CertMode::Parse => "解析证书",
+            CertMode::Connect => "连接服务器",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+pub struct InspectPanel {
+    mode: CertMode,
+    target: Entity<InputState>,
+    sni: Entity<InputState>,
+    tls: Option<Result<TlsInfo, String>>,
+    connecting: bool,
+    input: Entity<InputState>,
+    password: Entity<InputState>,
+    certs: Result<Vec<CertInfo>, String>,
+    trust: TrustSource,
+    purpose: Purpose,
+    hostname: Entity<InputState>,
+    ca_bundle: Entity<InputState>,
+    checks: Option<Result<Vec<Check>, String>>,
+    verifying: bool,
+    _subs: Vec<Subscription>,
+}
+
+impl InspectPanel {
+    pub fn new(
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Self {
+        let input = cx.new(|cx| {
+            InputState::new(window, cx)
+                .multi_line(true)
+                .placeholder("粘贴 PEM 证书链，或 Base64 编码的 DER/PKCS#7/PKCS#12 内容")
+        });
+        let password = cx.new(|cx| InputState::new(window, cx).masked(true));
+        let target = cx.new(|cx| InputState::new(window, cx).placeholder("host:port，例如 example.com:443"));
+        let sni = cx.new(|cx| InputState::new(window, cx).placeholder("可选，默认使用主机名"));
+        let hostname = cx.new(|cx| InputState::new(window, cx).placeholder("可选，例如 example.com"));
+        let ca_bundle = cx.new(|cx| {
+            InputState::new(window, cx)
+                .multi_line(true)
+                .placeholder("粘贴作为信任锚的 CA 证书（PEM）")
+        });
+        let subs = [&input, &password]
+            .into_iter()
+            .map(|state| cx.subscribe_in(state, window, Self::on_input_event))
+            .collect();
+
+        Self {
+            mode: CertMode::Parse,
+            target,
+            sni,
+            tls: None,
+            connecting: false,
+            input,
+            password,
+            certs: Ok(vec![]),
+            trust: TrustSource::System,
+            purpose: Purpose::Server,
+            hostname,
+            ca_bundle,
+            checks: None,
+            verifying: false,
+            _subs: subs,
+        }
+    }
+
+    fn on_input_event(
+        &mut self,
+        _state: &Entity<InputState>,
+        ev: &InputEvent,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        if let InputEvent::Change = ev {
+            self.parse(window, cx);
+        }
+    }
+
+    fn parse(
+        &mut self,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let text = self.input.read(cx).value().to_string();
+        let password = self.password.read(cx).value().to_string();
+        let source = (text.clone(), password.clone());
+        cx.spawn_in(window, async move |this, cx| {
+            let certs = cx
+                .background_executor()
+                .spawn(async move {
+                    if text.trim().is_empty() {
+                        return Ok(vec![]);
+                    }
+                    load_certs(&text, &password)?.iter().map(CertInfo::new).collect()
+                })
+                .await;
+
+            let _ = cx.update(|_window, cx| {
+                let _ = this.update(cx, |this, cx| {
+                    // 输入已经变化时丢弃过期的结果
+                    let current = (
+                        this.input.read(cx).value().to_string(),
+                        this.password.read(cx).value().to_string(),
+                    );
+                    if current == source {
+                        this.certs = certs;
+                        this.checks = None;
+                        cx.notify();
+                    }
+                });
+            });
+        })
+        .detach();
+    }
+
+    fn verify(
+        &mut self,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        if self.verifying {
+            return;
+        }
+        let text = self.input.read(cx).value().to_string();
+        if text.trim().is_empty() {
+            window.push_notification("请先输入证书", cx);
+            return;
+        }
+        let password = self.password.read(cx).value().to_string();
+        let ca_bundle = self.ca_bundle.read(cx).value().to_string();
+        let hostname = self.hostname.read(cx).value().to_string();
+        let (trust, purpose) = (self.trust, self.purpose);
+        self.verifying = true;
+        cx.notify();
+
+        cx.spawn_in(window, async move |this, cx| {
+            let checks = cx
+                .background_executor()
+                .spawn(async move {
+                    let certs = load_certs(&text, &password)?;
+                    verify_chain(&certs, trust, &ca_bundle, &hostname, purpose)
+                })
+                .await;
+
+            let _ = cx.update(|_window, cx| {
+                let _ = this.update(cx, |this, cx| {
+                    this.checks = Some(checks);
+                    this.verifying = false;
+                    cx.notify();
+                });
+            });
+        })
+        .detach();
+    }
+
+    fn connect(
+        &mut self,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        if self.connecting {
+            return;
+        }
+        let target = self.target.read(cx).value().to_string();
+        let sni = self.sni.read(cx).value().to_string();
+        self.connecting = true;
+        cx.notify();
+
+        cx.spawn_in(window, async move |this, cx| {
+            let tls = cx
+                .background_executor()
+                .spawn(async move { fetch(&target, &sni) })
+                .await;
+
+            let _ = cx.update(|window, cx| {
+                let _ = this.update(cx, |this, cx| {
+                    // 把服务器出示的证书链填入输入框，复用解析和验证功能
+                    if let Ok(info) = &tls {
+                        let pem: String = info
+                            .chain
+                            .iter()
+                            .filter_map(|cert| cert.to_pem().ok())
+                            .map(|pem| String::from_utf8_lossy(&pem).into_owned())
+                            .collect();
+                        let hostname = info.sni.clone().unwrap_or_else(|| info.host.clone());
+                        this.input.update(cx, |state, cx| state.set_value(pem, window, cx));
+                        this.hostname
+                            .update(cx, |state, cx| state.set_value(hostname, window, cx));
+                    }
+                    this.tls = Some(tls);
+                    this.connecting = false;
+                    cx.notify();
+                });
+            });
+        })
+        .detach();
+    }
+
+    fn render_input(
+        &mut self,
+        cx: &mut Context<Self>,
+    ) -> Div {
+        card()
+            .h_48()
+            .child(
+                div()
+                    .flex()
+                    .gap_4()
+                    .items_center()
+                    .child(label("证书"))
+                    .child(div().flex_1())
+                    .child(label("PKCS#12 密码"))
+                    .child(div().w_48().child(textarea(&self.password, |input| input)))
+                    .child(
+                        button(cx, "cert-open")
+                            .label("打开文件")
+                            .on_click(cx.listener(|this, _ev, window, cx| {
+                                open_file(this.input.clone(), "选择证书文件", window, cx);
+                            })),
+                    ),
+            )
+            .child(textarea(&self.input, |input| input.font_family("monospace")))
+    }
+
+    fn render_connect(
+        &mut self,
+        cx: &mut Context<Self>,
+    ) -> Div {
+        let tls = self.tls.as_ref().map(|tls| match tls {
+            Ok(info) => div()
+                .flex()
+                .flex_col()
+                .children(info.fields().into_iter().map(|(name, value)| field_row(name, value))),
+            Err(err) => div()
+                .text_sm()
+                .text_color(cx.theme().danger)
+                .child(format!("连接失败：{}", err)),
+        });
+
+        card()
+            .child(
+                div()
+                    .flex()
+                    .gap_4()
+                    .items_center()
+                    .child(label("服务器"))
+                    .child(div().w_64().child(textarea(&self.target, |input| input)))
+                    .child(label("SNI"))
+                    .child(div().w_64().child(textarea(&self.sni, |input| input)))
+                    .child(div().flex_1())
+                    .child(
+                        button(cx, "cert-connect")
+                            .label(if self.connecting { "连接中..." } else { "连接" })
+                            .on_click(cx.listener(|this, _ev, window, cx| {
+                                this.connect(window, cx);
+                            })),
+                    ),
+            )
+            .children(tls)
+    }
+
+    fn render_verify(
+        &mut self,
+        cx: &mut Context<Self>,
+    ) -> Div {
+        let checks = self.checks.as_ref().map(|checks| match checks {
+            Ok(checks) => div().flex().flex_col().children(checks.iter().map(|check| {
+                let (status, color, detail) = match &check.result {
+                    Ok(detail) => ("通过", cx.theme().success, detail.clone()),
+                    Err(detail) => ("失败", cx.theme().danger, detail.clone()),
+                };
+                div()
+                    .flex()
+                    .gap_4()
+                    .py_1()
+                    .border_b_1()
+                    .border_color(rgb(0x404040))
+                    .text_sm()
+                    .child(div().w_24().flex_none().text_color(rgb(0x808080)).child(check.name))
+                    .child(div().w_12().flex_none().text_color(color).child(status))
+                    .child(div().flex_1().min_w_0().child(detail))
+            })),
+            Err(err) => div()
+                .text_sm()
+                .text_color(cx.theme().danger)
+                .child(format!("验证失败：{}", err)),
+        });
+
+        card()
+            .child(
+                div()
+                    .flex()
+                    .gap_4()
+                    .items_center()
+                    .child(label("信任锚"))
+                    .child(
+                        options("cert-trust", &TrustSource::ALL, &self.trust).on_click(cx.listener(
+                            |this, index: &usize, _window, cx| {
+                                this.trust = TrustSource::ALL[*index];
+                                this.checks = None;
+                                cx.notify();
+                            },
+                        )),
+                    )
+                    .child(label("用途"))
+                    .child(
+                        options("cert-purpose", &Purpose::ALL, &self.purpose).on_click(cx.listener(
+                            |this, index: &usize, _window, cx| {
+                                this.purpose = Purpose::ALL[*index];
+                                this.checks = None;
+                                cx.notify();
+                            },
+                        )),
+                    )
+                    .child(label("主机名"))
+                    .child(div().w_64().child(textarea(&self.hostname, |input| input)))
+                    .child(div().flex_1())
+                    .child(
+                        button(cx, "cert-verify")
+                            .label(if self.verifying { "验证中..." } else { "验证" })
+                            .on_click(cx.listener(|this, _ev, window, cx| {
+                                this.verify(window, cx);
+                            })),
+                    ),
+            )
+            .children((self.trust == TrustSource::Custom).then(|| {
+                div()
+                    .h_24()
+                    .flex()
+                    .child(textarea(&self.ca_bundle, |input| input.font_family("monospace")))
+            }))
+            .children(checks)
+    }
+
+    fn render_cert(
+        i: usize,
+        info: &CertInfo,
+        cx: &mut Context<Self>,
+    ) -> Div {
+        let pem = info.pem.clone();
+        let header = div()
+            .flex()
+            .gap_4()
+            .items_center()
+            .child(label(format!("#{} {}", i + 1, info.title())))
+            .child(
+                div()
+                    .text_sm()
+                    .text_color(match info.status {
+                        Validity::NotYetValid => cx.theme().warning,
+                        Validity::Valid => cx.theme().success,
+                        Validity::Expired => cx.theme().danger,
+                    })
+                    .child(info.validity.clone()),
+            )
+            .child(div().flex_1())
+            .child(button(cx, ("cert-copy-pem", i)).label("复制 PEM").on_click(cx.listener(
+                move |_this, _ev, window, cx| {
+                    cx.write_to_clipboard(ClipboardItem::new_string(pem.clone()));
+                    window.push_notification("已复制到剪贴板", cx);
+                },
+            )));
+
+        card()
+            .child(header)
+            .children(info.fields().into_iter().map(|(name, value)| field_row(name, value)))
+    }
+}
+
+impl Render for InspectPanel {
+    fn render(
+        &mut self,
+        _window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> impl IntoElement {
+        let verify = self.render_verify(cx);
+        let result = match &self.certs {
+            Ok(certs) => div()
+                .id("cert-chain")
+                .flex_1()
+                .min_h_0()
+                .overflow_y_scroll()
+                .flex()
+                .flex_col()
+                .gap_4()
+                .children(certs.iter().enumerate().map(|(i, info)| Self::render_cert(i, info, cx))),
+            Err(err) => div().id("cert-chain").child(
+                div()
+                    .px_4()
+                    .py_2()
+                    .rounded_lg()
+                    .bg(cx.theme().danger)
+                    .text_sm()
+                    .text_color(cx.theme().danger_foreground)
+                    .child(format!("解析失败：{}", err)),
+            ),
+        };
+
+        let source = match self.mode {
+            CertMode::Parse => self.render_input(cx),
+            CertMode::Connect => self.render_connect(cx),
+        };
+
+        div()
+            .flex_1()
+            .flex()
+            .flex_col()
+            .gap_4()
+            .min_h_0()
+            .child(
+                card().child(div().flex().gap_4().items_center().child(label("来源")).child(
+                    options("cert-mode", &CertMode::ALL, &self.mode).on_click(cx.listener(
+                        |this, index: &usize, _window, cx| {
+                            this.mode = CertMode::ALL[*index];
+                            cx.notify();
+                        },
+                    )),
+                )),
+            )
+            .child(source)
+            .child(verify)
+            .child(result)
+    }
+}
@@ -0,0 +1,938 @@
+use std::{fmt, fs, net::IpAddr};
+
+use gpui::*;
+use gpui_component::{WindowExt, input::InputState};
+use openssl::{
+    asn1::{Asn1Integer, Asn1Time},
+    bn::{BigNum, MsbOption},
+    error::ErrorStack,
+    hash::MessageDigest,
+    nid::Nid,
+    pkcs12::Pkcs12,
+    pkey::{Id, PKey, Private},
+    stack::Stack,
+    x509::{
+        X509, X509Builder, X509Extension, X509Name, X509NameBuilder, X509NameRef, X509v3Context,
+        extension::{
+            AuthorityKeyIdentifier, BasicConstraints, ExtendedKeyUsage, KeyUsage, SubjectAlternativeName,
+            SubjectKeyIdentifier,
+        },
+    },
+};
+
+use x509_parser::{certificate::X509Certificate, prelude::FromDer};
+
+use super::{file_stem, options, parse::load_certs};
+use crate::{
+    comps::{button, card, label, textarea},
+    pages::devel::crypto::asymmetric::{KeyType, parse_private},
+};
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum CertKind {
+    Ca,
+    Leaf,
+}
+
+impl CertKind {
+    pub const ALL: [CertKind; 2] = [CertKind::Ca, CertKind::Leaf];
+
+    fn default_days(self) -> u32 {
+        match self {
+            CertKind::Ca => 3650,
+            CertKind::Leaf => 365,
+        }
+    }
+}
+
+impl fmt::Display for CertKind {
+    fn fmt(
+        &self,
+        f: &mut fmt::Formatter<'_>,
+    ) -> fmt::Result {
+        let name = match self {
+            CertKind::Ca => "CA 证书",
+            CertKind::Leaf => "终端证书",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Signer {
+    SelfSigned,
+    Ca,
+}
+
+impl Signer {
+    pub const ALL: [Signer; 2] = [Signer::SelfSigned, Signer::Ca];
+}
+
+impl fmt::Display for Signer {
+    fn fmt(
+        &self,
+        f: &mut fmt::Formatter<'_>,
+    ) -> fmt::Result {
+        let name = match self {
+            Signer::SelfSigned => "自签名",
+            Signer::Ca => "由 CA 签发",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+/// 证书主题，除通用名称外都可以为空
+#[derive(Default)]
+pub struct Subject {
+    pub common_name: String,
+    pub organization: String,
+    pub organizational_unit: String,
+    pub locality: String,
+    pub province: String,
+    pub country: String,
+}
+
+impl Subject {
+    pub fn name(&self) -> Result<X509Name, String> {
+        if self.common_name.trim().is_empty() {
+            return Err("通用名称（CN）不能为空".to_string());
+        }
+        let country = self.country.trim();
+        if !country.is_empty() && (country.len() != 2 || !country.chars().all(|c| c.is_ascii_alphabetic())) {
+            return Err("国家代码应为两位字母，例如 CN".to_string());
+        }
+
+        let entries = [
+            (Nid::COUNTRYNAME, country.to_uppercase()),
+            (Nid::STATEORPROVINCENAME, self.province.trim().to_string()),
+            (Nid::LOCALITYNAME, self.locality.trim().to_string()),
+            (Nid::ORGANIZATIONNAME, self.organization.trim().to_string()),
+            (Nid::ORGANIZATIONALUNITNAME, self.organizational_unit.trim().to_string()),
+            (Nid::COMMONNAME, self.common_name.trim().to_string()),
+        ];
+        let mut name = X509NameBuilder::new().map_err(|e| e.to_string())?;
+        for (nid, value) in entries.iter().filter(|(_, value)| !value.is_empty()) {
+            name.append_entry_by_nid(*nid, value)
+                .map_err(|e| format!("主题字段格式错误: {}", e))?;
+        }
+        Ok(name.build())
+    }
+}
+
+/// 签发者的证书链（第一个为签发证书）和私钥
+pub struct Issuer {
+    pub chain: Vec<X509>,
+    pub key: PKey<Private>,
+}
+
+impl Issuer {
+    pub fn parse(
+        cert: &str,
+        key: &str,
+    ) -> Result<Issuer, String> {
+        if cert.trim().is_empty() || key.trim().is_empty() {
+            return Err("请填写 CA 证书和私钥".to_string());
+        }
+        let chain = load_certs(cert, "").map_err(|e| format!("CA 证书{}", e))?;
+        let key = parse_private(key)?;
+        let matched = chain[0]
+            .public_key()
+            .map(|public| public.public_eq(&key))
+            .unwrap_or(false);
+        if !matched {
+            return Err("CA 私钥与证书不匹配".to_string());
+        }
+
+        // 签发者必须是 CA，且没有限制为不能签发证书
+        let der = chain[0].to_der().map_err(|e| e.to_string())?;
+        let (_, parsed) = X509Certificate::from_der(&der).map_err(|e| format!("无法解析 CA 证书: {}", e))?;
+        if !parsed.basic_constraints().ok().flatten().is_some_and(|bc| bc.value.ca) {
+            return Err("签发证书的基本约束不是 CA，不能用于签发".to_string());
+        }
+        if parsed
+            .key_usage()
+            .ok()
+            .flatten()
+            .is_some_and(|ku| !ku.value.key_cert_sign())
+        {
+            return Err("签发证书的密钥用途不包含证书签名（keyCertSign）".to_string());
+        }
+        Ok(Issuer { chain, key })
+    }
+}
+
+/// 签发结果，`chain` 为签发者的证书链，自签名时为空
+pub struct Issued {
+    pub key: PKey<Private>,
+    pub cert: X509,
+    pub chain: Vec<X509>,
+}
+
+impl Issued {
+    pub fn key_pem(&self) -> Result<String, String> {
+        self.key
+            .private_key_to_pem_pkcs8()
+            .map(|pem| String::from_utf8_lossy(&pem).into_owned())
+            .map_err(|e| format!("导出私钥失败: {}", e))
+    }
+
+    pub fn cert_pem(&self) -> Result<String, String> {
+        to_pem(&self.cert)
+    }
+
+    /// 证书和签发者证书链，可直接用于 Web 服务器配置
+    pub fn chain_pem(&self) -> Result<String, String> {
+        std::iter::once(&self.cert).chain(&self.chain).map(to_pem).collect()
+    }
+
+    pub fn pkcs12(
+        &self,
+        password: &str,
+    ) -> Result<Vec<u8>, String> {
+        let mut ca = Stack::new().map_err(|e| e.to_string())?;
+        for cert in &self.chain {
+            ca.push(cert.clone()).map_err(|e| e.to_string())?;
+        }
+        let name = self
+            .cert
+            .subject_name()
+            .entries_by_nid(Nid::COMMONNAME)
+            .next()
+            .and_then(|entry| entry.data().as_utf8().ok())
+            .map(|name| name.to_string())
+            .unwrap_or_default();
+        Pkcs12::builder()
+            .name(&name)
+            .pkey(&self.key)
+            .cert(&self.cert)
+            .ca(ca)
+            .build2(password)
+            .and_then(|pkcs12| pkcs12.to_der())
+            .map_err(|e| format!("导出 PKCS#12 失败: {}", e))
+    }
+}
+
+/// 解析备用名称，以逗号或换行分隔，IP 地址之外的都按 DNS 名称处理
+pub fn parse_sans(text: &str) -> Result<Vec<String>, String> {
+    text.split([',', '\n'])
+        .map(str::trim)
+        .filter(|name| !name.is_empty())
+        .map(|name| {
+            if name.parse::<IpAddr>().is_ok() || is_dns_name(name) {
+                Ok(name.to_string())
+            } else {
+                Err(format!("无效的备用名称: {}", name))
+            }
+        })
+        .collect()
+}
+
+/// 检查 DNS 名称的格式，通配符只能作为最左侧的完整标签，例如 `*.example.com`
+fn is_dns_name(name: &str) -> bool {
+    let labels = name.strip_prefix("*.").unwrap_or(name);
+    name.len() <= 253
+        && labels.split('.').all(|label| {
+            !label.is_empty()
+                && label.len() <= 63
+                && !label.starts_with('-')
+                && !label.ends_with('-')
+                && label.chars().all(|c| c.is_ascii_alphanumeric() || c == '-')
+        })
+}
+
+/// 生成密钥并签发证书，未提供签发者时为自签名
+pub fn issue(
+    kind: CertKind,
+    key_type: KeyType,
+    subject: &Subject,
+    sans: &[String],
+    days: u32,
+    issuer: Option<&Issuer>,
+) -> Result<Issued, String> {
+    let name = subject.name()?;
+    if days == 0 {
+        return Err("有效期必须大于 0 天".to_string());
+    }
+
+    let key = key_type.generate()?;
+    let cert =
+        build_cert(kind, &key, &name, subject, sans, days, issuer).map_err(|e| format!("签发证书失败: {}", e))?;
+    Ok(Issued {
+        key,
+        cert,
+        chain: issuer.map(|issuer| issuer.chain.clone()).unwrap_or_default(),
+    })
+}
+
+fn build_cert(
+    kind: CertKind,
+    key: &PKey<Private>,
+    name: &X509NameRef,
+    subject: &Subject,
+    sans: &[String],
+    days: u32,
+    issuer: Option<&Issuer>,
+) -> Result<X509, ErrorStack> {
+    // 序列号使用 159 位随机数，保证为正数且不超过 20 字节
+    let mut serial = BigNum::new()?;
+    serial.rand(159, MsbOption::MAYBE_ZERO, false)?;
+
+    let mut builder = X509Builder::new()?;
+    builder.set_version(2)?;
+    let serial = Asn1Integer::from_bn(&serial)?;
+    builder.set_serial_number(&serial)?;
+    builder.set_subject_name(name)?;
+    match issuer {
+        Some(issuer) => builder.set_issuer_name(issuer.chain[0].subject_name())?,
+        None => builder.set_issuer_name(name)?,
+    }
+    let not_before = Asn1Time::days_from_now(0)?;
+    let not_after = Asn1Time::days_from_now(days)?;
+    builder.set_not_before(&not_before)?;
+    // 签发的证书不能晚于签发者过期
+    match issuer.map(|issuer| issuer.chain[0].not_after()) {
+        Some(limit) if not_after > limit => builder.set_not_after(limit)?,
+        _ => builder.set_not_after(&not_after)?,
+    }
+    builder.set_pubkey(key)?;
+
+    match kind {
+        CertKind::Ca => {
+            builder.append_extension(BasicConstraints::new().critical().ca().build()?)?;
+            builder.append_extension(
+                KeyUsage::new()
+                    .critical()
+                    .key_cert_sign()
+                    .crl_sign()
+                    .digital_signature()
+                    .build()?,
+            )?;
+        }
+        CertKind::Leaf => {
+            builder.append_extension(BasicConstraints::new().critical().build()?)?;
+            let mut usage = KeyUsage::new();
+            usage.critical().digital_signature();
+            // 只有 RSA 密钥可以用于密钥交换
+            if key.id() == Id::RSA {
+                usage.key_encipherment();
+            }
+            builder.append_extension(usage.build()?)?;
+            builder.append_extension(ExtendedKeyUsage::new().server_auth().client_auth().build()?)?;
+        }
+    }
+
+    // 未填写备用名称时，终端证书使用通用名称作为 DNS 名称
+    let sans = match (kind, sans.is_empty()) {
+        (CertKind::Leaf, true) => vec![subject.common_name.trim().to_string()],
+        _ => sans.to_vec(),
+    };
+    let context = builder.x509v3_context(issuer.map(|issuer| &*issuer.chain[0]), None);
+    let subject_key_id = SubjectKeyIdentifier::new().build(&context)?;
+    let authority_key_id = match issuer {
+        Some(_) => Some(AuthorityKeyIdentifier::new().keyid(false).build(&context)?),
+        None => None,
+    };
+    let alt_names = san_extension(&sans, &context)?;
+    builder.append_extension(subject_key_id)?;
+    if let Some(authority_key_id) = authority_key_id {
+        builder.append_extension(authority_key_id)?;
+    }
+    if let Some(alt_names) = alt_names {
+        builder.append_extension(alt_names)?;
+    }
+
+    let signing_key = issuer.map(|issuer| &issuer.key).unwrap_or(key);
+    builder.sign(signing_key, digest_for(signing_key))?;
+    Ok(builder.build())
+}
+
+/// 生成备用名称扩展，没有备用名称时返回 `None`
+pub fn san_extension(
+    sans: &[String],
+    context: &X509v3Context,
+) -> Result<Option<X509Extension>, ErrorStack> {
+    if sans.is_empty() {
+        return Ok(None);
+    }
+    let mut alt_names = SubjectAlternativeName::new();
+    for san in sans {
+        if san.parse::<IpAddr>().is_ok() {
+            alt_names.ip(san);
+        } else {
+            alt_names.dns(san);
+        }
+    }
+    alt_names.build(context).map(Some)
+}
+
+/// 签名摘要，Ed25519 不使用单独的摘要算法
+pub fn digest_for(key: &PKey<Private>) -> MessageDigest {
+    match key.id() {
+        Id::ED25519 => MessageDigest::null(),
+        Id::EC if key.bits() > 256 => MessageDigest::sha384(),
+        _ => MessageDigest::sha256(),
+    }
+}
+
+fn to_pem(cert: &X509) -> Result<String, String> {
+    cert.to_pem()
+        .map(|pem| String::from_utf8_lossy(&pem).into_owned())
+        .map_err(|e| format!("导出证书失败: {}", e))
+}
+
+pub struct IssuePanel {
+    kind: CertKind,
+    signer: Signer,
+    key_type: KeyType,
+    generating: bool,
+    issued: Option<Issued>,
+    common_name: Entity<InputState>,
+    organization: Entity<InputState>,
+    organizational_unit: Entity<InputState>,
+    locality: Entity<InputState>,
+    province: Entity<InputState>,
+    country: Entity<InputState>,
+    days: Entity<InputState>,
+    sans: Entity<InputState>,
+    ca_cert: Entity<InputState>,
+    ca_key: Entity<InputState>,
+    password: Entity<InputState>,
+    key_output: Entity<InputState>,
+    cert_output: Entity<InputState>,
+}
+
+impl IssuePanel {
+    pub fn new(
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Self {
+        let kind = CertKind::Ca;
+        let common_name = cx.new(|cx| InputState::new(window, cx).placeholder("例如 Local Dev CA"));
+        let organization = cx.new(|cx| InputState::new(window, cx));
+        let organizational_unit = cx.new(|cx| InputState::new(window, cx));
+        let locality = cx.new(|cx| InputState::new(window, cx));
+        let province = cx.new(|cx| InputState::new(window, cx));
+        let country = cx.new(|cx| InputState::new(window, cx).placeholder("CN"));
+        let days = cx.new(|cx| {
+            let mut state = InputState::new(window, cx);
+            state.set_value(kind.default_days().to_string(), window, cx);
+            state
+        });
+        let sans = cx.new(|cx| {
+            InputState::new(window, cx).placeholder("以逗号或换行分隔，例如 example.com, *.example.com, 127.0.0.1")
+        });
+        let ca_cert = cx.new(|cx| {
+            InputState::new(window, cx)
+                .multi_line(true)
+                .placeholder("CA 证书（PEM），生成 CA 后自动填入")
+        });
+        let ca_key = cx.new(|cx| {
+            InputState::new(window, cx)
+                .multi_line(true)
+                .placeholder("CA 私钥（PEM）")
+        });
+        let password = cx.new(|cx| InputState::new(window, cx).masked(true));
+        let key_output = cx.new(|cx| InputState::new(window, cx).multi_line(true));
+        let cert_output = cx.new(|cx| InputState::new(window, cx).multi_line(true));
+
+        Self {
+            kind,
+            signer: Signer::SelfSigned,
+            key_type: KeyType::P256,
+            generating: false,
+            issued: None,
+            common_name,
+            organization,
+            organizational_unit,
+            locality,
+            province,
+            country,
+            days,
+            sans,
+            ca_cert,
+            ca_key,
+            password,
+            key_output,
+            cert_output,
+        }
+    }
+
+    fn generate(
+        &mut self,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        if self.generating {
+            return;
+        }
+        let days = match self.days.read(cx).value().trim().parse::<u32>() {
+            Ok(days) => days,
+            Err(_) => {
+                window.push_notification("有效期必须是正整数", cx);
+                return;
+            }
+        };
+        let sans = match self.kind {
+            CertKind::Ca => Ok(vec![]),
+            CertKind::Leaf => parse_sans(&self.sans.read(cx).value()),
+        };
+        let sans = match sans {
+            Ok(sans) => sans,
+            Err(e) => {
+                window.push_notification(e, cx);
+                return;
+            }
+        };
+        let subject = Subject {
+            common_name: self.common_name.read(cx).value().to_string(),
+            organization: self.organization.read(cx).value().to_string(),
+            organizational_unit: self.organizational_unit.read(cx).value().to_string(),
+            locality: self.locality.read(cx).value().to_string(),
+            province: self.province.read(cx).value().to_string(),
+            country: self.country.read(cx).value().to_string(),
+        };
+        let ca = match self.signer {
+            Signer::SelfSigned => None,
+            Signer::Ca => Some((
+                self.ca_cert.read(cx).value().to_string(),
+                self.ca_key.read(cx).value().to_string(),
+            )),
+        };
+        let (kind, key_type) = (self.kind, self.key_type);
+        self.generating = true;
+        cx.notify();
+
+        cx.spawn_in(window, async move |this, cx| {
+            // RSA 4096 生成较慢，放到后台执行
+            let result = cx
+                .background_executor()
+                .spawn(async move {
+                    let issuer = match ca {
+                        Some((cert, key)) => Some(Issuer::parse(&cert, &key)?),
+                        None => None,
+                    };
+                    let issued = issue(kind, key_type, &subject, &sans, days, issuer.as_ref())?;
+                    let key = issued.key_pem()?;
+                    let chain = issued.chain_pem()?;
+                    Ok::<_, String>((issued, key, chain))
+                })
+                .await;
+
+            let _ = cx.update(|window, cx| {
+                let _ = this.update(cx, |this, cx| {
+                    this.generating = false;
+                    match result {
+                        Ok((issued, key, chain)) => {
+                            this.key_output
+                                .update(cx, |state, cx| state.set_value(key.clone(), window, cx));
+                            this.cert_output
+                                .update(cx, |state, cx| state.set_value(chain.clone(), window, cx));
+                            // 新生成的 CA 直接作为后续签发的 CA
+                            if kind == CertKind::Ca {
+                                this.ca_cert.update(cx, |state, cx| state.set_value(chain, window, cx));
+                                this.ca_key.update(cx, |state, cx| state.set_value(key, window, cx));
+                                window.push_notification("CA 已生成，并已填入签发 CA", cx);
+                            }
+                            this.issued = Some(issued);
+                        }
+                        Err(e) => window.push_notification(e, cx),
+                    }
+                    cx.notify();
+                });
+            });
+        })
+        .detach();
+    }
+
+    fn export(
+        &mut self,
+        export: Export,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let Some(issued) = &self.issued else {
+            window.push_notification("请先生成证书", cx);
+            return;
+        };
+        let data = match export {
+            Export::Key => issued.key_pem().map(String::into_bytes),
+            Export::Cert => issued.cert_pem().map(String::into_bytes),
+            Export::Chain => issued.chain_pem().map(String::into_bytes),
+            Export::Pkcs12 => issued.pkcs12(&self.password.read(cx).value()),
+        };
+        let data = match data {
+            Ok(data) => data,
+            Err(e) => {
+                window.push_notification(e, cx);
+                return;
+            }
+        };
+
+        let stem = file_stem(&self.common_name.read(cx).value());
+        let name = match export {
+            Export::Key => format!("{}.key", stem),
+            Export::Cert => format!("{}.crt", stem),
+            Export::Chain => format!("{}-chain.pem", stem),
+            Export::Pkcs12 => format!("{}.p12", stem),
+        };
+        let directory = std::env::home_dir().unwrap_or_default();
+        let rx = cx.prompt_for_new_path(&directory, Some(&name));
+        cx.spawn_in(window, async move |_this, cx| {
+            if let Ok(Ok(Some(path))) = rx.await {
+                let message = match fs::write(&path, data) {
+                    Ok(_) => format!("已保存到 {}", path.display()),
+                    Err(e) => format!("保存失败: {}", e),
+                };
+                let _ = cx.update(|window, cx| {
+                    window.push_notification(message, cx);
+                });
+            }
+        })
+        .detach();
+    }
+
+    fn render_form(
+        &mut self,
+        cx: &mut Context<Self>,
+    ) -> Div {
+        card()
+            .child(
+                div()
+                    .flex()
+                    .gap_4()
+                    .items_center()
+                    .child(label("类型"))
+                    .child(
+                        options("cert-issue-kind", &CertKind::ALL, &self.kind).on_click(cx.listener(
+                            |this, index: &usize, window, cx| {
+                                this.kind = CertKind::ALL[*index];
+                                let days = this.kind.default_days().to_string();
+                                this.days.update(cx, |state, cx| state.set_value(days, window, cx));
+                                cx.notify();
+                            },
+                        )),
+                    )
+                    .child(label("签发者"))
+                    .child(
+                        options("cert-issue-signer", &Signer::ALL, &self.signer).on_click(cx.listener(
+                            |this, index: &usize, _window, cx| {
+                                this.signer = Signer::ALL[*index];
+                                cx.notify();
+                            },
+                        )),
+                    ),
+            )
+            .child(div().flex().gap_4().items_center().child(label("密钥类型")).child(
+                options("cert-issue-key-type", &KeyType::ALL, &self.key_type).on_click(cx.listener(
+                    |this, index: &usize, _window, cx| {
+                        this.key_type = KeyType::ALL[*index];
+                        cx.notify();
+                    },
+                )),
+            ))
+            .child(
+                div()
+                    .flex()
+                    .gap_4()
+                    .items_center()
+                    .child(label("通用名称"))
+                    .child(textarea(&self.common_name, |input| input))
+                    .child(label("组织"))
+                    .child(textarea(&self.organization, |input| input))
+                    .child(label("部门"))
+                    .child(textarea(&self.organizational_unit, |input| input)),
+            )
+            .child(
+                div()
+                    .flex()
+                    .gap_4()
+                    .items_center()
+                    .child(label("城市"))
+                    .child(textarea(&self.locality, |input| input))
+                    .child(label("省份"))
+                    .child(textarea(&self.province, |input| input))
+                    .child(label("国家"))
+                    .child(div().w_16().child(textarea(&self.country, |input| input)))
+                    .child(label("有效期（天）"))
+                    .child(div().w_24().child(textarea(&self.days, |input| input))),
+            )
+            .children((self.kind == CertKind::Leaf).then(|| {
+                div()
+                    .flex()
+                    .gap_4()
+                    .items_center()
+                    .child(label("备用名称"))
+                    .child(textarea(&self.sans, |input| input))
+            }))
+            .children((self.signer == Signer::Ca).then(|| {
+                div()
+                    .h_24()
+                    .flex()
+                    .gap_4()
+                    .child(textarea(&self.ca_cert, |input| input.font_family("monospace")))
+                    .child(textarea(&self.ca_key, |input| input.font_family("monospace")))
+            }))
+            .child(
+                div().flex().gap_4().items_center().child(div().flex_1()).child(
+                    button(cx, "cert-issue-generate")
+                        .label(if self.generating { "生成中..." } else { "生成" })
+                        .on_click(cx.listener(|this, _ev, window, cx| {
+                            this.generate(window, cx);
+                        })),
+                ),
+            )
+    }
+
+    fn render_output(
+        &mut self,
+        cx: &mut Context<Self>,
+    ) -> Div {
+        let exports = Export::ALL.iter().map(|export| {
+            let export = *export;
+            button(cx, ("cert-issue-export", export as usize))
+                .label(export.to_string())
+                .on_click(cx.listener(move |this, _ev, window, cx| {
+                    this.export(export, window, cx);
+                }))
+        });
+
+        card()
+            .flex_1()
+            .child(
+                div()
+                    .flex()
+                    .gap_4()
+                    .items_center()
+                    .child(label("私钥 / 证书链"))
+                    .child(div().flex_1())
+                    .child(label("PKCS#12 密码"))
+                    .child(div().w_48().child(textarea(&self.password, |input| input)))
+                    .children(exports.collect::<Vec<_>>()),
+            )
+            .child(
+                div()
+                    .flex_1()
+                    .flex()
+                    .gap_4()
+                    .min_h_0()
+                    .child(textarea(&self.key_output, |input| input.font_family("monospace")))
+                    .child(textarea(&self.cert_output, |input| input.font_family("monospace"))),
+            )
+    }
+}
+
+/// 导出的文件类型
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Export {
+    Key,
+    Cert,
+    Chain,
+    Pkcs12,
+}
+
+impl Export {
+    const ALL: [Export; 4] = [Export::Key, Export::Cert, Export::Chain, Export::Pkcs12];
+}
+
+impl fmt::Display for Export {
+    fn fmt(
+        &self,
+        f: &mut fmt::Formatter<'_>,
+    ) -> fmt::Result {
+        let name = match self {
+            Export::Key => "保存私钥",
+            Export::Cert => "保存证书",
+            Export::Chain => "保存证书链",
+            Export::Pkcs12 => "导出 PKCS#12",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+impl Render for IssuePanel {
+    fn render(
+        &mut self,
+        _window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> impl IntoElement {
+        div()
+            .flex_1()
+            .flex()
+            .flex_col()
+            .gap_4()
+            .min_h_0()
+            .child(self.render_form(cx))
+            .child(self.render_output(cx))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use base64::{Engine, engine::general_purpose};
+    use openssl::{asn1::Asn1Time, nid::Nid, x509::X509};
+
+    use super::{CertKind, Issued, Issuer, Subject, issue, parse_sans};
+    use crate::pages::devel::{
+        cert::{
+            parse::load_certs,
+            testing::{key, now, self_signed},
+            verify::{Purpose, TrustSource, verify_chain},
+        },
+        crypto::asymmetric::KeyType,
+    };
+
+    fn subject(cn: &str) -> Subject {
+        Subject {
+            common_name: cn.to_string(),
+            organization: "Sidecar".to_string(),
+            organizational_unit: "Dev".to_string(),
+            locality: "Hangzhou".to_string(),
+            province: "Zhejiang".to_string(),
+            country: "cn".to_string(),
+        }
+    }
+
+    fn pem(cert: &X509) -> String {
+        String::from_utf8(cert.to_pem().unwrap()).unwrap()
+    }
+
+    /// 生成 CA 并用它签发一张包含 DNS 和 IP 备用名称的终端证书
+    fn ca_and_leaf() -> (Issued, Issued) {
+        let ca = issue(
+            CertKind::Ca,
+            KeyType::P256,
+            &subject("Sidecar Test CA"),
+            &[],
+            3650,
+            None,
+        )
+        .unwrap();
+        let issuer = Issuer::parse(&ca.chain_pem().unwrap(), &ca.key_pem().unwrap()).unwrap();
+        let sans = parse_sans("localhost, 127.0.0.1\n::1").unwrap();
+        let leaf = issue(
+            CertKind::Leaf,
+            KeyType::Rsa2048,
+            &subject("localhost"),
+            &sans,
+            365,
+            Some(&issuer),
+        )
+        .unwrap();
+        (ca, leaf)
+    }
+
+    #[test]
+    fn issued_leaf_passes_every_check() {
+        let (ca, leaf) = ca_and_leaf();
+        let ca_pem = ca.cert_pem().unwrap();
+        for hostname in ["127.0.0.1", "::1", "localhost"] {
+            let checks = verify_chain(
+                std::slice::from_ref(&leaf.cert),
+                TrustSource::Custom,
+                &ca_pem,
+                hostname,
+                Purpose::Server,
+            )
+            .unwrap();
+            assert_eq!(checks.len(), 4);
+            for check in checks {
+                assert!(check.result.is_ok(), "{} {}: {:?}", hostname, check.name, check.result);
+            }
+        }
+
+        let subject = leaf.cert.subject_name();
+        let entry = |nid| {
+            subject
+                .entries_by_nid(nid)
+                .next()
+                .map(|entry| entry.data().as_utf8().unwrap().to_string())
+        };
+        assert_eq!(entry(Nid::COMMONNAME).as_deref(), Some("localhost"));
+        assert_eq!(entry(Nid::ORGANIZATIONALUNITNAME).as_deref(), Some("Dev"));
+        assert_eq!(entry(Nid::LOCALITYNAME).as_deref(), Some("Hangzhou"));
+        assert_eq!(entry(Nid::STATEORPROVINCENAME).as_deref(), Some("Zhejiang"));
+        assert_eq!(entry(Nid::COUNTRYNAME).as_deref(), Some("CN"));
+    }
+
+    #[test]
+    fn chain_pem_lists_leaf_then_issuer() {
+        let (ca, leaf) = ca_and_leaf();
+        let chain = load_certs(&leaf.chain_pem().unwrap(), "").unwrap();
+        assert_eq!(chain.len(), 2);
+        assert_eq!(chain[0].to_der().unwrap(), leaf.cert.to_der().unwrap());
+        assert_eq!(chain[1].to_der().unwrap(), ca.cert.to_der().unwrap());
+        // 自签名证书没有签发者链
+        assert_eq!(ca.chain_pem().unwrap(), ca.cert_pem().unwrap());
+    }
+
+    #[test]
+    fn pkcs12_round_trip() {
+        let (ca, leaf) = ca_and_leaf();
+        let der = leaf.pkcs12("secret").unwrap();
+        let text = general_purpose::STANDARD.encode(der);
+        let certs = load_certs(&text, "secret").unwrap();
+        assert_eq!(certs.len(), 2);
+        assert_eq!(certs[0].to_der().unwrap(), leaf.cert.to_der().unwrap());
+        assert_eq!(certs[1].to_der().unwrap(), ca.cert.to_der().unwrap());
+        assert!(load_certs(&text, "wrong").is_err());
+    }
+
+    #[test]
+    fn issuer_must_be_a_ca() {
+        let (_, leaf) = ca_and_leaf();
+        let error = Issuer::parse(&leaf.cert_pem().unwrap(), &leaf.key_pem().unwrap())
+            .err()
+            .unwrap();
+        assert!(error.contains("基本约束"), "{}", error);
+
+        let key = key();
+        let plain = self_signed("plain", &key, now() - 60, now() + 3600);
+        let key_pem = String::from_utf8(key.private_key_to_pem_pkcs8().unwrap()).unwrap();
+        assert!(Issuer::parse(&pem(&plain), &key_pem).is_err());
+
+        let (ca, _) = ca_and_leaf();
+        let error = Issuer::parse(&ca.cert_pem().unwrap(), &key_pem).err().unwrap();
+        assert_eq!(error, "CA 私钥与证书不匹配");
+    }
+
+    #[test]
+    fn leaf_expires_with_issuer() {
+        let ca = issue(CertKind::Ca, KeyType::P256, &subject("Short CA"), &[], 30, None).unwrap();
+        let issuer = Issuer::parse(&ca.cert_pem().unwrap(), &ca.key_pem().unwrap()).unwrap();
+        let leaf = issue(
+            CertKind::Leaf,
+            KeyType::P256,
+            &subject("leaf.test"),
+            &[],
+            365,
+            Some(&issuer),
+        )
+        .unwrap();
+        assert_eq!(
+            leaf.cert.not_after().compare(ca.cert.not_after()).unwrap(),
+            std::cmp::Ordering::Equal
+        );
+
+        let short = issue(
+            CertKind::Leaf,
+            KeyType::P256,
+            &subject("leaf.test"),
+            &[],
+            7,
+            Some(&issuer),
+        )
+        .unwrap();
+        assert!(short.cert.not_after() < Asn1Time::days_from_now(8).unwrap());
+    }
+
+    #[test]
+    fn parse_sans_validates_names() {
+        assert_eq!(
+            parse_sans(" example.com,*.example.com\n127.0.0.1 ,, ::1 ").unwrap(),
+            ["example.com", "*.example.com", "127.0.0.1", "::1"]
+        );
+        for invalid in ["a b", "foo_bar", "*", "a.*.com", "-a.com", "a..com", "例子.com"] {
+            assert!(parse_sans(invalid).is_err(), "{}", invalid);
+        }
+    }
+}
@@ -0,0 +1,439 @@
+use std::net::IpAddr;
+
+use base64::{Engine, engine::general_purpose};
+use chrono::{DateTime, Local};
+use openssl::{
+    asn1::{Asn1Time, Asn1TimeRef},
+    hash::MessageDigest,
+    nid::Nid,
+    pkcs7::Pkcs7,
+    pkcs12::Pkcs12,
+    pkey::{HasPublic, Id, PKeyRef},
+    x509::{X509, X509NameRef},
+};
+use x509_parser::{
+    certificate::X509Certificate,
+    extensions::{GeneralName, ParsedExtension, X509Extension},
+    objects::{oid_registry, oid2sn},
+    prelude::FromDer,
+};
+
+use crate::pages::utils::strip_str;
+
+/// 读取证书链，支持 PEM（可包含多个证书或 PKCS#7）、Base64 编码的 DER、PKCS#7 和 PKCS#12
+pub fn load_certs(
+    text: &str,
+    password: &str,
+) -> Result<Vec<X509>, String> {
+    let text = text.trim();
+    if text.contains("-----BEGIN PKCS7") {
+        let pkcs7 = Pkcs7::from_pem(text.as_bytes()).map_err(|e| format!("PKCS#7 解析失败: {}", e))?;
+        return pkcs7_certs(&pkcs7);
+    }
+    if text.contains("-----BEGIN") {
+        let certs = X509::stack_from_pem(text.as_bytes()).map_err(|e| format!("PEM 解析失败: {}", e))?;
+        if certs.is_empty() {
+            return Err("PEM 中没有证书".to_string());
+        }
+        return Ok(certs);
+    }
+
+    let der = general_purpose::STANDARD
+        .decode(strip_str(text))
+        .map_err(|_| "无法识别的格式，请输入 PEM 或 Base64 编码的 DER 内容".to_string())?;
+    load_der(&der, password)
+}
+
+fn load_der(
+    der: &[u8],
+    password: &str,
+) -> Result<Vec<X509>, String> {
+    if let Ok(cert) = X509::from_der(der) {
+        return Ok(vec![cert]);
+    }
+    if let Ok(pkcs7) = Pkcs7::from_der(der) {
+        return pkcs7_certs(&pkcs7);
+    }
+    let pkcs12 = Pkcs12::from_der(der).map_err(|_| "无法识别的格式，支持 X.509、PKCS#7 和 PKCS#12".to_string())?;
+    let parsed = pkcs12
+        .parse2(password)
+        .map_err(|_| "PKCS#12 解析失败，请检查密码".to_string())?;
+    let certs: Vec<X509> = parsed.cert.into_iter().chain(parsed.ca.into_iter().flatten()).collect();
+    if certs.is_empty() {
+        return Err("PKCS#12 中没有证书".to_string());
+    }
+    Ok(certs)
+}
+
+fn pkcs7_certs(pkcs7: &Pkcs7) -> Result<Vec<X509>, String> {
+    let certs: Vec<X509> = pkcs7
+        .signed()
+        .and_then(|signed| signed.certificates())
+        .map(|certs| certs.iter().map(|cert| cert.to_owned()).collect())
+        .unwrap_or_default();
+    if certs.is_empty() {
+        return Err("PKCS#7 中没有证书".to_string());
+    }
+    Ok(certs)
+}
+
+/// 证书相对当前时间的有效状态
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Validity {
+    NotYetValid,
+    Valid,
+    Expired,
+}
+
+/// 证书的结构化信息
+pub struct CertInfo {
+    pub subject: String,
+    pub issuer: String,
+    pub serial: String,
+    pub version: i32,
+    pub not_before: String,
+    pub not_after: String,
+    /// 有效状态的说明，例如剩余天数
+    pub validity: String,
+    pub status: Validity,
+    pub key: String,
+    pub signature_algorithm: String,
+    pub sha1: String,
+    pub sha256: String,
+    pub sans: Vec<String>,
+    pub basic_constraints: Option<String>,
+    pub key_usage: Option<String>,
+    pub extended_key_usage: Option<String>,
+    pub extensions: Vec<String>,
+    pub pem: String,
+}
+
+impl CertInfo {
+    pub fn new(cert: &X509) -> Result<CertInfo, String> {
+        let der = cert.to_der().map_err(|e| e.to_string())?;
+        let (_, parsed) = X509Certificate::from_der(&der).map_err(|e| format!("证书解析失败: {}", e))?;
+
+        let now = Asn1Time::days_from_now(0).map_err(|e| e.to_string())?;
+        let (validity, status) = if now < cert.not_before() {
+            let diff = now.diff(cert.not_before()).map_err(|e| e.to_string())?;
+            (format!("尚未生效，{} 天后生效", diff.days), Validity::NotYetValid)
+        } else {
+            let diff = now.diff(cert.not_after()).map_err(|e| e.to_string())?;
+            if diff.days < 0 || diff.secs < 0 {
+                (format!("已过期 {} 天", -diff.days), Validity::Expired)
+            } else {
+                (format!("剩余 {} 天", diff.days), Validity::Valid)
+            }
+        };
+
+        let basic_constraints = parsed.basic_constraints().ok().flatten().map(|bc| {
+            let ca = if bc.value.ca { "CA 证书" } else { "终端实体证书" };
+            match bc.value.path_len_constraint {
+                Some(len) => format!("{}，路径长度 {}", ca, len),
+                None => ca.to_string(),
+            }
+        });
+        let key_usage = parsed.key_usage().ok().flatten().map(|ku| ku.value.to_string());
+        let extended_key_usage = parsed.extended_key_usage().ok().flatten().map(|eku| {
+            let eku = eku.value;
+            let usages = [
+                (eku.any, "Any"),
+                (eku.server_auth, "TLS Web Server Authentication"),
+                (eku.client_auth, "TLS Web Client Authentication"),
+                (eku.code_signing, "Code Signing"),
+                (eku.email_protection, "E-mail Protection"),
+                (eku.time_stamping, "Time Stamping"),
+                (eku.ocsp_signing, "OCSP Signing"),
+            ];
+            usages
+                .into_iter()
+                .filter(|(set, _)| *set)
+                .map(|(_, name)| name.to_string())
+                .chain(eku.other.iter().map(|oid| oid.to_id_string()))
+                .collect::<Vec<_>>()
+                .join(", ")
+        });
+        let public_key = cert.public_key().map_err(|e| format!("无法读取公钥: {}", e))?;
+
+        Ok(CertInfo {
+            subject: format_name(cert.subject_name()),
+            issuer: format_name(cert.issuer_name()),
+            serial: cert
+                .serial_number()
+                .to_bn()
+                .and_then(|bn| bn.to_hex_str().map(|hex| hex.to_string()))
+                .map_err(|e| e.to_string())?,
+            version: cert.version() + 1,
+            not_before: format_time(cert.not_before())?,
+            not_after: format_time(cert.not_after())?,
+            validity,
+            status,
+            key: key_description(&public_key),
+            signature_algorithm: cert.signature_algorithm().object().to_string(),
+            sha1: fingerprint(cert, MessageDigest::sha1())?,
+            sha256: fingerprint(cert, MessageDigest::sha256())?,
+            sans: alt_names(parsed.extensions()),
+            basic_constraints,
+            key_usage,
+            extended_key_usage,
+            extensions: extension_names(parsed.extensions()),
+            pem: cert
+                .to_pem()
+                .map(|pem| String::from_utf8_lossy(&pem).into_owned())
+                .map_err(|e| e.to_string())?,
+        })
+    }
+
+    /// 证书标题，优先使用主题的 CN
+    pub fn title(&self) -> String {
+        self.subject
+            .split(", ")
+            .find_map(|part| part.strip_prefix("CN="))
+            .unwrap_or(&self.subject)
+            .to_string()
+    }
+
+    /// 以 (名称, 值) 形式列出所有字段
+    pub fn fields(&self) -> Vec<(&'static str, String)> {
+        let mut fields = vec![
+            ("主题", self.subject.clone()),
+            ("颁发者", self.issuer.clone()),
+            ("序列号", self.serial.clone()),
+            ("版本", format!("V{}", self.version)),
+            ("生效时间", self.not_before.clone()),
+            ("过期时间", format!("{}（{}）", self.not_after, self.validity)),
+            ("公钥", self.key.clone()),
+            ("签名算法", self.signature_algorithm.clone()),
+            ("SHA-1 指纹", self.sha1.clone()),
+            ("SHA-256 指纹", self.sha256.clone()),
+        ];
+        if !self.sans.is_empty() {
+            fields.push(("备用名称", self.sans.join("\n")));
+        }
+        if let Some(basic_constraints) = &self.basic_constraints {
+            fields.push(("基本约束", basic_constraints.clone()));
+        }
+        if let Some(key_usage) = &self.key_usage {
+            fields.push(("密钥用途", key_usage.clone()));
+        }
+        if let Some(extended_key_usage) = &self.extended_key_usage {
+            fields.push(("扩展密钥用途", extended_key_usage.clone()));
+        }
+        if !self.extensions.is_empty() {
+            fields.push(("扩展", self.extensions.join("\n")));
+        }
+        fields
+    }
+}
+
+pub fn format_name(name: &X509NameRef) -> String {
+    name.entries()
+        .map(|entry| {
+            let key = entry.object().nid().short_name().unwrap_or("?");
+            let value = entry
+                .data()
+                .as_utf8()
+                .map(|value| value.to_string())
+                .unwrap_or_else(|_| String::from_utf8_lossy(entry.data().as_slice()).into_owned());
+            format!("{}={}", key, value)
+        })
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+fn format_time(time: &Asn1TimeRef) -> Result<String, String> {
+    let diff = Asn1Time::from_unix(0)
+        .and_then(|epoch| epoch.diff(time))
+        .map_err(|e| e.to_string())?;
+    let secs = diff.days as i64 * 86400 + diff.secs as i64;
+    DateTime::from_timestamp(secs, 0)
+        .map(|time| time.with_timezone(&Local).format("%Y-%m-%d %H:%M:%S").to_string())
+        .ok_or_else(|| format!("时间超出范围: {}", time))
+}
+
+fn fingerprint(
+    cert: &X509,
+    digest: MessageDigest,
+) -> Result<String, String> {
+    let bytes = cert.digest(digest).map_err(|e| e.to_string())?;
+    Ok(bytes.iter().map(|b| format!("{:02X}", b)).collect::<Vec<_>>().join(":"))
+}
+
+/// 公钥算法和长度
+pub fn key_description<T: HasPublic>(key: &PKeyRef<T>) -> String {
+    match key.id() {
+        Id::RSA => format!("RSA {} 位", key.bits()),
+        Id::EC => {
+            let curve = key
+                .ec_key()
+                .ok()
+                .and_then(|ec| ec.group().curve_name())
+                .map(|nid| match nid {
+                    Nid::X9_62_PRIME256V1 => "P-256".to_string(),
+                    Nid::SECP384R1 => "P-384".to_string(),
+                    Nid::SECP521R1 => "P-521".to_string(),
+                    nid => nid.short_name().unwrap_or("未知曲线").to_string(),
+                })
+                .unwrap_or_else(|| "未知曲线".to_string());
+            format!("EC {}（{} 位）", curve, key.bits())
+        }
+        Id::ED25519 => "Ed25519".to_string(),
+        Id::ED448 => "Ed448".to_string(),
+        Id::DSA => format!("DSA {} 位", key.bits()),
+        _ => format!("其他（{} 位）", key.bits()),
+    }
+}
+
+/// 从扩展中取出所有备用名称
+pub fn alt_names(extensions: &[X509Extension]) -> Vec<String> {
+    extensions
+        .iter()
+        .filter_map(|ext| match ext.parsed_extension() {
+            ParsedExtension::SubjectAlternativeName(san) => Some(san.general_names.iter()),
+            _ => None,
+        })
+        .flatten()
+        .map(format_general_name)
+        .collect()
+}
+
+/// 列出扩展的名称和 OID，并标记关键扩展
+pub fn extension_names(extensions: &[X509Extension]) -> Vec<String> {
+    extensions
+        .iter()
+        .map(|ext| {
+            let name = match oid2sn(&ext.oid, oid_registry()) {
+                Ok(name) => format!("{} ({})", name, ext.oid.to_id_string()),
+                Err(_) => ext.oid.to_id_string(),
+            };
+            if ext.critical {
+                format!("{}，关键", name)
+            } else {
+                name
+            }
+        })
+        .collect()
+}
+
+fn format_general_name(name: &GeneralName) -> String {
+    match name {
+        GeneralName::DNSName(dns) => format!("DNS: {}", dns),
+        GeneralName::RFC822Name(email) => format!("Email: {}", email),
+        GeneralName::URI(uri) => format!("URI: {}", uri),
+        GeneralName::IPAddress(ip) => {
+            let ip = match ip.len() {
+                4 => <[u8; 4]>::try_from(*ip).map(IpAddr::from).ok(),
+                16 => <[u8; 16]>::try_from(*ip).map(IpAddr::from).ok(),
+                _ => None,
+            };
+            match ip {
+                Some(ip) => format!("IP: {}", ip),
+                None => name.to_string(),
+            }
+        }
+        GeneralName::DirectoryName(dn) => format!("DirName: {}", dn),
+        name => name.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use openssl::{
+        pkcs7::Pkcs7Flags,
+        pkey::{PKey, Private},
+        stack::Stack,
+    };
+
+    use super::*;
+    use crate::pages::devel::cert::testing::{key, now, self_signed};
+
+    fn chain() -> (PKey<Private>, X509, X509) {
+        let leaf_key = key();
+        let leaf = self_signed("leaf.example.com", &leaf_key, now() - 3600, now() + 86400 * 30);
+        let ca = self_signed("Example CA", &key(), now() - 3600, now() + 86400 * 365);
+        (leaf_key, leaf, ca)
+    }
+
+    fn titles(certs: &[X509]) -> Vec<String> {
+        certs.iter().map(|cert| CertInfo::new(cert).unwrap().title()).collect()
+    }
+
+    fn base64(der: &[u8]) -> String {
+        general_purpose::STANDARD.encode(der)
+    }
+
+    #[test]
+    fn loads_pem_chain() {
+        let (_, leaf, ca) = chain();
+        let pem = [leaf.to_pem().unwrap(), ca.to_pem().unwrap()].concat();
+        let certs = load_certs(&String::from_utf8(pem).unwrap(), "").unwrap();
+        assert_eq!(titles(&certs), ["leaf.example.com", "Example CA"]);
+    }
+
+    #[test]
+    fn loads_der() {
+        let (_, leaf, _) = chain();
+        // Base64 中的换行和空白会被忽略
+        let text = base64(&leaf.to_der().unwrap())
+            .as_bytes()
+            .chunks(64)
+            .map(|line| std::str::from_utf8(line).unwrap())
+            .collect::<Vec<_>>()
+            .join("\n");
+        let certs = load_certs(&text, "").unwrap();
+        assert_eq!(titles(&certs), ["leaf.example.com"]);
+        assert!(load_certs("not a certificate", "").is_err());
+    }
+
+    #[test]
+    fn loads_pkcs7() {
+        let (key, leaf, ca) = chain();
+        let mut extra = Stack::new().unwrap();
+        extra.push(ca).unwrap();
+        let pkcs7 = Pkcs7::sign(&leaf, &key, &extra, b"", Pkcs7Flags::empty()).unwrap();
+
+        let pem = String::from_utf8(pkcs7.to_pem().unwrap()).unwrap();
+        let mut from_pem = titles(&load_certs(&pem, "").unwrap());
+        from_pem.sort();
+        assert_eq!(from_pem, ["Example CA", "leaf.example.com"]);
+
+        let mut from_der = titles(&load_certs(&base64(&pkcs7.to_der().unwrap()), "").unwrap());
+        from_der.sort();
+        assert_eq!(from_der, from_pem);
+    }
+
+    #[test]
+    fn loads_pkcs12() {
+        let (key, leaf, ca) = chain();
+        let mut cas = Stack::new().unwrap();
+        cas.push(ca).unwrap();
+        let pkcs12 = Pkcs12::builder()
+            .name("leaf")
+            .pkey(&key)
+            .cert(&leaf)
+            .ca(cas)
+            .build2("secret")
+            .unwrap();
+        let text = base64(&pkcs12.to_der().unwrap());
+
+        let certs = load_certs(&text, "secret").unwrap();
+        assert_eq!(titles(&certs), ["leaf.example.com", "Example CA"]);
+        assert!(load_certs(&text, "wrong").is_err());
+    }
+
+    #[test]
+    fn validity_states() {
+        let key = key();
+        let info = CertInfo::new(&self_signed("valid", &key, now() - 86400, now() + 86400 * 10)).unwrap();
+        assert_eq!(info.status, Validity::Valid);
+        assert_eq!(info.sans, ["DNS: valid", "IP: 127.0.0.1"]);
+
+        let info = CertInfo::new(&self_signed("future", &key, now() + 86400 * 5, now() + 86400 * 10)).unwrap();
+        assert_eq!(info.status, Validity::NotYetValid);
+        assert!(info.validity.starts_with("尚未生效"));
+
+        let info = CertInfo::new(&self_signed("expired", &key, now() - 86400 * 10, now() - 86400 * 3)).unwrap();
+        assert_eq!(info.status, Validity::Expired);
+        assert_eq!(info.validity, "已过期 3 天");
+    }
+}
@@ -0,0 +1,103 @@
+use openssl::{
+    asn1::Asn1Time,
+    bn::{BigNum, MsbOption},
+    ec::{EcGroup, EcKey},
+    hash::MessageDigest,
+    nid::Nid,
+    pkey::{PKey, Private},
+    x509::{
+        X509, X509Builder, X509Name, X509NameBuilder,
+        extension::{BasicConstraints, ExtendedKeyUsage, KeyUsage, SubjectAlternativeName},
+    },
+};
+
+pub(crate) fn key() -> PKey<Private> {
+    let group = EcGroup::from_curve_name(Nid::X9_62_PRIME256V1).unwrap();
+    PKey::from_ec_key(EcKey::generate(&group).unwrap()).unwrap()
+}
+
+pub(crate) fn now() -> i64 {
+    chrono::Utc::now().timestamp()
+}
+
+fn name(cn: &str) -> X509Name {
+    let mut name = X509NameBuilder::new().unwrap();
+    name.append_entry_by_nid(Nid::COMMONNAME, cn).unwrap();
+    name.build()
+}
+
+/// 填好主体、公钥、有效期和备用名称（`cn` 和 127.0.0.1），颁发者和签名留给调用方
+fn builder(
+    cn: &str,
+    key: &PKey<Private>,
+    not_before: i64,
+    not_after: i64,
+) -> X509Builder {
+    let mut serial = BigNum::new().unwrap();
+    serial.rand(64, MsbOption::MAYBE_ZERO, false).unwrap();
+    let serial = serial.to_asn1_integer().unwrap();
+    let not_before = Asn1Time::from_unix(not_before).unwrap();
+    let not_after = Asn1Time::from_unix(not_after).unwrap();
+
+    let mut builder = X509Builder::new().unwrap();
+    builder.set_version(2).unwrap();
+    builder.set_serial_number(&serial).unwrap();
+    builder.set_subject_name(&name(cn)).unwrap();
+    builder.set_pubkey(key).unwrap();
+    builder.set_not_before(&not_before).unwrap();
+    builder.set_not_after(&not_after).unwrap();
+    let san = SubjectAlternativeName::new()
+        .dns(cn)
+        .ip("127.0.0.1")
+        .build(&builder.x509v3_context(None, None))
+        .unwrap();
+    builder.append_extension(san).unwrap();
+    builder
+}
+
+/// 生成自签名证书，有效期为 Unix 时间 `[not_before, not_after]`，备用名称包含 `cn` 和 127.0.0.1
+pub(crate) fn self_signed(
+    cn: &str,
+    key: &PKey<Private>,
+    not_before: i64,
+    not_after: i64,
+) -> X509 {
+    let mut builder = builder(cn, key, not_before, not_after);
+    builder.set_issuer_name(&name(cn)).unwrap();
+    builder.sign(key, MessageDigest::sha256()).unwrap();
+    builder.build()
+}
+
+/// 生成一年有效期的自签名 CA 证书
+pub(crate) fn ca(
+    cn: &str,
+    key: &PKey<Private>,
+) -> X509 {
+    let mut builder = builder(cn, key, now() - 3600, now() + 86400 * 365);
+    builder.set_issuer_name(&name(cn)).unwrap();
+    builder
+        .append_extension(BasicConstraints::new().critical().ca().build().unwrap())
+        .unwrap();
+    builder
+        .append_extension(KeyUsage::new().critical().key_cert_sign().crl_sign().build().unwrap())
+        .unwrap();
+    builder.sign(key, MessageDigest::sha256()).unwrap();
+    builder.build()
+}
+
+/// 生成由 `issuer` 签发的终端证书，扩展密钥用途由 `ext_key_usage` 指定
+pub(crate) fn issued(
+    cn: &str,
+    key: &PKey<Private>,
+    issuer: &X509,
+    issuer_key: &PKey<Private>,
+    not_before: i64,
+    not_after: i64,
+    ext_key_usage: &ExtendedKeyUsage,
+) -> X509 {
+    let mut builder = builder(cn, key, not_before, not_after);
+    builder.set_issuer_name(issuer.subject_name()).unwrap();
+    builder.append_extension(ext_key_usage.build().unwrap()).unwrap();
+    builder.sign(issuer_key, MessageDigest::sha256()).unwrap();
+    builder.build()
+}
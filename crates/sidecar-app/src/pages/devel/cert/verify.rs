@@ -0,0 +1,422 @@
+use std::{fmt, net::IpAddr};
+
+use openssl::{
+    asn1::Asn1Time,
+    error::ErrorStack,
+    nid::Nid,
+    stack::Stack,
+    x509::{
+        X509, X509PurposeId, X509Ref, X509StoreContext,
+        store::{X509Store, X509StoreBuilder},
+        verify::{X509VerifyFlags, X509VerifyParam},
+    },
+};
+use openssl_sys as ffi;
+
+use super::parse::{format_name, load_certs};
+
+/// 信任锚来源
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum TrustSource {
+    System,
+    Custom,
+}
+
+impl TrustSource {
+    pub const ALL: [TrustSource; 2] = [TrustSource::System, TrustSource::Custom];
+}
+
+impl fmt::Display for TrustSource {
+    fn fmt(
+        &self,
+        f: &mut fmt::Formatter<'_>,
+    ) -> fmt::Result {
+        let name = match self {
+            TrustSource::System => "系统信任库",
+            TrustSource::Custom => "自定义 CA",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+/// 终端证书的预期用途，对应密钥用途和扩展密钥用途检查
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Purpose {
+    Server,
+    Client,
+    None,
+}
+
+impl Purpose {
+    pub const ALL: [Purpose; 3] = [Purpose::Server, Purpose::Client, Purpose::None];
+
+    fn id(self) -> Option<X509PurposeId> {
+        match self {
+            Purpose::Server => Some(X509PurposeId::SSL_SERVER),
+            Purpose::Client => Some(X509PurposeId::SSL_CLIENT),
+            Purpose::None => None,
+        }
+    }
+}
+
+impl fmt::Display for Purpose {
+    fn fmt(
+        &self,
+        f: &mut fmt::Formatter<'_>,
+    ) -> fmt::Result {
+        let name = match self {
+            Purpose::Server => "TLS 服务器",
+            Purpose::Client => "TLS 客户端",
+            Purpose::None => "不检查",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+/// 单项检查的结果，成功和失败都附带说明
+pub struct Check {
+    pub name: &'static str,
+    pub result: Result<String, String>,
+}
+
+/// 验证证书链，第一个证书视为终端证书，其余证书作为可能的中间证书
+///
+/// OpenSSL 在遇到第一个错误时就会停止，为了把所有问题都列出来，这里把信任链、有效期、
+/// 主机名和用途拆成独立的检查分别执行。
+pub fn verify_chain(
+    certs: &[X509],
+    trust: TrustSource,
+    ca_bundle: &str,
+    hostname: &str,
+    purpose: Purpose,
+) -> Result<Vec<Check>, String> {
+    let (leaf, _) = certs.split_first().ok_or("没有需要验证的证书")?;
+    let anchors = match trust {
+        TrustSource::System => vec![],
+        TrustSource::Custom => {
+            if ca_bundle.trim().is_empty() {
+                return Err("请填写自定义 CA 证书".to_string());
+            }
+            load_certs(ca_bundle, "").map_err(|e| format!("CA 证书{}", e))?
+        }
+    };
+    let mut checks = vec![];
+
+    // 信任链：不检查时间，过期问题由下面的有效期检查单独报告
+    let store = build_store(
+        &anchors,
+        trust == TrustSource::System,
+        X509VerifyFlags::NO_CHECK_TIME,
+        |_| Ok(()),
+    )
+    .map_err(|e| format!("无法创建信任库: {}", e))?;
+    let chain = run(&store, certs)?;
+    checks.push(Check {
+        name: "信任链",
+        result: match &chain {
+            Ok(chain) => {
+                let names: Vec<String> = chain.iter().map(|cert| cert_name(cert)).collect();
+                Ok(format!("已信任：{}", names.join(" → ")))
+            }
+            Err(err) => Err(err.clone()),
+        },
+    });
+
+    // 有效期：链构建成功时检查整条链，否则检查输入的证书
+    let now = Asn1Time::days_from_now(0).map_err(|e| e.to_string())?;
+    let problems: Vec<String> = chain
+        .as_deref()
+        .unwrap_or(certs)
+        .iter()
+        .filter_map(|cert| {
+            if now < cert.not_before() {
+                Some(format!("{} 尚未生效", cert_name(cert)))
+            } else if now > cert.not_after() {
+                let days = cert.not_after().diff(&now).map(|diff| diff.days).unwrap_or_default();
+                Some(format!("{} 已过期 {} 天", cert_name(cert), days))
+            } else {
+                None
+            }
+        })
+        .collect();
+    checks.push(Check {
+        name: "有效期",
+        result: if problems.is_empty() {
+            Ok("所有证书均在有效期内".to_string())
+        } else {
+            Err(problems.join("；"))
+        },
+    });
+
+    // 主机名和用途只与终端证书有关，把它作为信任锚单独检查，避免被信任链错误掩盖
+    let hostname = hostname.trim();
+    if !hostname.is_empty() {
+        let store = build_store(
+            std::slice::from_ref(leaf),
+            false,
+            X509VerifyFlags::PARTIAL_CHAIN | X509VerifyFlags::NO_CHECK_TIME,
+            |builder| {
+                let mut param = X509VerifyParam::new()?;
+                match hostname.parse::<IpAddr>() {
+                    Ok(ip) => param.set_ip(ip)?,
+                    Err(_) => param.set_host(hostname)?,
+                }
+                builder.set_param(&param)
+            },
+        )
+        .map_err(|e| format!("主机名格式错误: {}", e))?;
+        checks.push(Check {
+            name: "主机名",
+            result: run(&store, std::slice::from_ref(leaf))?.map(|_| format!("与 {} 匹配", hostname)),
+        });
+    }
+
+    if let Some(id) = purpose.id() {
+        let store = build_store(
+            std::slice::from_ref(leaf),
+            false,
+            X509VerifyFlags::PARTIAL_CHAIN | X509VerifyFlags::NO_CHECK_TIME,
+            |builder| builder.set_purpose(id),
+        )
+        .map_err(|e| format!("无法创建信任库: {}", e))?;
+        checks.push(Check {
+            name: "密钥用途",
+            result: run(&store, std::slice::from_ref(leaf))?.map(|_| format!("允许用于{}", purpose)),
+        });
+    }
+
+    Ok(checks)
+}
+
+fn build_store(
+    anchors: &[X509],
+    system: bool,
+    flags: X509VerifyFlags,
+    configure: impl FnOnce(&mut X509StoreBuilder) -> Result<(), ErrorStack>,
+) -> Result<X509Store, ErrorStack> {
+    let mut builder = X509StoreBuilder::new()?;
+    if system {
+        builder.set_default_paths()?;
+    }
+    for cert in anchors {
+        builder.add_cert(cert.clone())?;
+    }
+    builder.set_flags(flags)?;
+    configure(&mut builder)?;
+    Ok(builder.build())
+}
+
+/// 执行一次验证，成功时返回构建出的证书链，失败时返回出错的层级和原因
+fn run(
+    store: &X509Store,
+    certs: &[X509],
+) -> Result<Result<Vec<X509>, String>, String> {
+    let (leaf, rest) = certs.split_first().ok_or("没有需要验证的证书")?;
+    let mut untrusted = Stack::new().map_err(|e| e.to_string())?;
+    for cert in rest {
+        untrusted.push(cert.clone()).map_err(|e| e.to_string())?;
+    }
+
+    let mut context = X509StoreContext::new().map_err(|e| e.to_string())?;
+    context
+        .init(store, leaf, &untrusted, |context| {
+            if context.verify_cert()? {
+                let chain = context
+                    .chain()
+                    .map(|chain| chain.iter().map(|cert| cert.to_owned()).collect())
+                    .unwrap_or_default();
+                return Ok(Ok(chain));
+            }
+            let name = context.current_cert().map(cert_name).unwrap_or_default();
+            let error = context.error();
+            let reason = match describe_error(error.as_raw()) {
+                Some(reason) => format!("{}（{}）", reason, error.error_string()),
+                None => error.error_string().to_string(),
+            };
+            Ok(Err(format!("第 {} 级证书 {}：{}", context.error_depth(), name, reason)))
+        })
+        .map_err(|e| format!("验证失败: {}", e))
+}
+
+fn describe_error(code: i32) -> Option<&'static str> {
+    let reason = match code {
+        ffi::X509_V_ERR_UNABLE_TO_GET_ISSUER_CERT
+        | ffi::X509_V_ERR_UNABLE_TO_GET_ISSUER_CERT_LOCALLY
+        | ffi::X509_V_ERR_UNABLE_TO_VERIFY_LEAF_SIGNATURE => "未知颁发者",
+        ffi::X509_V_ERR_DEPTH_ZERO_SELF_SIGNED_CERT => "自签名证书不受信任",
+        ffi::X509_V_ERR_SELF_SIGNED_CERT_IN_CHAIN => "根证书不受信任",
+        ffi::X509_V_ERR_CERT_SIGNATURE_FAILURE => "证书签名无效",
+        ffi::X509_V_ERR_CERT_NOT_YET_VALID => "证书尚未生效",
+        ffi::X509_V_ERR_CERT_HAS_EXPIRED => "证书已过期",
+        ffi::X509_V_ERR_INVALID_CA => "颁发者不是有效的 CA",
+        ffi::X509_V_ERR_PATH_LENGTH_EXCEEDED => "超出路径长度限制",
+        ffi::X509_V_ERR_INVALID_PURPOSE => "密钥用途不符",
+        ffi::X509_V_ERR_HOSTNAME_MISMATCH => "主机名不匹配",
+        ffi::X509_V_ERR_IP_ADDRESS_MISMATCH => "IP 地址不匹配",
+        _ => return None,
+    };
+    Some(reason)
+}
+
+fn cert_name(cert: &X509Ref) -> String {
+    cert.subject_name()
+        .entries_by_nid(Nid::COMMONNAME)
+        .next()
+        .and_then(|entry| entry.data().as_utf8().ok())
+        .map(|name| name.to_string())
+        .unwrap_or_else(|| format_name(cert.subject_name()))
+}
+
+#[cfg(test)]
+mod tests {
+    use openssl::{
+        pkey::{PKey, Private},
+        x509::{X509, extension::ExtendedKeyUsage},
+    };
+
+    use super::{Purpose, TrustSource, verify_chain};
+    use crate::pages::devel::cert::testing::{ca, issued, key, now};
+
+    struct Fixture {
+        ca: X509,
+        ca_key: PKey<Private>,
+    }
+
+    impl Fixture {
+        fn new() -> Self {
+            let ca_key = key();
+            Self {
+                ca: ca("Test CA", &ca_key),
+                ca_key,
+            }
+        }
+
+        fn ca_pem(&self) -> String {
+            String::from_utf8(self.ca.to_pem().unwrap()).unwrap()
+        }
+
+        fn leaf(
+            &self,
+            not_after: i64,
+            ext_key_usage: &ExtendedKeyUsage,
+        ) -> X509 {
+            issued(
+                "leaf.test",
+                &key(),
+                &self.ca,
+                &self.ca_key,
+                not_after - 86400 * 30,
+                not_after,
+                ext_key_usage,
+            )
+        }
+
+        fn server_leaf(&self) -> X509 {
+            self.leaf(now() + 86400 * 30, ExtendedKeyUsage::new().server_auth())
+        }
+    }
+
+    /// 执行验证，返回每项检查的名称和结果
+    fn checks(
+        leaf: X509,
+        ca_bundle: &str,
+        hostname: &str,
+        purpose: Purpose,
+    ) -> Vec<(&'static str, Result<String, String>)> {
+        verify_chain(&[leaf], TrustSource::Custom, ca_bundle, hostname, purpose)
+            .unwrap()
+            .into_iter()
+            .map(|check| (check.name, check.result))
+            .collect()
+    }
+
+    #[test]
+    fn good_chain_passes_every_check() {
+        let fixture = Fixture::new();
+        assert_eq!(
+            checks(fixture.server_leaf(), &fixture.ca_pem(), "leaf.test", Purpose::Server),
+            [
+                ("信任链", Ok("已信任：leaf.test → Test CA".to_string())),
+                ("有效期", Ok("所有证书均在有效期内".to_string())),
+                ("主机名", Ok("与 leaf.test 匹配".to_string())),
+                ("密钥用途", Ok("允许用于TLS 服务器".to_string())),
+            ]
+        );
+
+        // 不填主机名、不检查用途时只有前两项
+        let names: Vec<_> = checks(fixture.server_leaf(), &fixture.ca_pem(), "", Purpose::None)
+            .into_iter()
+            .map(|(name, _)| name)
+            .collect();
+        assert_eq!(names, ["信任链", "有效期"]);
+    }
+
+    #[test]
+    fn expired_leaf_is_reported_separately() {
+        let fixture = Fixture::new();
+        let leaf = fixture.leaf(now() - 86400 * 10 - 60, ExtendedKeyUsage::new().server_auth());
+        let checks = checks(leaf, &fixture.ca_pem(), "leaf.test", Purpose::Server);
+        assert_eq!(checks[0].1, Ok("已信任：leaf.test → Test CA".to_string()));
+        assert_eq!(checks[1], ("有效期", Err("leaf.test 已过期 10 天".to_string())));
+        assert!(checks[2].1.is_ok());
+        assert!(checks[3].1.is_ok());
+    }
+
+    #[test]
+    fn missing_issuer() {
+        let fixture = Fixture::new();
+        let other = String::from_utf8(ca("Other CA", &key()).to_pem().unwrap()).unwrap();
+        let checks = checks(fixture.server_leaf(), &other, "leaf.test", Purpose::Server);
+        assert_eq!(
+            checks[0],
+            (
+                "信任链",
+                Err("第 0 级证书 leaf.test：未知颁发者（unable to get local issuer certificate）".to_string())
+            )
+        );
+        // 其余检查只看终端证书，不受信任链错误影响
+        assert_eq!(checks[1].1, Ok("所有证书均在有效期内".to_string()));
+        assert_eq!(checks[2].1, Ok("与 leaf.test 匹配".to_string()));
+        assert_eq!(checks[3].1, Ok("允许用于TLS 服务器".to_string()));
+
+        assert!(verify_chain(&[fixture.server_leaf()], TrustSource::Custom, " ", "", Purpose::None).is_err());
+        assert!(verify_chain(&[], TrustSource::Custom, &fixture.ca_pem(), "", Purpose::None).is_err());
+    }
+
+    #[test]
+    fn hostname_and_ip_mismatch() {
+        let fixture = Fixture::new();
+        let hostname = |hostname| checks(fixture.server_leaf(), &fixture.ca_pem(), hostname, Purpose::None)[2].clone();
+        assert_eq!(hostname("127.0.0.1"), ("主机名", Ok("与 127.0.0.1 匹配".to_string())));
+        assert_eq!(
+            hostname("other.test"),
+            (
+                "主机名",
+                Err("第 0 级证书 leaf.test：主机名不匹配（hostname mismatch）".to_string())
+            )
+        );
+        assert_eq!(
+            hostname("10.0.0.1"),
+            (
+                "主机名",
+                Err("第 0 级证书 leaf.test：IP 地址不匹配（IP address mismatch）".to_string())
+            )
+        );
+    }
+
+    #[test]
+    fn client_only_leaf_is_not_a_server() {
+        let fixture = Fixture::new();
+        let leaf = fixture.leaf(now() + 86400 * 30, ExtendedKeyUsage::new().client_auth());
+        let server = checks(leaf.clone(), &fixture.ca_pem(), "", Purpose::Server);
+        assert_eq!(
+            server[2],
+            (
+                "密钥用途",
+                Err("第 0 级证书 leaf.test：密钥用途不符（unsuitable certificate purpose）".to_string())
+            )
+        );
+        let client = checks(leaf, &fixture.ca_pem(), "", Purpose::Client);
+        assert_eq!(client[2], ("密钥用途", Ok("允许用于TLS 客户端".to_string())));
+    }
+}
@@ -1,15 +1,108 @@
+pub mod asymmetric;
+pub mod kdf;
+pub mod symmetric;
+
+use std::fmt;
+
+use base64::{Engine, engine::general_purpose};
 use gpui::*;
+use gpui_component::radio::RadioGroup;
+
+use crate::{
+    MainView,
+    comps::{card, label, page},
+    pages::utils::strip_str,
+};
+use asymmetric::AsymmetricPanel;
+use kdf::KdfPanel;
+use symmetric::SymmetricPanel;
+
+/// 密钥、输入和输出的文本编码
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Encoding {
+    Hex,
+    Base64,
+    Utf8,
+}
+
+impl Encoding {
+    pub const ALL: [Encoding; 3] = [Encoding::Hex, Encoding::Base64, Encoding::Utf8];
+
+    pub fn decode(
+        self,
+        s: &str,
+    ) -> Result<Vec<u8>, String> {
+        match self {
+            Encoding::Hex => hex::decode(strip_str(s)).map_err(|e| format!("Hex 格式错误: {}", e)),
+            Encoding::Base64 => general_purpose::STANDARD
+                .decode(strip_str(s))
+                .map_err(|e| format!("Base64 格式错误: {}", e)),
+            Encoding::Utf8 => Ok(s.as_bytes().to_vec()),
+        }
+    }
+
+    pub fn encode(
+        self,
+        bytes: &[u8],
+    ) -> Result<String, String> {
+        match self {
+            Encoding::Hex => Ok(hex::encode(bytes)),
+            Encoding::Base64 => Ok(general_purpose::STANDARD.encode(bytes)),
+            Encoding::Utf8 => String::from_utf8(bytes.to_vec())
+                .map_err(|_| "结果不是有效的 UTF-8 文本，请选择 Hex 或 Base64 格式".to_string()),
+        }
+    }
+}
+
+impl fmt::Display for Encoding {
+    fn fmt(
+        &self,
+        f: &mut fmt::Formatter<'_>,
+    ) -> fmt::Result {
+        let name = match self {
+            Encoding::Hex => "Hex",
+            Encoding::Base64 => "Base64",
+            Encoding::Utf8 => "UTF-8",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+fn encoding_group(
+    id: &'static str,
+    selected: Encoding,
+) -> RadioGroup {
+    Encoding::ALL.iter().fold(
+        RadioGroup::horizontal(id).selected_index(Encoding::ALL.iter().position(|encoding| *encoding == selected)),
+        |group, encoding| group.child(encoding.to_string()),
+    )
+}
 
-use crate::{MainView, comps::page};
+#[derive(Clone, Copy, PartialEq)]
+enum CryptoTab {
+    Symmetric,
+    Asymmetric,
+    Kdf,
+}
 
-pub struct CryptoPage;
+pub struct CryptoPage {
+    tab: CryptoTab,
+    symmetric: Entity<SymmetricPanel>,
+    asymmetric: Entity<AsymmetricPanel>,
+    kdf: Entity<KdfPanel>,
+}
 
 impl CryptoPage {
     pub fn build(
-        _window: &mut Window,
+        window: &mut Window,
         cx: &mut Context<MainView>,
     ) -> AnyView {
-        AnyView::from(cx.new(|_| CryptoPage))
+        AnyView::from(cx.new(|cx| Self {
+            tab: CryptoTab::Symmetric,
+            symmetric: cx.new(|cx| SymmetricPanel::new(window, cx)),
+            asymmetric: cx.new(|cx| AsymmetricPanel::new(window, cx)),
+            kdf: cx.new(|cx| KdfPanel::new(window, cx)),
+        }))
     }
 }
 
@@ -17,15 +110,39 @@ impl Render for CryptoPage {
     fn render(
         &mut self,
         _window: &mut Window,
-        _cx: &mut Context<Self>,
+        cx: &mut Context<Self>,
     ) -> impl IntoElement {
-        page().child(
-            div()
-                .flex_1()
-                .flex()
-                .items_center()
-                .justify_center()
-                .child(div().text_xl().text_color(rgb(0x808080)).child("功能开发中...")),
-        )
+        let panel = match self.tab {
+            CryptoTab::Symmetric => AnyView::from(self.symmetric.clone()),
+            CryptoTab::Asymmetric => AnyView::from(self.asymmetric.clone()),
+            CryptoTab::Kdf => AnyView::from(self.kdf.clone()),
+        };
+
+        page()
+            .size_full()
+            .child(
+                card().child(
+                    div().flex().gap_4().items_center().child(label("功能")).child(
+                        RadioGroup::horizontal("crypto-tab")
+                            .selected_index(Some(match self.tab {
+                                CryptoTab::Symmetric => 0,
+                                CryptoTab::Asymmetric => 1,
+                                CryptoTab::Kdf => 2,
+                            }))
+                            .child("对称加密")
+                            .child("非对称加密")
+                            .child("密钥派生")
+                            .on_click(cx.listener(|this, index: &usize, _window, cx| {
+                                this.tab = match *index {
+                                    1 => CryptoTab::Asymmetric,
+                                    2 => CryptoTab::Kdf,
+                                    _ => CryptoTab::Symmetric,
+                                };
+                                cx.notify();
+                            })),
+                    ),
+                ),
+            )
+            .child(div().flex_1().flex().flex_col().min_h_0().child(panel))
     }
 }
@@ -0,0 +1,929 @@
+use std::fmt;
+
+use gpui::*;
+use gpui_component::{WindowExt, input::InputState, radio::RadioGroup};
+use openssl::{
+    error::ErrorStack,
+    rand::rand_bytes,
+    symm::{self, Cipher, Crypter},
+};
+
+use super::{Encoding, encoding_group};
+use crate::comps::{button, card, label, textarea};
+
+/// AES 和 SM4 的分组大小
+const BLOCK_SIZE: usize = 16;
+/// GCM 和 Poly1305 认证标签的长度，附加在密文末尾
+const TAG_LEN: usize = 16;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Algorithm {
+    Aes128,
+    Aes192,
+    Aes256,
+    Sm4,
+    ChaCha20Poly1305,
+}
+
+impl Algorithm {
+    pub const ALL: [Algorithm; 5] = [
+        Algorithm::Aes128,
+        Algorithm::Aes192,
+        Algorithm::Aes256,
+        Algorithm::Sm4,
+        Algorithm::ChaCha20Poly1305,
+    ];
+
+    pub fn key_len(self) -> usize {
+        match self {
+            Algorithm::Aes128 | Algorithm::Sm4 => 16,
+            Algorithm::Aes192 => 24,
+            Algorithm::Aes256 | Algorithm::ChaCha20Poly1305 => 32,
+        }
+    }
+
+    /// 支持的工作模式，ChaCha20-Poly1305 是流密码，没有模式可选
+    pub fn modes(self) -> &'static [CipherMode] {
+        match self {
+            Algorithm::Aes128 | Algorithm::Aes192 | Algorithm::Aes256 => &CipherMode::ALL,
+            Algorithm::Sm4 => &[CipherMode::Ecb, CipherMode::Cbc, CipherMode::Ctr],
+            Algorithm::ChaCha20Poly1305 => &[],
+        }
+    }
+
+    /// IV 或 Nonce 的长度，ECB 模式不需要
+    pub fn iv_len(
+        self,
+        mode: CipherMode,
+    ) -> usize {
+        if self == Algorithm::ChaCha20Poly1305 {
+            return 12;
+        }
+        match mode {
+            CipherMode::Ecb => 0,
+            CipherMode::Cbc | CipherMode::Ctr => 16,
+            CipherMode::Gcm => 12,
+        }
+    }
+
+    /// GCM 和 ChaCha20-Poly1305 是带认证的加密，密文末尾附加认证标签
+    pub fn is_aead(
+        self,
+        mode: CipherMode,
+    ) -> bool {
+        self == Algorithm::ChaCha20Poly1305 || mode == CipherMode::Gcm
+    }
+
+    /// 只有分组模式需要填充
+    pub fn uses_padding(
+        self,
+        mode: CipherMode,
+    ) -> bool {
+        self != Algorithm::ChaCha20Poly1305 && matches!(mode, CipherMode::Ecb | CipherMode::Cbc)
+    }
+}
+
+impl fmt::Display for Algorithm {
+    fn fmt(
+        &self,
+        f: &mut fmt::Formatter<'_>,
+    ) -> fmt::Result {
+        let name = match self {
+            Algorithm::Aes128 => "AES-128",
+            Algorithm::Aes192 => "AES-192",
+            Algorithm::Aes256 => "AES-256",
+            Algorithm::Sm4 => "SM4",
+            Algorithm::ChaCha20Poly1305 => "ChaCha20-Poly1305",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum CipherMode {
+    Ecb,
+    Cbc,
+    Ctr,
+    Gcm,
+}
+
+impl CipherMode {
+    pub const ALL: [CipherMode; 4] = [CipherMode::Ecb, CipherMode::Cbc, CipherMode::Ctr, CipherMode::Gcm];
+}
+
+impl fmt::Display for CipherMode {
+    fn fmt(
+        &self,
+        f: &mut fmt::Formatter<'_>,
+    ) -> fmt::Result {
+        let name = match self {
+            CipherMode::Ecb => "ECB",
+            CipherMode::Cbc => "CBC",
+            CipherMode::Ctr => "CTR",
+            CipherMode::Gcm => "GCM",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Padding {
+    Pkcs7,
+    /// 用 0 补齐到分组大小，解密时去掉末尾的 0
+    Zero,
+    None,
+}
+
+impl Padding {
+    pub const ALL: [Padding; 3] = [Padding::Pkcs7, Padding::Zero, Padding::None];
+}
+
+impl fmt::Display for Padding {
+    fn fmt(
+        &self,
+        f: &mut fmt::Formatter<'_>,
+    ) -> fmt::Result {
+        let name = match self {
+            Padding::Pkcs7 => "PKCS7",
+            Padding::Zero => "零填充",
+            Padding::None => "不填充",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct SymmetricParams {
+    pub algorithm: Algorithm,
+    pub mode: CipherMode,
+    pub padding: Padding,
+    pub key: Vec<u8>,
+    pub iv: Vec<u8>,
+    /// GCM 和 ChaCha20-Poly1305 的附加认证数据
+    pub aad: Vec<u8>,
+}
+
+impl SymmetricParams {
+    fn is_aead(&self) -> bool {
+        self.algorithm.is_aead(self.mode)
+    }
+
+    fn uses_padding(&self) -> bool {
+        self.algorithm.uses_padding(self.mode)
+    }
+
+    fn iv_len(&self) -> usize {
+        self.algorithm.iv_len(self.mode)
+    }
+
+    /// 算法和模式对应的 OpenSSL 算法，不支持的组合返回 None
+    fn cipher(&self) -> Option<Cipher> {
+        let cipher = match (self.algorithm, self.mode) {
+            (Algorithm::ChaCha20Poly1305, _) => Cipher::chacha20_poly1305(),
+            (Algorithm::Aes128, CipherMode::Ecb) => Cipher::aes_128_ecb(),
+            (Algorithm::Aes128, CipherMode::Cbc) => Cipher::aes_128_cbc(),
+            (Algorithm::Aes128, CipherMode::Ctr) => Cipher::aes_128_ctr(),
+            (Algorithm::Aes128, CipherMode::Gcm) => Cipher::aes_128_gcm(),
+            (Algorithm::Aes192, CipherMode::Ecb) => Cipher::aes_192_ecb(),
+            (Algorithm::Aes192, CipherMode::Cbc) => Cipher::aes_192_cbc(),
+            (Algorithm::Aes192, CipherMode::Ctr) => Cipher::aes_192_ctr(),
+            (Algorithm::Aes192, CipherMode::Gcm) => Cipher::aes_192_gcm(),
+            (Algorithm::Aes256, CipherMode::Ecb) => Cipher::aes_256_ecb(),
+            (Algorithm::Aes256, CipherMode::Cbc) => Cipher::aes_256_cbc(),
+            (Algorithm::Aes256, CipherMode::Ctr) => Cipher::aes_256_ctr(),
+            (Algorithm::Aes256, CipherMode::Gcm) => Cipher::aes_256_gcm(),
+            (Algorithm::Sm4, CipherMode::Ecb) => Cipher::sm4_ecb(),
+            (Algorithm::Sm4, CipherMode::Cbc) => Cipher::sm4_cbc(),
+            (Algorithm::Sm4, CipherMode::Ctr) => Cipher::sm4_ctr(),
+            (Algorithm::Sm4, CipherMode::Gcm) => return None,
+        };
+        Some(cipher)
+    }
+
+    fn validate(&self) -> Result<Cipher, String> {
+        let Some(cipher) = self.cipher() else {
+            return Err(format!("{} 不支持 {} 模式", self.algorithm, self.mode));
+        };
+        if self.key.len() != self.algorithm.key_len() {
+            return Err(format!(
+                "{} 的密钥长度应为 {} 字节，当前为 {} 字节",
+                self.algorithm,
+                self.algorithm.key_len(),
+                self.key.len()
+            ));
+        }
+        if self.iv.len() != self.iv_len() {
+            return Err(format!(
+                "IV 长度应为 {} 字节，当前为 {} 字节",
+                self.iv_len(),
+                self.iv.len()
+            ));
+        }
+        Ok(cipher)
+    }
+
+    fn iv(&self) -> Option<&[u8]> {
+        (!self.iv.is_empty()).then_some(self.iv.as_slice())
+    }
+
+    pub fn encrypt(
+        &self,
+        data: &[u8],
+    ) -> Result<Vec<u8>, String> {
+        let cipher = self.validate()?;
+        if self.is_aead() {
+            let mut tag = [0u8; TAG_LEN];
+            let mut out = symm::encrypt_aead(cipher, &self.key, self.iv(), &self.aad, data, &mut tag)
+                .map_err(|e| format!("加密失败: {}", e))?;
+            out.extend_from_slice(&tag);
+            return Ok(out);
+        }
+
+        let mut data = data.to_vec();
+        if self.uses_padding() && self.padding == Padding::Zero && !data.len().is_multiple_of(BLOCK_SIZE) {
+            data.resize(data.len().next_multiple_of(BLOCK_SIZE), 0);
+        }
+        self.crypt(cipher, symm::Mode::Encrypt, &data)
+    }
+
+    pub fn decrypt(
+        &self,
+        data: &[u8],
+    ) -> Result<Vec<u8>, String> {
+        let cipher = self.validate()?;
+        if self.is_aead() {
+            if data.len() < TAG_LEN {
+                return Err(format!("密文太短，末尾应包含 {} 字节的认证标签", TAG_LEN));
+            }
+            let (data, tag) = data.split_at(data.len() - TAG_LEN);
+            return symm::decrypt_aead(cipher, &self.key, self.iv(), &self.aad, data, tag)
+                .map_err(|_| "解密失败: 认证标签校验失败，请检查密钥、IV 和附加数据".to_string());
+        }
+
+        let mut out = self.crypt(cipher, symm::Mode::Decrypt, data)?;
+        if self.uses_padding() && self.padding == Padding::Zero {
+            let len = out.iter().rposition(|b| *b != 0).map_or(0, |i| i + 1);
+            out.truncate(len);
+        }
+        Ok(out)
+    }
+
+    fn crypt(
+        &self,
+        cipher: Cipher,
+        mode: symm::Mode,
+        data: &[u8],
+    ) -> Result<Vec<u8>, String> {
+        let decrypt = matches!(mode, symm::Mode::Decrypt);
+        let pkcs7 = self.uses_padding() && self.padding == Padding::Pkcs7;
+        if self.uses_padding() && (!pkcs7 || decrypt) && !data.len().is_multiple_of(BLOCK_SIZE) {
+            return Err(format!("数据长度需要是 {} 字节的整数倍", BLOCK_SIZE));
+        }
+
+        let action = if decrypt { "解密" } else { "加密" };
+        let run = || -> Result<Vec<u8>, openssl::error::ErrorStack> {
+            let mut crypter = Crypter::new(cipher, mode, &self.key, self.iv())?;
+            crypter.pad(pkcs7);
+            let mut out = vec![0u8; data.len() + cipher.block_size()];
+            let mut n = crypter.update(data, &mut out)?;
+            n += crypter.finalize(&mut out[n..])?;
+            out.truncate(n);
+            Ok(out)
+        };
+        run().map_err(|e| {
+            if decrypt && pkcs7 {
+                format!("{}失败: 填充校验失败，请检查密钥、IV 和填充方式", action)
+            } else {
+                format!("{}失败: {}", action, e)
+            }
+        })
+    }
+}
+
+/// 随机生成 UTF-8 格式密钥时使用的字符
+const KEY_CHARS: &[u8] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789";
+
+/// 从 `KEY_CHARS` 中均匀地随机取字符，丢弃超出其整数倍范围的字节以避免取模偏差
+fn random_chars(len: usize) -> Result<String, ErrorStack> {
+    let limit = 256 - 256 % KEY_CHARS.len();
+    let mut out = String::with_capacity(len);
+    let mut bytes = [0u8; 64];
+    while out.len() < len {
+        rand_bytes(&mut bytes)?;
+        out.extend(
+            bytes
+                .iter()
+                .filter(|b| (**b as usize) < limit)
+                .take(len - out.len())
+                .map(|b| KEY_CHARS[*b as usize % KEY_CHARS.len()] as char),
+        );
+    }
+    Ok(out)
+}
+
+pub struct SymmetricPanel {
+    algorithm: Algorithm,
+    mode: CipherMode,
+    padding: Padding,
+    key_encoding: Encoding,
+    input_encoding: Encoding,
+    output_encoding: Encoding,
+    key: Entity<InputState>,
+    iv: Entity<InputState>,
+    aad: Entity<InputState>,
+    input: Entity<InputState>,
+    output: Entity<InputState>,
+}
+
+impl SymmetricPanel {
+    pub fn new(
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Self {
+        let key = cx.new(|cx| InputState::new(window, cx));
+        let iv = cx.new(|cx| InputState::new(window, cx));
+        let aad = cx.new(|cx| InputState::new(window, cx).placeholder("可选，按 UTF-8 处理"));
+        let input = cx.new(|cx| InputState::new(window, cx).multi_line(true));
+        let output = cx.new(|cx| InputState::new(window, cx).multi_line(true));
+
+        Self {
+            algorithm: Algorithm::Aes256,
+            mode: CipherMode::Cbc,
+            padding: Padding::Pkcs7,
+            key_encoding: Encoding::Hex,
+            input_encoding: Encoding::Utf8,
+            output_encoding: Encoding::Base64,
+            key,
+            iv,
+            aad,
+            input,
+            output,
+        }
+    }
+
+    fn params(
+        &self,
+        cx: &App,
+    ) -> Result<SymmetricParams, String> {
+        let key = self
+            .key_encoding
+            .decode(&self.key.read(cx).value())
+            .map_err(|e| format!("密钥{}", e))?;
+        let iv = self
+            .key_encoding
+            .decode(&self.iv.read(cx).value())
+            .map_err(|e| format!("IV {}", e))?;
+
+        Ok(SymmetricParams {
+            algorithm: self.algorithm,
+            mode: self.mode,
+            padding: self.padding,
+            key,
+            // ECB 模式不使用 IV，忽略残留的输入
+            iv: if self.algorithm.iv_len(self.mode) == 0 {
+                Vec::new()
+            } else {
+                iv
+            },
+            aad: self.aad.read(cx).value().as_bytes().to_vec(),
+        })
+    }
+
+    fn run(
+        &mut self,
+        encrypt: bool,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let result = self.params(cx).and_then(|params| {
+            let data = self
+                .input_encoding
+                .decode(&self.input.read(cx).value())
+                .map_err(|e| format!("输入{}", e))?;
+            let out = if encrypt {
+                params.encrypt(&data)
+            } else {
+                params.decrypt(&data)
+            }?;
+            self.output_encoding.encode(&out)
+        });
+
+        match result {
+            Ok(out) => {
+                self.output.update(cx, |state, cx2| {
+                    state.set_value(out, window, cx2);
+                });
+            }
+            Err(e) => {
+                self.output.update(cx, |state, cx2| {
+                    state.set_value("", window, cx2);
+                });
+                window.push_notification(e, cx);
+            }
+        }
+        cx.notify();
+    }
+
+    /// 按当前的密钥格式生成随机的密钥或 IV
+    fn random(
+        &mut self,
+        iv: bool,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let len = if iv {
+            self.algorithm.iv_len(self.mode)
+        } else {
+            self.algorithm.key_len()
+        };
+        let value = match self.key_encoding {
+            Encoding::Utf8 => random_chars(len),
+            encoding => {
+                let mut bytes = vec![0u8; len];
+                rand_bytes(&mut bytes).map(|_| encoding.encode(&bytes).unwrap_or_default())
+            }
+        };
+        let value = match value {
+            Ok(value) => value,
+            Err(e) => {
+                window.push_notification(format!("生成失败: {}", e), cx);
+                return;
+            }
+        };
+
+        let state = if iv { &self.iv } else { &self.key };
+        state.update(cx, |state, cx2| {
+            state.set_value(value, window, cx2);
+        });
+        cx.notify();
+    }
+
+    /// 切换密钥格式时转换已输入的密钥和 IV
+    fn set_key_encoding(
+        &mut self,
+        encoding: Encoding,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        for state in [&self.key, &self.iv] {
+            let converted = self
+                .key_encoding
+                .decode(&state.read(cx).value())
+                .and_then(|bytes| encoding.encode(&bytes));
+            if let Ok(value) = converted {
+                state.update(cx, |state, cx2| {
+                    state.set_value(value, window, cx2);
+                });
+            }
+        }
+        self.key_encoding = encoding;
+        cx.notify();
+    }
+
+    /// 把输出作为新的输入，方便对加密结果解密
+    fn swap(
+        &mut self,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let output = self.output.read(cx).value();
+        self.input.update(cx, |state, cx2| {
+            state.set_value(output, window, cx2);
+        });
+        self.output.update(cx, |state, cx2| {
+            state.set_value("", window, cx2);
+        });
+        std::mem::swap(&mut self.input_encoding, &mut self.output_encoding);
+        cx.notify();
+    }
+}
+
+impl SymmetricPanel {
+    fn render_settings(
+        &mut self,
+        cx: &mut Context<Self>,
+    ) -> Div {
+        let iv_len = self.algorithm.iv_len(self.mode);
+        let aead = self.algorithm.is_aead(self.mode);
+        let modes = self.algorithm.modes();
+        let algorithm_index = Algorithm::ALL.iter().position(|algorithm| *algorithm == self.algorithm);
+        let mode_index = modes.iter().position(|mode| *mode == self.mode);
+        let padding_index = Padding::ALL.iter().position(|padding| *padding == self.padding);
+
+        card()
+            .child(
+                div().flex().gap_4().items_center().child(label("算法")).child(
+                    Algorithm::ALL
+                        .iter()
+                        .fold(
+                            RadioGroup::horizontal("crypto-algorithm").selected_index(algorithm_index),
+                            |group, algorithm| group.child(algorithm.to_string()),
+                        )
+                        .on_click(cx.listener(|this, index: &usize, _window, cx| {
+                            this.algorithm = Algorithm::ALL[*index];
+                            let modes = this.algorithm.modes();
+                            if !modes.is_empty() && !modes.contains(&this.mode) {
+                                this.mode = CipherMode::Cbc;
+                            }
+                            cx.notify();
+                        })),
+                ),
+            )
+            .children((!modes.is_empty()).then(|| {
+                div().flex().gap_4().items_center().child(label("模式")).child(
+                    modes
+                        .iter()
+                        .fold(
+                            RadioGroup::horizontal("crypto-mode").selected_index(mode_index),
+                            |group, mode| group.child(mode.to_string()),
+                        )
+                        .on_click(cx.listener(|this, index: &usize, _window, cx| {
+                            this.mode = this.algorithm.modes()[*index];
+                            cx.notify();
+                        })),
+                )
+            }))
+            .children(self.algorithm.uses_padding(self.mode).then(|| {
+                div().flex().gap_4().items_center().child(label("填充")).child(
+                    Padding::ALL
+                        .iter()
+                        .fold(
+                            RadioGroup::horizontal("crypto-padding").selected_index(padding_index),
+                            |group, padding| group.child(padding.to_string()),
+                        )
+                        .on_click(cx.listener(|this, index: &usize, _window, cx| {
+                            this.padding = Padding::ALL[*index];
+                            cx.notify();
+                        })),
+                )
+            }))
+            .child(div().flex().gap_4().items_center().child(label("密钥格式")).child(
+                encoding_group("crypto-key-encoding", self.key_encoding).on_click(cx.listener(
+                    |this, index: &usize, window, cx| {
+                        this.set_key_encoding(Encoding::ALL[*index], window, cx);
+                    },
+                )),
+            ))
+            .child(
+                div()
+                    .flex()
+                    .gap_4()
+                    .items_center()
+                    .child(label(format!("密钥（{} 字节）", self.algorithm.key_len())))
+                    .child(textarea(&self.key, |input| input.font_family("monospace")))
+                    .child(button(cx, "crypto-random-key").label("随机生成").on_click(cx.listener(
+                        |this, _ev, window, cx| {
+                            this.random(false, window, cx);
+                        },
+                    ))),
+            )
+            .children((iv_len > 0).then(|| {
+                let name = if aead { "Nonce" } else { "IV" };
+                div()
+                    .flex()
+                    .gap_4()
+                    .items_center()
+                    .child(label(format!("{}（{} 字节）", name, iv_len)))
+                    .child(textarea(&self.iv, |input| input.font_family("monospace")))
+                    .child(button(cx, "crypto-random-iv").label("随机生成").on_click(cx.listener(
+                        |this, _ev, window, cx| {
+                            this.random(true, window, cx);
+                        },
+                    )))
+            }))
+            .children(aead.then(|| {
+                div()
+                    .flex()
+                    .gap_4()
+                    .items_center()
+                    .child(label("附加数据"))
+                    .child(textarea(&self.aad, |input| input))
+            }))
+    }
+}
+
+impl Render for SymmetricPanel {
+    fn render(
+        &mut self,
+        _window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> impl IntoElement {
+        let aead = self.algorithm.is_aead(self.mode);
+
+        div()
+            .flex_1()
+            .flex()
+            .flex_col()
+            .gap_4()
+            .min_h_0()
+            .child(self.render_settings(cx))
+            .child(
+                card()
+                    .flex_1()
+                    .child(
+                        div()
+                            .flex()
+                            .gap_4()
+                            .items_center()
+                            .child(label("输入"))
+                            .child(
+                                encoding_group("crypto-input-encoding", self.input_encoding).on_click(cx.listener(
+                                    |this, index: &usize, _window, cx| {
+                                        this.input_encoding = Encoding::ALL[*index];
+                                        cx.notify();
+                                    },
+                                )),
+                            )
+                            .child(div().flex_1())
+                            .child(button(cx, "crypto-encrypt").label("加密").on_click(cx.listener(
+                                |this, _ev, window, cx| {
+                                    this.run(true, window, cx);
+                                },
+                            )))
+                            .child(button(cx, "crypto-decrypt").label("解密").on_click(cx.listener(
+                                |this, _ev, window, cx| {
+                                    this.run(false, window, cx);
+                                },
+                            )))
+                            .child(button(cx, "crypto-swap").label("交换输入输出").on_click(cx.listener(
+                                |this, _ev, window, cx| {
+                                    this.swap(window, cx);
+                                },
+                            ))),
+                    )
+                    .child(textarea(&self.input, |input| input)),
+            )
+            .child(
+                card()
+                    .flex_1()
+                    .child(
+                        div()
+                            .flex()
+                            .gap_4()
+                            .items_center()
+                            .child(label("输出"))
+                            .child(encoding_group("crypto-output-encoding", self.output_encoding).on_click(
+                                cx.listener(|this, index: &usize, _window, cx| {
+                                    this.output_encoding = Encoding::ALL[*index];
+                                    cx.notify();
+                                }),
+                            ))
+                            .children(aead.then(|| {
+                                div()
+                                    .text_sm()
+                                    .text_color(rgb(0x808080))
+                                    .child("密文末尾附加 16 字节认证标签")
+                            })),
+                    )
+                    .child(textarea(&self.output, |input| input)),
+            )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Algorithm, CipherMode, KEY_CHARS, Padding, SymmetricParams, random_chars};
+
+    fn params(
+        algorithm: Algorithm,
+        mode: CipherMode,
+        key: &str,
+        iv: &str,
+    ) -> SymmetricParams {
+        SymmetricParams {
+            algorithm,
+            mode,
+            padding: Padding::None,
+            key: hex::decode(key).unwrap(),
+            iv: hex::decode(iv).unwrap(),
+            aad: Vec::new(),
+        }
+    }
+
+    /// 检查加密结果与标准向量一致，并能解密回明文
+    fn assert_vector(
+        params: &SymmetricParams,
+        plaintext: &str,
+        ciphertext: &str,
+    ) {
+        let plaintext = hex::decode(plaintext).unwrap();
+        let encrypted = params.encrypt(&plaintext).unwrap();
+        assert_eq!(hex::encode(&encrypted), ciphertext);
+        assert_eq!(params.decrypt(&encrypted).unwrap(), plaintext);
+    }
+
+    /// NIST SP 800-38A 附录 F 的明文
+    const SP800_38A_PLAINTEXT: &str = concat!(
+        "6bc1bee22e409f96e93d7e117393172a",
+        "ae2d8a571e03ac9c9eb76fac45af8e51",
+        "30c81c46a35ce411e5fbc1191a0a52ef",
+        "f69f2445df4f9b17ad2b417be66c3710",
+    );
+    const AES128_KEY: &str = "2b7e151628aed2a6abf7158809cf4f3c";
+
+    #[test]
+    fn aes_sp800_38a_vectors() {
+        let iv = "000102030405060708090a0b0c0d0e0f";
+        assert_vector(
+            &params(Algorithm::Aes128, CipherMode::Ecb, AES128_KEY, ""),
+            SP800_38A_PLAINTEXT,
+            concat!(
+                "3ad77bb40d7a3660a89ecaf32466ef97",
+                "f5d3d58503b9699de785895a96fdbaaf",
+                "43b1cd7f598ece23881b00e3ed030688",
+                "7b0c785e27e8ad3f8223207104725dd4",
+            ),
+        );
+        assert_vector(
+            &params(Algorithm::Aes128, CipherMode::Cbc, AES128_KEY, iv),
+            SP800_38A_PLAINTEXT,
+            concat!(
+                "7649abac8119b246cee98e9b12e9197d",
+                "5086cb9b507219ee95db113a917678b2",
+                "73bed6b8e3c1743b7116e69e22229516",
+                "3ff1caa1681fac09120eca307586e1a7",
+            ),
+        );
+        assert_vector(
+            &params(
+                Algorithm::Aes128,
+                CipherMode::Ctr,
+                AES128_KEY,
+                "f0f1f2f3f4f5f6f7f8f9fafbfcfdfeff",
+            ),
+            SP800_38A_PLAINTEXT,
+            concat!(
+                "874d6191b620e3261bef6864990db6ce",
+                "9806f66b7970fdff8617187bb9fffdff",
+                "5ae4df3edbd5d35e5b4f09020db03eab",
+                "1e031dda2fbe03d1792170a0f3009cee",
+            ),
+        );
+        assert_vector(
+            &params(
+                Algorithm::Aes192,
+                CipherMode::Cbc,
+                "8e73b0f7da0e6452c810f32b809079e562f8ead2522c6b7b",
+                iv,
+            ),
+            SP800_38A_PLAINTEXT,
+            concat!(
+                "4f021db243bc633d7178183a9fa071e8",
+                "b4d9ada9ad7dedf4e5e738763f69145a",
+                "571b242012fb7ae07fa9baac3df102e0",
+                "08b0e27988598881d920a9e64f5615cd",
+            ),
+        );
+        assert_vector(
+            &params(
+                Algorithm::Aes256,
+                CipherMode::Ecb,
+                "603deb1015ca71be2b73aef0857d77811f352c073b6108d72d9810a30914dff4",
+                "",
+            ),
+            SP800_38A_PLAINTEXT,
+            concat!(
+                "f3eed1bdb5d2a03c064b5a7e3db181f8",
+                "591ccb10d410ed26dc5ba74a31362870",
+                "b6ed21b99ca6f4f9f153e7b1beafed1d",
+                "23304b7a39f9f3ff067d8d8f9e24ecc7",
+            ),
+        );
+    }
+
+    #[test]
+    fn aes_gcm_test_case_4() {
+        let mut params = params(
+            Algorithm::Aes128,
+            CipherMode::Gcm,
+            "feffe9928665731c6d6a8f9467308308",
+            "cafebabefacedbaddecaf888",
+        );
+        params.aad = hex::decode("feedfacedeadbeeffeedfacedeadbeefabaddad2").unwrap();
+        assert_vector(
+            &params,
+            concat!(
+                "d9313225f88406e5a55909c5aff5269a",
+                "86a7a9531534f7da2e4c303d8a318a72",
+                "1c3c0c95956809532fcf0e2449a6b525",
+                "b16aedf5aa0de657ba637b39",
+            ),
+            concat!(
+                "42831ec2217774244b7221b784d0d49c",
+                "e3aa212f2c02a4e035c17e2329aca12e",
+                "21d514b25466931c7d8f6a5aac84aa05",
+                "1ba30b396a0aac973d58e091",
+                // 认证标签
+                "5bc94fbc3221a5db94fae95ae7121a47",
+            ),
+        );
+    }
+
+    #[test]
+    fn sm4_gbt_32907_vector() {
+        let key = "0123456789abcdeffedcba9876543210";
+        assert_vector(
+            &params(Algorithm::Sm4, CipherMode::Ecb, key, ""),
+            key,
+            "681edf34d206965e86b3e94f536e4246",
+        );
+    }
+
+    #[test]
+    fn chacha20_poly1305_rfc8439_vector() {
+        let mut params = params(
+            Algorithm::ChaCha20Poly1305,
+            CipherMode::Gcm,
+            "808182838485868788898a8b8c8d8e8f909192939495969798999a9b9c9d9e9f",
+            "070000004041424344454647",
+        );
+        params.aad = hex::decode("50515253c0c1c2c3c4c5c6c7").unwrap();
+        let plaintext = b"Ladies and Gentlemen of the class of '99: \
+            If I could offer you only one tip for the future, sunscreen would be it.";
+        assert_vector(
+            &params,
+            &hex::encode(plaintext),
+            concat!(
+                "d31a8d34648e60db7b86afbc53ef7ec2",
+                "a4aded51296e08fea9e2b5a736ee62d6",
+                "3dbea45e8ca9671282fafb69da92728b",
+                "1a71de0a9e060b2905d6a5b67ecd3b36",
+                "92ddbd7f2d778b8c9803aee328091b58",
+                "fab324e4fad675945585808b4831d7bc",
+                "3ff4def08e4b7a9de576d26586cec64b",
+                "6116",
+                "1ae10b594f09e26a7e902ecbd0600691",
+            ),
+        );
+    }
+
+    #[test]
+    fn padding_round_trip() {
+        let data = b"sidecar padding!!ok";
+        let mut params = params(
+            Algorithm::Aes128,
+            CipherMode::Cbc,
+            AES128_KEY,
+            "000102030405060708090a0b0c0d0e0f",
+        );
+
+        params.padding = Padding::Pkcs7;
+        let encrypted = params.encrypt(data).unwrap();
+        assert_eq!(encrypted.len(), 32);
+        assert_eq!(params.decrypt(&encrypted).unwrap(), data);
+
+        params.padding = Padding::Zero;
+        let encrypted = params.encrypt(data).unwrap();
+        assert_eq!(encrypted.len(), 32);
+        assert_eq!(params.decrypt(&encrypted).unwrap(), data);
+
+        params.padding = Padding::None;
+        assert!(params.encrypt(data).is_err());
+        let encrypted = params.encrypt(&data[..16]).unwrap();
+        assert_eq!(encrypted.len(), 16);
+        assert_eq!(params.decrypt(&encrypted).unwrap(), &data[..16]);
+
+        // 流模式忽略填充设置
+        params.mode = CipherMode::Ctr;
+        params.padding = Padding::Pkcs7;
+        let encrypted = params.encrypt(data).unwrap();
+        assert_eq!(encrypted.len(), data.len());
+        assert_eq!(params.decrypt(&encrypted).unwrap(), data);
+    }
+
+    #[test]
+    fn wrong_tag_or_key_fails() {
+        for (algorithm, key) in [
+            (Algorithm::Aes128, AES128_KEY),
+            (
+                Algorithm::ChaCha20Poly1305,
+                "808182838485868788898a8b8c8d8e8f909192939495969798999a9b9c9d9e9f",
+            ),
+        ] {
+            let mut params = params(algorithm, CipherMode::Gcm, key, "cafebabefacedbaddecaf888");
+            params.aad = b"header".to_vec();
+            let encrypted = params.encrypt(b"attack at dawn").unwrap();
+            assert_eq!(params.decrypt(&encrypted).unwrap(), b"attack at dawn");
+
+            let mut tampered = encrypted.clone();
+            *tampered.last_mut().unwrap() ^= 1;
+            assert!(params.decrypt(&tampered).is_err(), "{}", algorithm);
+            assert!(params.decrypt(&encrypted[..8]).is_err());
+
+            let mut other = params.clone();
+            other.key[0] ^= 1;
+            assert!(other.decrypt(&encrypted).is_err(), "{}", algorithm);
+
+            let mut other = params.clone();
+            other.aad = b"Header".to_vec();
+            assert!(other.decrypt(&encrypted).is_err(), "{}", algorithm);
+        }
+    }
+
+    #[test]
+    fn random_chars_use_key_alphabet() {
+        let chars = random_chars(1000).unwrap();
+        assert_eq!(chars.len(), 1000);
+        assert!(chars.bytes().all(|b| KEY_CHARS.contains(&b)));
+    }
+}
@@ -1,4 +1,5 @@
 use std::{
+    fmt,
     fs::File,
     io::{BufReader, Read},
 };
@@ -25,9 +26,13 @@ pub struct HashResults {
     pub sha512: String,
 }
 
-impl HashResults {
-    pub fn to_string(&self) -> String {
-        format!(
+impl fmt::Display for HashResults {
+    fn fmt(
+        &self,
+        f: &mut fmt::Formatter<'_>,
+    ) -> fmt::Result {
+        write!(
+            f,
             "MD5       {}\nSHA1      {}\nSHA256    {}\nSHA512    {}",
             self.md5, self.sha1, self.sha256, self.sha512
         )
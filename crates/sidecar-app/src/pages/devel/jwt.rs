@@ -0,0 +1,835 @@
+use std::fmt;
+
+use base64::{Engine, engine::general_purpose};
+use chrono::{DateTime, Local};
+use gpui::*;
+use gpui_component::{
+    ActiveTheme, WindowExt,
+    checkbox::Checkbox,
+    input::{InputEvent, InputState},
+};
+use openssl::{
+    bn::BigNum,
+    ecdsa::EcdsaSig,
+    hash::MessageDigest,
+    memcmp,
+    nid::Nid,
+    pkey::{HasPublic, Id, PKey, Public},
+    rsa::Padding,
+    sign::{RsaPssSaltlen, Signer, Verifier},
+    x509::X509,
+};
+use serde_json::{Map, Value};
+
+use crate::{
+    MainView,
+    comps::{button, card, label, page, textarea},
+    pages::{
+        devel::crypto::asymmetric::{parse_private, parse_public},
+        utils::{format_json, strip_str},
+    },
+};
+
+/// JWS 签名算法（RFC 7518）
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum JwtAlgorithm {
+    Hs256,
+    Hs384,
+    Hs512,
+    Rs256,
+    Rs384,
+    Rs512,
+    Ps256,
+    Ps384,
+    Ps512,
+    Es256,
+    Es384,
+    Es512,
+    EdDsa,
+}
+
+impl JwtAlgorithm {
+    pub const ALL: [JwtAlgorithm; 13] = [
+        JwtAlgorithm::Hs256,
+        JwtAlgorithm::Hs384,
+        JwtAlgorithm::Hs512,
+        JwtAlgorithm::Rs256,
+        JwtAlgorithm::Rs384,
+        JwtAlgorithm::Rs512,
+        JwtAlgorithm::Ps256,
+        JwtAlgorithm::Ps384,
+        JwtAlgorithm::Ps512,
+        JwtAlgorithm::Es256,
+        JwtAlgorithm::Es384,
+        JwtAlgorithm::Es512,
+        JwtAlgorithm::EdDsa,
+    ];
+
+    pub fn from_name(name: &str) -> Option<JwtAlgorithm> {
+        Self::ALL.into_iter().find(|algorithm| algorithm.to_string() == name)
+    }
+
+    fn digest(self) -> MessageDigest {
+        match self {
+            JwtAlgorithm::Hs256 | JwtAlgorithm::Rs256 | JwtAlgorithm::Ps256 | JwtAlgorithm::Es256 => {
+                MessageDigest::sha256()
+            }
+            JwtAlgorithm::Hs384 | JwtAlgorithm::Rs384 | JwtAlgorithm::Ps384 | JwtAlgorithm::Es384 => {
+                MessageDigest::sha384()
+            }
+            JwtAlgorithm::Hs512 | JwtAlgorithm::Rs512 | JwtAlgorithm::Ps512 | JwtAlgorithm::Es512 => {
+                MessageDigest::sha512()
+            }
+            JwtAlgorithm::EdDsa => MessageDigest::null(),
+        }
+    }
+
+    pub fn is_hmac(self) -> bool {
+        matches!(self, JwtAlgorithm::Hs256 | JwtAlgorithm::Hs384 | JwtAlgorithm::Hs512)
+    }
+
+    fn is_pss(self) -> bool {
+        matches!(self, JwtAlgorithm::Ps256 | JwtAlgorithm::Ps384 | JwtAlgorithm::Ps512)
+    }
+
+    /// ECDSA 签名中 r、s 各自的字节长度，同时也限定了曲线（P-256/P-384/P-521）
+    fn ec_size(self) -> Option<(usize, Nid)> {
+        match self {
+            JwtAlgorithm::Es256 => Some((32, Nid::X9_62_PRIME256V1)),
+            JwtAlgorithm::Es384 => Some((48, Nid::SECP384R1)),
+            JwtAlgorithm::Es512 => Some((66, Nid::SECP521R1)),
+            _ => None,
+        }
+    }
+
+    /// 检查密钥类型是否与算法匹配
+    fn check_key<T: HasPublic>(
+        self,
+        key: &PKey<T>,
+    ) -> Result<(), String> {
+        let ok = match self {
+            JwtAlgorithm::EdDsa => key.id() == Id::ED25519,
+            _ if self.ec_size().is_some() => {
+                let curve = key.ec_key().ok().and_then(|ec| ec.group().curve_name());
+                curve.is_some() && self.ec_size().map(|(_, nid)| nid) == curve
+            }
+            _ => key.id() == Id::RSA,
+        };
+        if ok {
+            Ok(())
+        } else {
+            Err(format!("密钥类型与算法 {} 不匹配", self))
+        }
+    }
+}
+
+impl fmt::Display for JwtAlgorithm {
+    fn fmt(
+        &self,
+        f: &mut fmt::Formatter<'_>,
+    ) -> fmt::Result {
+        let name = match self {
+            JwtAlgorithm::Hs256 => "HS256",
+            JwtAlgorithm::Hs384 => "HS384",
+            JwtAlgorithm::Hs512 => "HS512",
+            JwtAlgorithm::Rs256 => "RS256",
+            JwtAlgorithm::Rs384 => "RS384",
+            JwtAlgorithm::Rs512 => "RS512",
+            JwtAlgorithm::Ps256 => "PS256",
+            JwtAlgorithm::Ps384 => "PS384",
+            JwtAlgorithm::Ps512 => "PS512",
+            JwtAlgorithm::Es256 => "ES256",
+            JwtAlgorithm::Es384 => "ES384",
+            JwtAlgorithm::Es512 => "ES512",
+            JwtAlgorithm::EdDsa => "EdDSA",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+/// 拆分后的 JWT
+pub struct Jwt {
+    pub header: Value,
+    pub payload: Value,
+    /// `header.payload` 原文，签名覆盖的内容
+    pub signing_input: String,
+    pub signature: Vec<u8>,
+}
+
+impl Jwt {
+    pub fn parse(token: &str) -> Result<Jwt, String> {
+        let token = strip_str(token);
+        let parts: Vec<&str> = token.split('.').collect();
+        if parts.len() != 3 {
+            return Err(format!("令牌应由 3 段组成，实际为 {} 段", parts.len()));
+        }
+        let header = decode_segment(parts[0]).map_err(|e| format!("头部{}", e))?;
+        let payload = decode_segment(parts[1]).map_err(|e| format!("载荷{}", e))?;
+        let signature = general_purpose::URL_SAFE_NO_PAD
+            .decode(parts[2].trim_end_matches('='))
+            .map_err(|e| format!("签名 Base64URL 格式错误: {}", e))?;
+        Ok(Jwt {
+            header: serde_json::from_slice(&header).map_err(|e| format!("头部不是有效的 JSON: {}", e))?,
+            payload: serde_json::from_slice(&payload).map_err(|e| format!("载荷不是有效的 JSON: {}", e))?,
+            signing_input: format!("{}.{}", parts[0], parts[1]),
+            signature,
+        })
+    }
+
+    pub fn algorithm(&self) -> Result<JwtAlgorithm, String> {
+        header_algorithm(&self.header)
+    }
+
+    pub fn verify(
+        &self,
+        key: &str,
+        secret_base64: bool,
+    ) -> Result<bool, String> {
+        let algorithm = self.algorithm()?;
+        let data = self.signing_input.as_bytes();
+        if algorithm.is_hmac() {
+            let expected = hmac(algorithm, &secret(key, secret_base64)?, data)?;
+            return Ok(expected.len() == self.signature.len() && memcmp::eq(&expected, &self.signature));
+        }
+
+        let key = verifying_key(key)?;
+        algorithm.check_key(&key)?;
+        let signature = match algorithm.ec_size() {
+            Some((size, _)) => {
+                if self.signature.len() != size * 2 {
+                    return Ok(false);
+                }
+                ecdsa_raw_to_der(&self.signature, size)?
+            }
+            None => self.signature.clone(),
+        };
+
+        let mut verifier = if algorithm == JwtAlgorithm::EdDsa {
+            Verifier::new_without_digest(&key)
+        } else {
+            Verifier::new(algorithm.digest(), &key)
+        }
+        .map_err(|e| format!("验证失败: {}", e))?;
+        if algorithm.is_pss() {
+            verifier
+                .set_rsa_padding(Padding::PKCS1_PSS)
+                .and_then(|_| verifier.set_rsa_pss_saltlen(RsaPssSaltlen::DIGEST_LENGTH))
+                .map_err(|e| format!("验证失败: {}", e))?;
+        }
+        // 签名格式不对时 OpenSSL 会返回错误，这里统一视为不匹配
+        Ok(verifier.verify_oneshot(&signature, data).unwrap_or(false))
+    }
+}
+
+/// 用头部和载荷的 JSON 文本签发新令牌，算法取自头部的 `alg` 字段
+pub fn sign(
+    header: &str,
+    payload: &str,
+    key: &str,
+    secret_base64: bool,
+) -> Result<String, String> {
+    let header: Value = serde_json::from_str(header).map_err(|e| format!("头部不是有效的 JSON: {}", e))?;
+    let payload: Value = serde_json::from_str(payload).map_err(|e| format!("载荷不是有效的 JSON: {}", e))?;
+    if !payload.is_object() {
+        return Err("载荷必须是 JSON 对象".to_string());
+    }
+    let algorithm = header_algorithm(&header)?;
+
+    let signing_input = format!(
+        "{}.{}",
+        general_purpose::URL_SAFE_NO_PAD.encode(header.to_string()),
+        general_purpose::URL_SAFE_NO_PAD.encode(payload.to_string())
+    );
+    let data = signing_input.as_bytes();
+
+    let signature = if algorithm.is_hmac() {
+        hmac(algorithm, &secret(key, secret_base64)?, data)?
+    } else {
+        let key = parse_private(key)?;
+        algorithm.check_key(&key)?;
+        let mut signer = if algorithm == JwtAlgorithm::EdDsa {
+            Signer::new_without_digest(&key)
+        } else {
+            Signer::new(algorithm.digest(), &key)
+        }
+        .map_err(|e| format!("签名失败: {}", e))?;
+        if algorithm.is_pss() {
+            signer
+                .set_rsa_padding(Padding::PKCS1_PSS)
+                .and_then(|_| signer.set_rsa_pss_saltlen(RsaPssSaltlen::DIGEST_LENGTH))
+                .map_err(|e| format!("签名失败: {}", e))?;
+        }
+        let signature = signer
+            .sign_oneshot_to_vec(data)
+            .map_err(|e| format!("签名失败: {}", e))?;
+
+        // JWS 中的 ECDSA 签名是定长的 r || s，而不是 OpenSSL 输出的 DER
+        match algorithm.ec_size() {
+            Some((size, _)) => ecdsa_der_to_raw(&signature, size)?,
+            None => signature,
+        }
+    };
+
+    Ok(format!(
+        "{}.{}",
+        signing_input,
+        general_purpose::URL_SAFE_NO_PAD.encode(signature)
+    ))
+}
+
+/// 把载荷中的 `exp`/`iat`/`nbf` 转换为本地时间，附带当前是否有效的说明
+pub fn claim_times(payload: &Value) -> Vec<(&'static str, String)> {
+    let Some(claims) = payload.as_object() else {
+        return vec![];
+    };
+    let now = Local::now().timestamp();
+    [
+        ("exp", "过期时间 (exp)"),
+        ("iat", "签发时间 (iat)"),
+        ("nbf", "生效时间 (nbf)"),
+    ]
+    .into_iter()
+    .filter_map(|(name, title)| {
+        let value = claims.get(name)?;
+        let text = match value.as_i64().or_else(|| value.as_f64().map(|v| v as i64)) {
+            Some(secs) => match DateTime::from_timestamp(secs, 0) {
+                Some(time) => {
+                    let status = match name {
+                        "exp" if secs <= now => "（已过期）",
+                        "exp" => "（未过期）",
+                        "nbf" if secs > now => "（尚未生效）",
+                        "iat" if secs > now => "（晚于当前时间）",
+                        _ => "",
+                    };
+                    format!("{}{}", time.with_timezone(&Local).format("%Y-%m-%d %H:%M:%S"), status)
+                }
+                None => format!("时间超出范围: {}", secs),
+            },
+            None => format!("不是数字时间戳: {}", value),
+        };
+        Some((title, text))
+    })
+    .collect()
+}
+
+fn decode_segment(segment: &str) -> Result<Vec<u8>, String> {
+    general_purpose::URL_SAFE_NO_PAD
+        .decode(segment.trim_end_matches('='))
+        .map_err(|e| format!(" Base64URL 格式错误: {}", e))
+}
+
+fn header_algorithm(header: &Value) -> Result<JwtAlgorithm, String> {
+    let name = header
+        .as_object()
+        .and_then(|header| header.get("alg"))
+        .and_then(Value::as_str)
+        .ok_or("头部缺少 alg 字段")?;
+    JwtAlgorithm::from_name(name).ok_or(format!("不支持的算法: {}", name))
+}
+
+fn secret(
+    key: &str,
+    base64: bool,
+) -> Result<Vec<u8>, String> {
+    let secret = if base64 {
+        let key = strip_str(key).replace('-', "+").replace('_', "/");
+        general_purpose::STANDARD
+            .decode(key.trim_end_matches('='))
+            .or_else(|_| general_purpose::STANDARD_NO_PAD.decode(key.trim_end_matches('=')))
+            .map_err(|e| format!("密钥 Base64 格式错误: {}", e))?
+    } else if key.trim_start().starts_with("-----BEGIN") {
+        // 令牌的 alg 由签发方决定，用公钥文本作为 HMAC 密钥会让伪造的 HS 令牌通过验证
+        return Err("HMAC 算法需要共享密钥，不能使用 PEM 格式的公钥、私钥或证书".to_string());
+    } else {
+        key.as_bytes().to_vec()
+    };
+    if secret.is_empty() {
+        return Err("密钥不能为空".to_string());
+    }
+    Ok(secret)
+}
+
+/// 定长的 `r || s` 转换为 DER 编码的 ECDSA 签名
+fn ecdsa_raw_to_der(
+    raw: &[u8],
+    size: usize,
+) -> Result<Vec<u8>, String> {
+    let r = BigNum::from_slice(&raw[..size]).map_err(|e| e.to_string())?;
+    let s = BigNum::from_slice(&raw[size..]).map_err(|e| e.to_string())?;
+    EcdsaSig::from_private_components(r, s)
+        .and_then(|sig| sig.to_der())
+        .map_err(|e| e.to_string())
+}
+
+/// DER 编码的 ECDSA 签名转换为定长的 `r || s`，r、s 各占 `size` 字节
+fn ecdsa_der_to_raw(
+    der: &[u8],
+    size: usize,
+) -> Result<Vec<u8>, String> {
+    let sig = EcdsaSig::from_der(der).map_err(|e| e.to_string())?;
+    let mut raw = sig.r().to_vec_padded(size as i32).map_err(|e| e.to_string())?;
+    raw.extend(sig.s().to_vec_padded(size as i32).map_err(|e| e.to_string())?);
+    Ok(raw)
+}
+
+fn hmac(
+    algorithm: JwtAlgorithm,
+    secret: &[u8],
+    data: &[u8],
+) -> Result<Vec<u8>, String> {
+    PKey::hmac(secret)
+        .and_then(|key| Signer::new(algorithm.digest(), &key)?.sign_oneshot_to_vec(data))
+        .map_err(|e| format!("HMAC 计算失败: {}", e))
+}
+
+/// 验证时接受公钥、私钥或 X.509 证书
+fn verifying_key(text: &str) -> Result<PKey<Public>, String> {
+    let text = text.trim();
+    if text.starts_with("-----BEGIN CERTIFICATE") {
+        return X509::from_pem(text.as_bytes())
+            .and_then(|cert| cert.public_key())
+            .map_err(|e| format!("无法解析证书: {}", e));
+    }
+    parse_public(text).or_else(|err| {
+        let key = parse_private(text).map_err(|_| err)?;
+        key.public_key_to_der()
+            .and_then(|der| PKey::public_key_from_der(&der))
+            .map_err(|e| e.to_string())
+    })
+}
+
+pub struct JwtPage {
+    error: Option<String>,
+    secret_base64: bool,
+    verified: Option<Result<bool, String>>,
+    claims: Vec<(&'static str, String)>,
+    token: Entity<InputState>,
+    header: Entity<InputState>,
+    payload: Entity<InputState>,
+    key: Entity<InputState>,
+    _subs: Vec<Subscription>,
+}
+
+impl JwtPage {
+    pub fn build(
+        window: &mut Window,
+        cx: &mut Context<MainView>,
+    ) -> AnyView {
+        AnyView::from(cx.new(|cx| {
+            let token = cx.new(|cx| {
+                InputState::new(window, cx)
+                    .multi_line(true)
+                    .placeholder("粘贴 JWT 令牌，例如 eyJhbGciOi...")
+            });
+            let header = cx.new(|cx| {
+                let mut state = InputState::new(window, cx).code_editor("json");
+                state.set_value("{\n  \"alg\": \"HS256\",\n  \"typ\": \"JWT\"\n}", window, cx);
+                state
+            });
+            let payload = cx.new(|cx| InputState::new(window, cx).code_editor("json"));
+            let key = cx.new(|cx| {
+                InputState::new(window, cx)
+                    .multi_line(true)
+                    .placeholder("HS* 填写共享密钥；其他算法填写 PEM 格式的私钥（签名）或公钥/证书（验证）")
+            });
+            let subs = vec![cx.subscribe_in(&token, window, Self::on_token_event)];
+
+            Self {
+                error: None,
+                secret_base64: false,
+                verified: None,
+                claims: vec![],
+                token,
+                header,
+                payload,
+                key,
+                _subs: subs,
+            }
+        }))
+    }
+
+    fn on_token_event(
+        &mut self,
+        _state: &Entity<InputState>,
+        ev: &InputEvent,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        if let InputEvent::Change = ev {
+            self.decode(window, cx);
+        }
+    }
+
+    fn decode(
+        &mut self,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.verified = None;
+        let token = self.token.read(cx).value().to_string();
+        if token.trim().is_empty() {
+            self.error = None;
+            self.claims.clear();
+            cx.notify();
+            return;
+        }
+
+        match Jwt::parse(&token) {
+            Ok(jwt) => {
+                let header = format_json(&jwt.header.to_string()).unwrap_or_default();
+                let payload = format_json(&jwt.payload.to_string()).unwrap_or_default();
+                self.header.update(cx, |state, cx| state.set_value(header, window, cx));
+                self.payload
+                    .update(cx, |state, cx| state.set_value(payload, window, cx));
+                self.claims = claim_times(&jwt.payload);
+                self.error = None;
+            }
+            Err(err) => {
+                self.claims.clear();
+                self.error = Some(err);
+            }
+        }
+        cx.notify();
+    }
+
+    fn verify(
+        &mut self,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let token = self.token.read(cx).value().to_string();
+        let key = self.key.read(cx).value().to_string();
+        if token.trim().is_empty() {
+            window.push_notification("请先输入令牌", cx);
+            return;
+        }
+        self.verified = Some(Jwt::parse(&token).and_then(|jwt| jwt.verify(&key, self.secret_base64)));
+        cx.notify();
+    }
+
+    fn sign(
+        &mut self,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let header = self.header.read(cx).value().to_string();
+        let payload = self.payload.read(cx).value().to_string();
+        let key = self.key.read(cx).value().to_string();
+        // 空载荷按空对象处理，方便从头签发
+        let payload = if payload.trim().is_empty() {
+            Value::Object(Map::new()).to_string()
+        } else {
+            payload
+        };
+
+        match sign(&header, &payload, &key, self.secret_base64) {
+            Ok(token) => {
+                self.token.update(cx, |state, cx| state.set_value(token, window, cx));
+                self.decode(window, cx);
+                self.verified = Some(Ok(true));
+                window.push_notification("已生成新令牌", cx);
+            }
+            Err(err) => window.push_notification(err, cx),
+        }
+        cx.notify();
+    }
+}
+
+impl Render for JwtPage {
+    fn render(
+        &mut self,
+        _window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> impl IntoElement {
+        let theme = cx.theme();
+        let error = self.error.as_ref().map(|err| {
+            div()
+                .px_4()
+                .py_2()
+                .rounded_lg()
+                .bg(theme.danger)
+                .text_sm()
+                .text_color(theme.danger_foreground)
+                .child(format!("解析失败：{}", err))
+        });
+        let verified = self.verified.as_ref().map(|verified| {
+            let (text, color) = match verified {
+                Ok(true) => ("签名有效".to_string(), theme.success),
+                Ok(false) => ("签名无效".to_string(), theme.danger),
+                Err(err) => (err.clone(), theme.danger),
+            };
+            div().text_sm().text_color(color).child(text)
+        });
+
+        page()
+            .size_full()
+            .child(
+                card()
+                    .h_32()
+                    .child(label("令牌"))
+                    .child(textarea(&self.token, |input| input.font_family("monospace"))),
+            )
+            .children(error)
+            .child(
+                div()
+                    .flex_1()
+                    .flex()
+                    .gap_4()
+                    .min_h_0()
+                    .child(
+                        card()
+                            .flex_1()
+                            .child(label("头部"))
+                            .child(textarea(&self.header, |input| input)),
+                    )
+                    .child(
+                        card()
+                            .flex_1()
+                            .child(label("载荷"))
+                            .child(textarea(&self.payload, |input| input))
+                            .children(self.claims.iter().map(|(title, text)| {
+                                div()
+                                    .flex()
+                                    .gap_4()
+                                    .text_sm()
+                                    .child(div().w_32().text_color(rgb(0x808080)).child(*title))
+                                    .child(div().text_color(white()).child(text.clone()))
+                            })),
+                    ),
+            )
+            .child(
+                card()
+                    .child(
+                        div()
+                            .flex()
+                            .gap_4()
+                            .items_center()
+                            .child(label("密钥"))
+                            .child(
+                                Checkbox::new("jwt-secret-base64")
+                                    .label("HMAC 密钥为 Base64")
+                                    .checked(self.secret_base64)
+                                    .on_click(cx.listener(|this, checked: &bool, _window, cx| {
+                                        this.secret_base64 = *checked;
+                                        this.verified = None;
+                                        cx.notify();
+                                    })),
+                            )
+                            .child(
+                                div()
+                                    .text_sm()
+                                    .text_color(rgb(0x808080))
+                                    .child("算法取自头部的 alg 字段"),
+                            )
+                            .child(div().flex_1())
+                            .children(verified)
+                            .child(button(cx, "jwt-verify").label("验证签名").on_click(cx.listener(
+                                |this, _ev, window, cx| {
+                                    this.verify(window, cx);
+                                },
+                            )))
+                            .child(button(cx, "jwt-sign").label("签名生成").on_click(cx.listener(
+                                |this, _ev, window, cx| {
+                                    this.sign(window, cx);
+                                },
+                            ))),
+                    )
+                    .child(
+                        div()
+                            .h_24()
+                            .flex()
+                            .child(textarea(&self.key, |input| input.font_family("monospace"))),
+                    ),
+            )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use base64::{Engine, engine::general_purpose};
+    use chrono::Local;
+    use openssl::{
+        ec::{EcGroup, EcKey},
+        ecdsa::EcdsaSig,
+        nid::Nid,
+        pkey::{PKey, Private},
+        rsa::Rsa,
+    };
+    use serde_json::json;
+
+    use super::{Jwt, JwtAlgorithm, claim_times, ecdsa_der_to_raw, ecdsa_raw_to_der, hmac, sign};
+
+    /// RFC 7515 附录 A.1 的 HS256 示例
+    const RFC7515_TOKEN: &str = concat!(
+        "eyJ0eXAiOiJKV1QiLA0KICJhbGciOiJIUzI1NiJ9",
+        ".eyJpc3MiOiJqb2UiLA0KICJleHAiOjEzMDA4MTkzODAsDQogImh0dHA6Ly9leGFtcGxlLmNvbS9pc19yb290Ijp0cnVlfQ",
+        ".dBjftJeZ4CVP-mB92K27uhbUJU1p1r_wW1gFWFOEjXk",
+    );
+    const RFC7515_KEY: &str = "AyM1SysPpbyDfgZld3umj1qzKObwVMkoqQ-EstJQLr_T-1qS0gZH75aKtMN3Yj0iPS4hcgUuTwjAzZr1Z9CAow";
+
+    fn ec_key(nid: Nid) -> PKey<Private> {
+        let group = EcGroup::from_curve_name(nid).unwrap();
+        PKey::from_ec_key(EcKey::generate(&group).unwrap()).unwrap()
+    }
+
+    fn pem(key: &PKey<Private>) -> (String, String) {
+        (
+            String::from_utf8(key.private_key_to_pem_pkcs8().unwrap()).unwrap(),
+            String::from_utf8(key.public_key_to_pem().unwrap()).unwrap(),
+        )
+    }
+
+    fn header(algorithm: JwtAlgorithm) -> String {
+        json!({ "alg": algorithm.to_string(), "typ": "JWT" }).to_string()
+    }
+
+    #[test]
+    fn rfc7515_hs256_example() {
+        let jwt = Jwt::parse(RFC7515_TOKEN).unwrap();
+        assert_eq!(jwt.algorithm(), Ok(JwtAlgorithm::Hs256));
+        assert_eq!(jwt.payload["iss"], "joe");
+        assert_eq!(jwt.payload["exp"], 1300819380);
+        assert_eq!(jwt.verify(RFC7515_KEY, true), Ok(true));
+        assert_eq!(jwt.verify("wrong", false), Ok(false));
+    }
+
+    #[test]
+    fn tampered_payload_is_rejected() {
+        let payload = general_purpose::URL_SAFE_NO_PAD.encode(r#"{"iss":"joe","exp":1300819380,"admin":true}"#);
+        let parts: Vec<&str> = RFC7515_TOKEN.split('.').collect();
+        let tampered = format!("{}.{}.{}", parts[0], payload, parts[2]);
+        let jwt = Jwt::parse(&tampered).unwrap();
+        assert_eq!(jwt.payload["admin"], true);
+        assert_eq!(jwt.verify(RFC7515_KEY, true), Ok(false));
+    }
+
+    #[test]
+    fn sign_and_verify_each_algorithm() {
+        let rsa = PKey::from_rsa(Rsa::generate(2048).unwrap()).unwrap();
+        let keys = [
+            (JwtAlgorithm::Rs256, rsa.clone()),
+            (JwtAlgorithm::Rs384, rsa.clone()),
+            (JwtAlgorithm::Rs512, rsa.clone()),
+            (JwtAlgorithm::Ps256, rsa.clone()),
+            (JwtAlgorithm::Ps384, rsa.clone()),
+            (JwtAlgorithm::Ps512, rsa),
+            (JwtAlgorithm::Es256, ec_key(Nid::X9_62_PRIME256V1)),
+            (JwtAlgorithm::Es384, ec_key(Nid::SECP384R1)),
+            (JwtAlgorithm::Es512, ec_key(Nid::SECP521R1)),
+            (JwtAlgorithm::EdDsa, PKey::generate_ed25519().unwrap()),
+        ];
+        let payload = json!({ "sub": "sidecar" }).to_string();
+
+        for algorithm in [JwtAlgorithm::Hs256, JwtAlgorithm::Hs384, JwtAlgorithm::Hs512] {
+            let token = sign(&header(algorithm), &payload, "secret", false).unwrap();
+            let jwt = Jwt::parse(&token).unwrap();
+            assert_eq!(jwt.algorithm(), Ok(algorithm));
+            assert_eq!(jwt.verify("secret", false), Ok(true));
+            assert_eq!(jwt.verify("secret2", false), Ok(false));
+        }
+
+        for (algorithm, key) in keys {
+            let (private, public) = pem(&key);
+            let token = sign(&header(algorithm), &payload, &private, false).unwrap();
+            let jwt = Jwt::parse(&token).unwrap();
+            assert_eq!(jwt.algorithm(), Ok(algorithm));
+            if let Some((size, _)) = algorithm.ec_size() {
+                assert_eq!(jwt.signature.len(), size * 2, "{}", algorithm);
+            }
+            assert_eq!(jwt.verify(&public, false), Ok(true), "{}", algorithm);
+            // 私钥同样可以用于验证
+            assert_eq!(jwt.verify(&private, false), Ok(true), "{}", algorithm);
+
+            let (_, other) = pem(&match algorithm.ec_size() {
+                Some((_, nid)) => ec_key(nid),
+                None if algorithm == JwtAlgorithm::EdDsa => PKey::generate_ed25519().unwrap(),
+                None => PKey::from_rsa(Rsa::generate(2048).unwrap()).unwrap(),
+            });
+            assert_eq!(jwt.verify(&other, false), Ok(false), "{}", algorithm);
+        }
+    }
+
+    #[test]
+    fn key_must_match_algorithm() {
+        let payload = json!({ "sub": "sidecar" }).to_string();
+        let (p256, _) = pem(&ec_key(Nid::X9_62_PRIME256V1));
+        assert!(sign(&header(JwtAlgorithm::Es384), &payload, &p256, false).is_err());
+        assert!(sign(&header(JwtAlgorithm::Rs256), &payload, &p256, false).is_err());
+        // secp256k1 也是 256 位，但 ES256 只允许 P-256
+        let (k256, _) = pem(&ec_key(Nid::SECP256K1));
+        assert!(sign(&header(JwtAlgorithm::Es256), &payload, &k256, false).is_err());
+
+        let token = sign(&header(JwtAlgorithm::Es256), &payload, &p256, false).unwrap();
+        let (_, p384) = pem(&ec_key(Nid::SECP384R1));
+        assert!(Jwt::parse(&token).unwrap().verify(&p384, false).is_err());
+    }
+
+    #[test]
+    fn ecdsa_signature_conversion() {
+        for (algorithm, nid) in [
+            (JwtAlgorithm::Es256, Nid::X9_62_PRIME256V1),
+            (JwtAlgorithm::Es384, Nid::SECP384R1),
+            (JwtAlgorithm::Es512, Nid::SECP521R1),
+        ] {
+            let (size, _) = algorithm.ec_size().unwrap();
+            let key = ec_key(nid);
+            let der = EcdsaSig::sign(b"digest", key.ec_key().unwrap().as_ref())
+                .unwrap()
+                .to_der()
+                .unwrap();
+            let raw = ecdsa_der_to_raw(&der, size).unwrap();
+            assert_eq!(raw.len(), size * 2);
+            assert_eq!(ecdsa_raw_to_der(&raw, size).unwrap(), der);
+        }
+
+        // 较短的 r、s 需要在前面补 0，ES512 的每个分量为 66 字节
+        let mut raw = vec![0u8; 132];
+        raw[65] = 1;
+        raw[131] = 2;
+        let der = ecdsa_raw_to_der(&raw, 66).unwrap();
+        assert_eq!(der, [0x30, 0x06, 0x02, 0x01, 0x01, 0x02, 0x01, 0x02]);
+        assert_eq!(ecdsa_der_to_raw(&der, 66).unwrap(), raw);
+    }
+
+    #[test]
+    fn hmac_with_public_key_is_refused() {
+        let rsa = PKey::from_rsa(Rsa::generate(2048).unwrap()).unwrap();
+        let (private, public) = pem(&rsa);
+        let payload = json!({ "sub": "admin" }).to_string();
+
+        // 攻击者用公开的公钥文本作为 HMAC 密钥伪造 HS256 令牌
+        let signing_input = format!(
+            "{}.{}",
+            general_purpose::URL_SAFE_NO_PAD.encode(header(JwtAlgorithm::Hs256)),
+            general_purpose::URL_SAFE_NO_PAD.encode(&payload)
+        );
+        let forged = hmac(JwtAlgorithm::Hs256, public.as_bytes(), signing_input.as_bytes()).unwrap();
+        let token = format!("{}.{}", signing_input, general_purpose::URL_SAFE_NO_PAD.encode(forged));
+
+        let jwt = Jwt::parse(&token).unwrap();
+        assert!(jwt.verify(&public, false).is_err());
+        assert!(jwt.verify(&format!("\n{}", public), false).is_err());
+        assert!(sign(&header(JwtAlgorithm::Hs256), &payload, &private, false).is_err());
+    }
+
+    #[test]
+    fn claim_time_status() {
+        let now = Local::now().timestamp();
+        let claims = claim_times(&json!({ "exp": now - 60, "iat": now + 3600, "nbf": now + 3600 }));
+        assert_eq!(claims.len(), 3);
+        assert_eq!(claims[0].0, "过期时间 (exp)");
+        assert!(claims[0].1.ends_with("（已过期）"), "{}", claims[0].1);
+        assert!(claims[1].1.ends_with("（晚于当前时间）"), "{}", claims[1].1);
+        assert!(claims[2].1.ends_with("（尚未生效）"), "{}", claims[2].1);
+
+        let claims = claim_times(&json!({ "exp": (now + 3600) as f64, "iat": now - 60, "nbf": now - 60 }));
+        assert!(claims[0].1.ends_with("（未过期）"), "{}", claims[0].1);
+        assert!(!claims[1].1.contains('（'), "{}", claims[1].1);
+        assert!(!claims[2].1.contains('（'), "{}", claims[2].1);
+
+        let claims = claim_times(&json!({ "exp": "tomorrow", "nbf": i64::MAX }));
+        assert_eq!(claims[0].1, "不是数字时间戳: \"tomorrow\"");
+        assert_eq!(claims[1].1, format!("时间超出范围: {}", i64::MAX));
+        assert!(claim_times(&json!([1, 2])).is_empty());
+    }
+}
@@ -1,19 +1,165 @@
+pub mod payload;
+
 use std::{
-    io::{Error, ErrorKind},
-    path::PathBuf,
+    fmt, fs,
+    io::{Cursor, Error, ErrorKind},
+    sync::Arc,
+    time::Duration,
 };
 
 use gpui::*;
-use gpui_component::input::InputState;
-use image;
+use gpui_component::{
+    WindowExt,
+    checkbox::Checkbox,
+    input::{InputEvent, InputState},
+    radio::RadioGroup,
+};
+use image::{self, DynamicImage, ImageFormat, Rgba, RgbaImage};
+use qrcode::{Color, EcLevel, QrCode};
 use rqrr::PreparedImage;
+use rxing::{
+    BarcodeFormat, DecodeHintValue, DecodeHints, EncodeHintValue, EncodeHints, MultiFormatWriter, Writer,
+    datamatrix::encoder::SymbolShapeHint, helpers::detect_multiple_in_image_with_hints,
+};
 
 use crate::{
     MainView,
     comps::{button, card, label, page, textarea},
 };
+use payload::{Contact, Event, Payload, WifiSecurity, parse_datetime};
+
+/// 一维条码生成图片时的高度，单位为模块
+const LINEAR_HEIGHT: u32 = 50;
+/// 生成图片的像素数上限，超出时自动缩小模块大小
+const MAX_IMAGE_PIXELS: u64 = 4096 * 4096;
+/// 输入停止变化后再生成预览
+const PREVIEW_DELAY: Duration = Duration::from_millis(200);
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Symbology {
+    QrCode,
+    Code128,
+    Ean13,
+    Code39,
+    DataMatrix,
+}
+
+impl Symbology {
+    pub const ALL: [Symbology; 5] = [
+        Symbology::QrCode,
+        Symbology::Code128,
+        Symbology::Ean13,
+        Symbology::Code39,
+        Symbology::DataMatrix,
+    ];
+
+    /// 除二维码外由 rxing 处理的格式
+    fn format(self) -> Option<BarcodeFormat> {
+        match self {
+            Symbology::QrCode => None,
+            Symbology::Code128 => Some(BarcodeFormat::CODE_128),
+            Symbology::Ean13 => Some(BarcodeFormat::EAN_13),
+            Symbology::Code39 => Some(BarcodeFormat::CODE_39),
+            Symbology::DataMatrix => Some(BarcodeFormat::DATA_MATRIX),
+        }
+    }
+
+    fn from_format(format: &BarcodeFormat) -> Option<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|symbology| symbology.format().as_ref() == Some(format))
+    }
+}
+
+impl fmt::Display for Symbology {
+    fn fmt(
+        &self,
+        f: &mut fmt::Formatter<'_>,
+    ) -> fmt::Result {
+        let name = match self {
+            Symbology::QrCode => "QR Code",
+            Symbology::Code128 => "Code 128",
+            Symbology::Ean13 => "EAN-13",
+            Symbology::Code39 => "Code 39",
+            Symbology::DataMatrix => "Data Matrix",
+        };
+        write!(f, "{}", name)
+    }
+}
 
-pub fn parse_qrcode<P: AsRef<std::path::Path>>(path: P) -> std::io::Result<String> {
+/// 图片中识别到的一个条码，每个条码单独记录解析结果
+#[derive(Clone, Debug)]
+pub struct DecodedBarcode {
+    pub symbology: Symbology,
+    /// 定位点在图片中的坐标，二维码为四个角，一维条码为扫描线的两端
+    pub bounds: Vec<(i32, i32)>,
+    pub content: Result<String, String>,
+}
+
+impl DecodedBarcode {
+    /// 外接矩形的左上角和右下角
+    pub fn rect(&self) -> ((i32, i32), (i32, i32)) {
+        let xs = self.bounds.iter().map(|(x, _)| *x);
+        let ys = self.bounds.iter().map(|(_, y)| *y);
+        (
+            (xs.clone().min().unwrap_or(0), ys.clone().min().unwrap_or(0)),
+            (xs.max().unwrap_or(0), ys.max().unwrap_or(0)),
+        )
+    }
+}
+
+/// 识别并解析图片中的所有条码，按从上到下、从左到右排序
+pub fn decode_barcodes(img: &DynamicImage) -> Vec<DecodedBarcode> {
+    // 二维码使用 rqrr，能够单独报告每个二维码的解析错误
+    let mut prepared = PreparedImage::prepare(img.to_luma8());
+    let mut decoded: Vec<DecodedBarcode> = prepared
+        .detect_grids()
+        .into_iter()
+        .map(|grid| DecodedBarcode {
+            symbology: Symbology::QrCode,
+            bounds: grid.bounds.iter().map(|p| (p.x, p.y)).collect(),
+            content: grid.decode().map(|(_, content)| content).map_err(|e| e.to_string()),
+        })
+        .collect();
+
+    // 其他格式交给 rxing，它只返回解析成功的结果
+    let formats = Symbology::ALL
+        .iter()
+        .filter_map(|symbology| symbology.format())
+        .collect();
+    let mut hints = DecodeHints::default().with(DecodeHintValue::PossibleFormats(formats));
+    if let Ok(results) = detect_multiple_in_image_with_hints(img.clone(), &mut hints) {
+        decoded.extend(results.iter().filter_map(|result| {
+            Some(DecodedBarcode {
+                symbology: Symbology::from_format(result.getBarcodeFormat())?,
+                bounds: result.getPoints().iter().map(|p| (p.x as i32, p.y as i32)).collect(),
+                content: Ok(result.getText().to_string()),
+            })
+        }));
+    }
+
+    decoded.sort_by_key(|code| {
+        let ((x, y), _) = code.rect();
+        (y, x)
+    });
+    decoded
+}
+
+fn decode_image(
+    img: &DynamicImage,
+    name: &str,
+) -> std::io::Result<Vec<DecodedBarcode>> {
+    let decoded = decode_barcodes(img);
+    if decoded.is_empty() {
+        return Err(Error::new(
+            ErrorKind::InvalidInput,
+            format!("can not find barcode in image: {}", name),
+        ));
+    }
+    Ok(decoded)
+}
+
+pub fn parse_qrcode<P: AsRef<std::path::Path>>(path: P) -> std::io::Result<Vec<DecodedBarcode>> {
     let p = path.as_ref();
     // 读取图片
     let img = match image::open(p) {
@@ -26,33 +172,364 @@ pub fn parse_qrcode<P: AsRef<std::path::Path>>(path: P) -> std::io::Result<Strin
         }
     };
 
-    let img = img.to_luma8();
-    let mut img = PreparedImage::prepare(img);
+    decode_image(&img, &p.display().to_string())
+}
 
-    let grids = img.detect_grids();
-    if grids.is_empty() {
-        return Err(Error::new(
-            ErrorKind::InvalidInput,
-            format!("can not find qrcode in image: {}", p.display()),
-        ));
-    }
+/// 解析内存中的图片，例如剪贴板中的截图
+pub fn parse_qrcode_bytes(bytes: &[u8]) -> std::io::Result<Vec<DecodedBarcode>> {
+    let img = image::load_from_memory(bytes)
+        .map_err(|e| Error::new(ErrorKind::InvalidInput, format!("can not open image: {}", e)))?;
+    decode_image(&img, "clipboard")
+}
 
-    let (_, content) = match grids[0].decode() {
-        Ok(decoded) => decoded,
-        Err(e) => {
-            return Err(Error::new(
-                ErrorKind::InvalidInput,
-                format!("can not decode qrcode in image: {} {}", p.display(), e),
-            ));
+#[derive(Clone, Debug)]
+pub struct QrcodeStyle {
+    pub ec_level: EcLevel,
+    /// 每个模块的像素大小
+    pub module_size: u32,
+    /// 四周留白的模块数
+    pub margin: u32,
+    pub dark: Rgba<u8>,
+    pub light: Rgba<u8>,
+}
+
+impl Default for QrcodeStyle {
+    fn default() -> Self {
+        Self {
+            ec_level: EcLevel::M,
+            module_size: 8,
+            margin: 4,
+            dark: Rgba([0, 0, 0, 255]),
+            light: Rgba([255, 255, 255, 255]),
         }
+    }
+}
+
+/// 解析 `#rrggbb` 或 `#rrggbbaa` 格式的颜色
+pub fn parse_color(s: &str) -> Option<Rgba<u8>> {
+    let bytes = hex::decode(s.trim().trim_start_matches('#')).ok()?;
+    match bytes.as_slice() {
+        [r, g, b] => Some(Rgba([*r, *g, *b, 255])),
+        [r, g, b, a] => Some(Rgba([*r, *g, *b, *a])),
+        _ => None,
+    }
+}
+
+/// 条码的模块矩阵，一维条码只有一行
+struct Modules {
+    width: u32,
+    height: u32,
+    dark: Vec<bool>,
+}
+
+impl Modules {
+    fn is_dark(
+        &self,
+        x: u32,
+        y: u32,
+    ) -> bool {
+        self.dark[(y * self.width + x) as usize]
+    }
+}
+
+fn encode_modules(
+    text: &str,
+    symbology: Symbology,
+    style: &QrcodeStyle,
+) -> std::io::Result<Modules> {
+    let Some(format) = symbology.format() else {
+        let code = QrCode::with_error_correction_level(text.as_bytes(), style.ec_level)
+            .map_err(|e| Error::new(ErrorKind::InvalidInput, format!("can not encode qrcode: {}", e)))?;
+        return Ok(Modules {
+            width: code.width() as u32,
+            height: code.width() as u32,
+            dark: code.to_colors().into_iter().map(|c| c == Color::Dark).collect(),
+        });
     };
 
-    Ok(content)
+    // 留白由 QrcodeStyle 控制，rxing 不再额外添加
+    let hints = EncodeHints::default()
+        .with(EncodeHintValue::Margin("0".to_string()))
+        .with(EncodeHintValue::DataMatrixShape(SymbolShapeHint::FORCE_SQUARE));
+    let matrix = MultiFormatWriter
+        .encode_with_hints(text, &format, 0, 0, &hints)
+        .map_err(|e| Error::new(ErrorKind::InvalidInput, format!("can not encode {}: {}", symbology, e)))?;
+    let (width, height) = (matrix.width(), matrix.height());
+    Ok(Modules {
+        width,
+        height,
+        dark: (0..height)
+            .flat_map(|y| (0..width).map(move |x| (x, y)))
+            .map(|(x, y)| matrix.get(x, y))
+            .collect(),
+    })
+}
+
+pub fn encode_barcode(
+    text: &str,
+    symbology: Symbology,
+    style: &QrcodeStyle,
+) -> std::io::Result<RgbaImage> {
+    let modules = encode_modules(text, symbology, style)?;
+
+    let width = modules.width;
+    // 一维条码只有一行，纵向拉伸到固定高度
+    let height = if modules.height == 1 {
+        LINEAR_HEIGHT
+    } else {
+        modules.height
+    };
+    let module_size = fit_module_size(width + style.margin * 2, height + style.margin * 2, style.module_size)?;
+    let (w, h) = (
+        (width + style.margin * 2) * module_size,
+        (height + style.margin * 2) * module_size,
+    );
+
+    Ok(RgbaImage::from_fn(w, h, |x, y| {
+        let (mx, my) = (x / module_size, y / module_size);
+        let inside =
+            (style.margin..style.margin + width).contains(&mx) && (style.margin..style.margin + height).contains(&my);
+        let row = if modules.height == 1 {
+            0
+        } else {
+            my.wrapping_sub(style.margin)
+        };
+        if inside && modules.is_dark(mx - style.margin, row) {
+            style.dark
+        } else {
+            style.light
+        }
+    }))
+}
+
+/// 在像素数上限内尽量保持指定的模块大小
+fn fit_module_size(
+    cols: u32,
+    rows: u32,
+    module_size: u32,
+) -> std::io::Result<u32> {
+    let max = ((MAX_IMAGE_PIXELS / (cols as u64 * rows as u64).max(1)) as f64).sqrt() as u32;
+    if max == 0 {
+        return Err(Error::new(ErrorKind::InvalidInput, "barcode is too large"));
+    }
+    Ok(module_size.clamp(1, max))
+}
+
+pub fn encode_barcode_png(
+    text: &str,
+    symbology: Symbology,
+    style: &QrcodeStyle,
+) -> std::io::Result<Vec<u8>> {
+    let img = encode_barcode(text, symbology, style)?;
+    let mut buf = Cursor::new(Vec::new());
+    img.write_to(&mut buf, ImageFormat::Png)
+        .map_err(|e| Error::other(format!("can not write png: {}", e)))?;
+    Ok(buf.into_inner())
+}
+
+pub fn encode_qrcode_png(
+    text: &str,
+    style: &QrcodeStyle,
+) -> std::io::Result<Vec<u8>> {
+    encode_barcode_png(text, Symbology::QrCode, style)
+}
+
+fn svg_fill(color: Rgba<u8>) -> String {
+    let [r, g, b, a] = color.0;
+    let mut fill = format!("fill=\"#{:02x}{:02x}{:02x}\"", r, g, b);
+    if a < 255 {
+        fill.push_str(&format!(" fill-opacity=\"{:.3}\"", a as f32 / 255.0));
+    }
+    fill
+}
+
+pub fn encode_barcode_svg(
+    text: &str,
+    symbology: Symbology,
+    style: &QrcodeStyle,
+) -> std::io::Result<String> {
+    let modules = encode_modules(text, symbology, style)?;
+
+    let linear = modules.height == 1;
+    let bar_height = if linear { LINEAR_HEIGHT } else { 1 };
+    // 以模块为单位绘制，`width`/`height` 决定默认的像素尺寸
+    let width = modules.width + style.margin * 2;
+    let height = if linear { LINEAR_HEIGHT } else { modules.height } + style.margin * 2;
+    let module_size = style.module_size.max(1);
+    let mut path = String::new();
+    for y in 0..modules.height {
+        for x in 0..modules.width {
+            if modules.is_dark(x, y) {
+                path.push_str(&format!(
+                    "M{},{}h1v{}h-1z",
+                    x + style.margin,
+                    y + style.margin,
+                    bar_height
+                ));
+            }
+        }
+    }
+
+    Ok(format!(
+        "<?xml version=\"1.0\" encoding=\"UTF-8\"?>
+<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{}\" height=\"{}\" viewBox=\"0 0 {width} {height}\" shape-rendering=\"crispEdges\">
+<rect width=\"{width}\" height=\"{height}\" {light}/>
+<path d=\"{path}\" {dark}/>
+</svg>
+",
+        width * module_size,
+        height * module_size,
+        light = svg_fill(style.light),
+        dark = svg_fill(style.dark),
+    ))
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum QrcodeMode {
+    Decode,
+    Encode,
+}
+
+const EC_LEVELS: [EcLevel; 4] = [EcLevel::L, EcLevel::M, EcLevel::Q, EcLevel::H];
+
+/// 生成条码时的内容模板
+#[derive(Clone, Copy, PartialEq)]
+enum Template {
+    Text,
+    Url,
+    Wifi,
+    Contact,
+    Event,
+    Geo,
+}
+
+impl Template {
+    const ALL: [Template; 6] = [
+        Template::Text,
+        Template::Url,
+        Template::Wifi,
+        Template::Contact,
+        Template::Event,
+        Template::Geo,
+    ];
+
+    fn name(self) -> &'static str {
+        match self {
+            Template::Text => "文本",
+            Template::Url => "链接",
+            Template::Wifi => "Wi-Fi",
+            Template::Contact => "名片",
+            Template::Event => "日程",
+            Template::Geo => "位置",
+        }
+    }
+}
+
+/// 各模板的输入框
+struct PayloadForm {
+    url: Entity<InputState>,
+    ssid: Entity<InputState>,
+    wifi_password: Entity<InputState>,
+    name: Entity<InputState>,
+    phone: Entity<InputState>,
+    email: Entity<InputState>,
+    org: Entity<InputState>,
+    title: Entity<InputState>,
+    website: Entity<InputState>,
+    address: Entity<InputState>,
+    summary: Entity<InputState>,
+    start: Entity<InputState>,
+    end: Entity<InputState>,
+    location: Entity<InputState>,
+    description: Entity<InputState>,
+    latitude: Entity<InputState>,
+    longitude: Entity<InputState>,
+    place: Entity<InputState>,
+}
+
+impl PayloadForm {
+    fn new(
+        window: &mut Window,
+        cx: &mut Context<QrcodePage>,
+    ) -> Self {
+        let mut input = |placeholder: &'static str| cx.new(|cx| InputState::new(window, cx).placeholder(placeholder));
+        Self {
+            url: input("https://example.com"),
+            ssid: input("网络名称"),
+            wifi_password: input("密码"),
+            name: input("姓名"),
+            phone: input("电话"),
+            email: input("邮箱"),
+            org: input("公司"),
+            title: input("职位"),
+            website: input("网址"),
+            address: input("地址"),
+            summary: input("标题"),
+            start: input("2026-01-01 09:00"),
+            end: input("2026-01-01 10:00"),
+            location: input("地点"),
+            description: input("描述"),
+            latitude: input("39.9042"),
+            longitude: input("116.4074"),
+            place: input("名称"),
+        }
+    }
+
+    fn inputs(&self) -> [&Entity<InputState>; 18] {
+        [
+            &self.url,
+            &self.ssid,
+            &self.wifi_password,
+            &self.name,
+            &self.phone,
+            &self.email,
+            &self.org,
+            &self.title,
+            &self.website,
+            &self.address,
+            &self.summary,
+            &self.start,
+            &self.end,
+            &self.location,
+            &self.description,
+            &self.latitude,
+            &self.longitude,
+            &self.place,
+        ]
+    }
+}
+
+/// 一行并排的输入框，每个输入框前带标签
+fn form_row(fields: &[(&'static str, &Entity<InputState>)]) -> Div {
+    fields
+        .iter()
+        .fold(div().flex().gap_4().items_center(), |row, (name, state)| {
+            row.child(label(*name)).child(textarea(state, |input| input))
+        })
 }
 
 pub struct QrcodePage {
-    path: Option<PathBuf>,
-    output: Entity<InputState>,
+    _subs: Vec<Subscription>,
+    mode: QrcodeMode,
+    /// 当前解析的图片，来自文件或剪贴板
+    source: Option<ImageSource>,
+    results: Vec<DecodedBarcode>,
+    decode_error: Option<String>,
+    template: Template,
+    text: Entity<InputState>,
+    form: PayloadForm,
+    wifi_security: WifiSecurity,
+    wifi_hidden: bool,
+    symbology: Symbology,
+    ec_level: EcLevel,
+    module_size: Entity<InputState>,
+    margin: Entity<InputState>,
+    dark: Entity<InputState>,
+    light: Entity<InputState>,
+    preview: Option<Arc<Image>>,
+    error: Option<String>,
+    /// 每次请求生成预览时递增，用于丢弃过期的结果
+    generation: usize,
 }
 
 impl QrcodePage {
@@ -61,12 +538,351 @@ impl QrcodePage {
         cx: &mut Context<MainView>,
     ) -> AnyView {
         AnyView::from(cx.new(|cx| {
-            let output = cx.new(|cx| InputState::new(window, cx).multi_line(true));
+            let text = cx.new(|cx| {
+                InputState::new(window, cx)
+                    .multi_line(true)
+                    .placeholder("输入文本或链接")
+            });
+            let style = QrcodeStyle::default();
+            let module_size = cx.new(|cx| InputState::new(window, cx).default_value(style.module_size.to_string()));
+            let margin = cx.new(|cx| InputState::new(window, cx).default_value(style.margin.to_string()));
+            let dark = cx.new(|cx| InputState::new(window, cx).default_value("#000000"));
+            let light = cx.new(|cx| InputState::new(window, cx).default_value("#ffffff"));
+            let form = PayloadForm::new(window, cx);
+
+            let subs = [&text, &module_size, &margin, &dark, &light]
+                .into_iter()
+                .chain(form.inputs())
+                .map(|state| cx.subscribe_in(state, window, Self::on_input_event))
+                .collect();
 
-            Self { path: None, output }
+            Self {
+                _subs: subs,
+                mode: QrcodeMode::Decode,
+                source: None,
+                results: Vec::new(),
+                decode_error: None,
+                template: Template::Text,
+                text,
+                form,
+                wifi_security: WifiSecurity::Wpa,
+                wifi_hidden: false,
+                symbology: Symbology::QrCode,
+                ec_level: style.ec_level,
+                module_size,
+                margin,
+                dark,
+                light,
+                preview: None,
+                error: None,
+                generation: 0,
+            }
         }))
     }
 
+    fn on_input_event(
+        &mut self,
+        _state: &Entity<InputState>,
+        ev: &InputEvent,
+        _window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        if let InputEvent::Change = ev {
+            self.generate(cx);
+        }
+    }
+
+    fn style(
+        &self,
+        cx: &App,
+    ) -> Result<QrcodeStyle, String> {
+        let Some(module_size) = self
+            .module_size
+            .read(cx)
+            .value()
+            .trim()
+            .parse::<u32>()
+            .ok()
+            .filter(|n| (1..=64).contains(n))
+        else {
+            return Err("模块大小需要在 1-64 之间".to_string());
+        };
+        let Some(margin) = self
+            .margin
+            .read(cx)
+            .value()
+            .trim()
+            .parse::<u32>()
+            .ok()
+            .filter(|n| *n <= 32)
+        else {
+            return Err("边距需要在 0-32 之间".to_string());
+        };
+        let Some(dark) = parse_color(&self.dark.read(cx).value()) else {
+            return Err("前景色格式错误，例如: #000000".to_string());
+        };
+        let Some(light) = parse_color(&self.light.read(cx).value()) else {
+            return Err("背景色格式错误，例如: #ffffff".to_string());
+        };
+
+        Ok(QrcodeStyle {
+            ec_level: self.ec_level,
+            module_size,
+            margin,
+            dark,
+            light,
+        })
+    }
+
+    /// 按当前模板生成写入条码的文本，主要内容为空时返回 None
+    fn content(
+        &self,
+        cx: &App,
+    ) -> Result<Option<String>, String> {
+        let value = |state: &Entity<InputState>| state.read(cx).value().trim().to_string();
+        let form = &self.form;
+        let payload = match self.template {
+            Template::Text => {
+                let text = self.text.read(cx).value().to_string();
+                (!text.is_empty()).then_some(Payload::Text(text))
+            }
+            Template::Url => {
+                let url = value(&form.url);
+                (!url.is_empty()).then_some(Payload::Url(url))
+            }
+            Template::Wifi => {
+                let ssid = value(&form.ssid);
+                if ssid.is_empty() {
+                    None
+                } else {
+                    let password = form.wifi_password.read(cx).value().to_string();
+                    if self.wifi_security != WifiSecurity::None && password.is_empty() {
+                        return Err("请输入 Wi-Fi 密码".to_string());
+                    }
+                    Some(Payload::Wifi {
+                        ssid,
+                        password,
+                        security: self.wifi_security,
+                        hidden: self.wifi_hidden,
+                    })
+                }
+            }
+            Template::Contact => {
+                let contact = Contact {
+                    name: value(&form.name),
+                    phone: value(&form.phone),
+                    email: value(&form.email),
+                    org: value(&form.org),
+                    title: value(&form.title),
+                    url: value(&form.website),
+                    address: value(&form.address),
+                };
+                (contact != Contact::default()).then_some(Payload::Contact(contact))
+            }
+            Template::Event => {
+                let summary = value(&form.summary);
+                if summary.is_empty() {
+                    None
+                } else {
+                    let Some(start) = parse_datetime(&value(&form.start)) else {
+                        return Err("开始时间格式错误，例如: 2026-01-01 09:00".to_string());
+                    };
+                    let end = match value(&form.end) {
+                        end if end.is_empty() => None,
+                        end => Some(
+                            parse_datetime(&end)
+                                .filter(|end| *end >= start)
+                                .ok_or("结束时间格式错误或早于开始时间")?,
+                        ),
+                    };
+                    Some(Payload::Event(Event {
+                        summary,
+                        start: Some(start),
+                        end,
+                        location: value(&form.location),
+                        description: value(&form.description),
+                    }))
+                }
+            }
+            Template::Geo => {
+                let (latitude, longitude) = (value(&form.latitude), value(&form.longitude));
+                if latitude.is_empty() && longitude.is_empty() {
+                    None
+                } else {
+                    let latitude = latitude
+                        .parse::<f64>()
+                        .ok()
+                        .filter(|n| (-90.0..=90.0).contains(n))
+                        .ok_or("纬度需要在 -90 到 90 之间")?;
+                    let longitude = longitude
+                        .parse::<f64>()
+                        .ok()
+                        .filter(|n| (-180.0..=180.0).contains(n))
+                        .ok_or("经度需要在 -180 到 180 之间")?;
+                    Some(Payload::Geo {
+                        latitude,
+                        longitude,
+                        query: value(&form.place),
+                    })
+                }
+            }
+        };
+        Ok(payload.map(|payload| payload.encode()))
+    }
+
+    /// 根据当前输入重新生成预览，输入停止变化后在后台编码
+    fn generate(
+        &mut self,
+        cx: &mut Context<Self>,
+    ) {
+        self.generation += 1;
+        let generation = self.generation;
+        let request = self.content(cx).and_then(|content| match content {
+            Some(text) => self.style(cx).map(|style| Some((text, style))),
+            None => Ok(None),
+        });
+        let (text, style) = match request {
+            Ok(Some(request)) => request,
+            Ok(None) => {
+                self.preview = None;
+                self.error = None;
+                cx.notify();
+                return;
+            }
+            Err(e) => {
+                self.preview = None;
+                self.error = Some(e);
+                cx.notify();
+                return;
+            }
+        };
+        let symbology = self.symbology;
+
+        cx.spawn(async move |this, cx| {
+            cx.background_executor().timer(PREVIEW_DELAY).await;
+            if this.update(cx, |this, _| this.generation != generation).unwrap_or(true) {
+                return;
+            }
+            let png = cx
+                .background_executor()
+                .spawn(async move { encode_barcode_png(&text, symbology, &style) })
+                .await;
+
+            let _ = this.update(cx, |this, cx| {
+                if this.generation != generation {
+                    return;
+                }
+                match png {
+                    Ok(png) => {
+                        this.preview = Some(Arc::new(Image::from_bytes(gpui::ImageFormat::Png, png)));
+                        this.error = None;
+                    }
+                    Err(e) => {
+                        this.preview = None;
+                        this.error = Some(format!("生成失败: {}", e));
+                    }
+                }
+                cx.notify();
+            });
+        })
+        .detach();
+    }
+
+    fn save(
+        &mut self,
+        svg: bool,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let text = match self.content(cx) {
+            Ok(Some(text)) => text,
+            Ok(None) => {
+                window.push_notification("请先输入条码内容", cx);
+                return;
+            }
+            Err(e) => {
+                window.push_notification(e, cx);
+                return;
+            }
+        };
+        let style = match self.style(cx) {
+            Ok(style) => style,
+            Err(e) => {
+                window.push_notification(e, cx);
+                return;
+            }
+        };
+        let symbology = self.symbology;
+
+        let directory = std::env::home_dir().unwrap_or_default();
+        let name = format!(
+            "{}.{}",
+            if self.symbology == Symbology::QrCode {
+                "qrcode"
+            } else {
+                "barcode"
+            },
+            if svg { "svg" } else { "png" }
+        );
+        let rx = cx.prompt_for_new_path(&directory, Some(&name));
+        cx.spawn_in(window, async move |_this, cx| {
+            if let Ok(Ok(Some(path))) = rx.await {
+                let message = cx
+                    .background_executor()
+                    .spawn(async move {
+                        let data = if svg {
+                            encode_barcode_svg(&text, symbology, &style).map(String::into_bytes)
+                        } else {
+                            encode_barcode_png(&text, symbology, &style)
+                        };
+                        match data {
+                            Ok(data) => match fs::write(&path, data) {
+                                Ok(_) => format!("已保存到 {}", path.display()),
+                                Err(e) => format!("保存失败: {}", e),
+                            },
+                            Err(e) => format!("生成失败: {}", e),
+                        }
+                    })
+                    .await;
+                let _ = cx.update(|window, cx| {
+                    window.push_notification(message, cx);
+                });
+            }
+        })
+        .detach();
+    }
+
+    fn copy_image(
+        &mut self,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let Some(preview) = self.preview.as_ref() else {
+            window.push_notification("请先输入条码内容", cx);
+            return;
+        };
+        cx.write_to_clipboard(ClipboardItem::new_image(preview));
+        window.push_notification("已复制到剪贴板", cx);
+    }
+
+    fn set_decoded(
+        &mut self,
+        result: std::io::Result<Vec<DecodedBarcode>>,
+        cx: &mut Context<Self>,
+    ) {
+        match result {
+            Ok(decoded) => {
+                self.results = decoded;
+                self.decode_error = None;
+            }
+            Err(e) => {
+                self.results.clear();
+                self.decode_error = Some(format!("解析失败: {}", e));
+            }
+        }
+        cx.notify();
+    }
+
     fn choose_file(
         &mut self,
         window: &mut Window,
@@ -84,22 +900,19 @@ impl QrcodePage {
                     if let Some(path) = paths.pop() {
                         let _ = cx.update(|_window, cx| {
                             let _ = this.update(cx, |this, _cx| {
-                                this.path = Some(path.clone());
+                                this.source = Some(path.clone().into());
                             });
                         });
 
                         let qrcode = path.clone();
-                        let output = cx
+                        let result = cx
                             .background_executor()
                             .spawn(async move { parse_qrcode(&qrcode) })
-                            .await
-                            .unwrap_or_else(|e| format!("解析失败: {}", e));
+                            .await;
 
-                        let _ = cx.update(|window, cx| {
+                        let _ = cx.update(|_window, cx| {
                             let _ = this.update(cx, |this, cx| {
-                                this.output.update(cx, |state, cx2| {
-                                    state.set_value(output, window, cx2);
-                                });
+                                this.set_decoded(result, cx);
                             });
                         });
                     }
@@ -108,11 +921,10 @@ impl QrcodePage {
                     // 忽略
                 }
                 Ok(Err(e)) => {
-                    let _ = cx.update(|window, cx| {
+                    let _ = cx.update(|_window, cx| {
                         let _ = this.update(cx, |this, cx| {
-                            this.output.update(cx, |state, cx2| {
-                                state.set_value(format!("无法打开文件选择器: {}", e), window, cx2);
-                            });
+                            this.decode_error = Some(format!("无法打开文件选择器: {}", e));
+                            cx.notify();
                         });
                     });
                 }
@@ -123,6 +935,349 @@ impl QrcodePage {
         })
         .detach();
     }
+
+    fn paste_image(
+        &mut self,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let image = cx.read_from_clipboard().and_then(|item| {
+            item.into_entries().find_map(|entry| match entry {
+                ClipboardEntry::Image(image) => Some(image),
+                _ => None,
+            })
+        });
+        let Some(image) = image else {
+            window.push_notification("剪贴板中没有图片", cx);
+            return;
+        };
+
+        let image = Arc::new(image);
+        self.source = Some(image.clone().into());
+        cx.notify();
+
+        cx.spawn_in(window, async move |this, cx| {
+            let result = cx
+                .background_executor()
+                .spawn(async move { parse_qrcode_bytes(&image.bytes) })
+                .await;
+
+            let _ = cx.update(|_window, cx| {
+                let _ = this.update(cx, |this, cx| {
+                    this.set_decoded(result, cx);
+                });
+            });
+        })
+        .detach();
+    }
+}
+
+impl QrcodePage {
+    fn render_decode(
+        &mut self,
+        cx: &mut Context<Self>,
+    ) -> Vec<Div> {
+        vec![
+            card()
+                .flex_1()
+                .child(
+                    div()
+                        .flex()
+                        .items_center()
+                        .justify_between()
+                        .gap_4()
+                        .child(label("二维码"))
+                        .child(div().flex_1())
+                        .child(button(cx, "paste-image").label("粘贴图片").on_click(cx.listener(
+                            |this, _ev, window, cx| {
+                                this.paste_image(window, cx);
+                            },
+                        )))
+                        .child(button(cx, "choose-file").label("选择文件").on_click(cx.listener(
+                            |this, _ev, window, cx| {
+                                this.choose_file(window, cx);
+                            },
+                        ))),
+                )
+                .child(
+                    div()
+                        .flex_1()
+                        .flex()
+                        .items_center()
+                        .justify_center()
+                        .min_w_0()
+                        .min_h_0()
+                        .child(if let Some(source) = &self.source {
+                            img(source.clone())
+                                .max_w_full()
+                                .max_h_full()
+                                .object_fit(ObjectFit::Contain)
+                        } else {
+                            img("")
+                        }),
+                ),
+            card().h_64().child(label("解析结果")).child(
+                div()
+                    .id("qrcode-results")
+                    .flex_1()
+                    .min_h_0()
+                    .overflow_y_scroll()
+                    .flex()
+                    .flex_col()
+                    .gap_4()
+                    .children(
+                        self.decode_error
+                            .clone()
+                            .map(|error| div().text_sm().text_color(rgb(0x808080)).child(error)),
+                    )
+                    .children(
+                        self.results
+                            .iter()
+                            .enumerate()
+                            .map(|(i, code)| Self::render_result(i, code, cx)),
+                    ),
+            ),
+        ]
+    }
+
+    /// 单个条码的解析结果，识别出的结构化内容以表格展示
+    fn render_result(
+        i: usize,
+        code: &DecodedBarcode,
+        cx: &mut Context<Self>,
+    ) -> Div {
+        let ((x0, y0), (x1, y1)) = code.rect();
+        let payload = code.content.as_ref().ok().map(|content| Payload::parse(content));
+        let header = div()
+            .flex()
+            .gap_4()
+            .items_center()
+            .text_sm()
+            .text_color(rgb(0x808080))
+            .child(format!(
+                "#{} {} 位置: ({}, {}) - ({}, {})",
+                i + 1,
+                code.symbology,
+                x0,
+                y0,
+                x1,
+                y1
+            ))
+            .children(payload.as_ref().map(|payload| payload.kind()))
+            .child(div().flex_1())
+            .children(code.content.clone().ok().map(|content| {
+                button(cx, ("qrcode-copy-result", i))
+                    .label("复制")
+                    .on_click(cx.listener(move |_this, _ev, window, cx| {
+                        cx.write_to_clipboard(ClipboardItem::new_string(content.clone()));
+                        window.push_notification("已复制到剪贴板", cx);
+                    }))
+            }));
+
+        let body = match (&code.content, &payload) {
+            (Err(e), _) => div()
+                .text_sm()
+                .text_color(rgb(0x808080))
+                .child(format!("解析失败: {}", e)),
+            (Ok(content), None | Some(Payload::Text(_))) => div().text_sm().child(content.clone()),
+            (Ok(_), Some(payload)) => {
+                div()
+                    .flex()
+                    .flex_col()
+                    .children(payload.fields().into_iter().map(|(name, value)| {
+                        div()
+                            .flex()
+                            .gap_4()
+                            .py_1()
+                            .border_b_1()
+                            .border_color(rgb(0x404040))
+                            .text_sm()
+                            .child(div().w_24().text_color(rgb(0x808080)).child(name))
+                            .child(div().flex_1().min_w_0().child(value))
+                    }))
+            }
+        };
+
+        div().flex().flex_col().gap_1().child(header).child(body)
+    }
+
+    fn render_encode(
+        &mut self,
+        cx: &mut Context<Self>,
+    ) -> Vec<Div> {
+        let ec_index = EC_LEVELS.iter().position(|level| *level == self.ec_level);
+        let symbology_index = Symbology::ALL.iter().position(|symbology| *symbology == self.symbology);
+
+        vec![
+            self.render_template(cx),
+            card()
+                .child(
+                    div().flex().gap_4().items_center().child(label("码制")).child(
+                        Symbology::ALL
+                            .iter()
+                            .fold(
+                                RadioGroup::horizontal("qrcode-symbology").selected_index(symbology_index),
+                                |group, symbology| group.child(symbology.to_string()),
+                            )
+                            .on_click(cx.listener(|this, index: &usize, _window, cx| {
+                                this.symbology = Symbology::ALL[*index];
+                                this.generate(cx);
+                            })),
+                    ),
+                )
+                .children((self.symbology == Symbology::QrCode).then(|| {
+                    div().flex().gap_4().items_center().child(label("纠错等级")).child(
+                        RadioGroup::horizontal("qrcode-ec-level")
+                            .selected_index(ec_index)
+                            .child("L (7%)")
+                            .child("M (15%)")
+                            .child("Q (25%)")
+                            .child("H (30%)")
+                            .on_click(cx.listener(|this, index: &usize, _window, cx| {
+                                this.ec_level = EC_LEVELS[*index];
+                                this.generate(cx);
+                            })),
+                    )
+                }))
+                .child(
+                    div()
+                        .flex()
+                        .gap_4()
+                        .items_center()
+                        .child(label("模块大小"))
+                        .child(textarea(&self.module_size, |input| input))
+                        .child(label("边距"))
+                        .child(textarea(&self.margin, |input| input))
+                        .child(label("前景色"))
+                        .child(textarea(&self.dark, |input| input))
+                        .child(label("背景色"))
+                        .child(textarea(&self.light, |input| input)),
+                ),
+            card()
+                .flex_1()
+                .child(
+                    div()
+                        .flex()
+                        .gap_4()
+                        .items_center()
+                        .child(label("预览"))
+                        .child(div().flex_1())
+                        .child(button(cx, "qrcode-save-png").label("保存 PNG").on_click(cx.listener(
+                            |this, _ev, window, cx| {
+                                this.save(false, window, cx);
+                            },
+                        )))
+                        .child(button(cx, "qrcode-save-svg").label("保存 SVG").on_click(cx.listener(
+                            |this, _ev, window, cx| {
+                                this.save(true, window, cx);
+                            },
+                        )))
+                        .child(button(cx, "qrcode-copy").label("复制图片").on_click(cx.listener(
+                            |this, _ev, window, cx| {
+                                this.copy_image(window, cx);
+                            },
+                        ))),
+                )
+                .children(self.error.clone().map(|error| label(error).text_color(rgb(0x808080))))
+                .child(
+                    div()
+                        .flex_1()
+                        .flex()
+                        .items_center()
+                        .justify_center()
+                        .min_w_0()
+                        .min_h_0()
+                        .children(
+                            self.preview
+                                .clone()
+                                .map(|preview| img(preview).max_w_full().max_h_full().object_fit(ObjectFit::Contain)),
+                        ),
+                ),
+        ]
+    }
+}
+
+impl QrcodePage {
+    fn render_template(
+        &mut self,
+        cx: &mut Context<Self>,
+    ) -> Div {
+        let template_index = Template::ALL.iter().position(|template| *template == self.template);
+        let security_index = WifiSecurity::ALL
+            .iter()
+            .position(|security| *security == self.wifi_security);
+        let form = &self.form;
+
+        let content = card().child(
+            div().flex().gap_4().items_center().child(label("模板")).child(
+                Template::ALL
+                    .iter()
+                    .fold(
+                        RadioGroup::horizontal("qrcode-template").selected_index(template_index),
+                        |group, template| group.child(template.name()),
+                    )
+                    .on_click(cx.listener(|this, index: &usize, _window, cx| {
+                        this.template = Template::ALL[*index];
+                        this.generate(cx);
+                    })),
+            ),
+        );
+        match self.template {
+            Template::Text => content.h_40().child(textarea(&self.text, |input| input)),
+            Template::Url => content.child(form_row(&[("链接", &form.url)])),
+            Template::Wifi => content
+                .child(form_row(&[("网络名称", &form.ssid), ("密码", &form.wifi_password)]))
+                .child(
+                    div()
+                        .flex()
+                        .gap_4()
+                        .items_center()
+                        .child(label("加密方式"))
+                        .child(
+                            WifiSecurity::ALL
+                                .iter()
+                                .fold(
+                                    RadioGroup::horizontal("qrcode-wifi-security").selected_index(security_index),
+                                    |group, security| group.child(security.to_string()),
+                                )
+                                .on_click(cx.listener(|this, index: &usize, _window, cx| {
+                                    this.wifi_security = WifiSecurity::ALL[*index];
+                                    this.generate(cx);
+                                })),
+                        )
+                        .child(
+                            Checkbox::new("qrcode-wifi-hidden")
+                                .label("隐藏网络")
+                                .checked(self.wifi_hidden)
+                                .on_click(cx.listener(|this, checked: &bool, _window, cx| {
+                                    this.wifi_hidden = *checked;
+                                    this.generate(cx);
+                                })),
+                        ),
+                ),
+            Template::Contact => content
+                .child(form_row(&[
+                    ("姓名", &form.name),
+                    ("电话", &form.phone),
+                    ("邮箱", &form.email),
+                ]))
+                .child(form_row(&[
+                    ("公司", &form.org),
+                    ("职位", &form.title),
+                    ("网址", &form.website),
+                ]))
+                .child(form_row(&[("地址", &form.address)])),
+            Template::Event => content
+                .child(form_row(&[("标题", &form.summary), ("地点", &form.location)]))
+                .child(form_row(&[("开始时间", &form.start), ("结束时间", &form.end)]))
+                .child(form_row(&[("描述", &form.description)])),
+            Template::Geo => content.child(form_row(&[
+                ("纬度", &form.latitude),
+                ("经度", &form.longitude),
+                ("名称", &form.place),
+            ])),
+        }
+    }
 }
 
 impl Render for QrcodePage {
@@ -131,46 +1286,134 @@ impl Render for QrcodePage {
         _window: &mut Window,
         cx: &mut Context<Self>,
     ) -> impl IntoElement {
+        let cards = match self.mode {
+            QrcodeMode::Decode => self.render_decode(cx),
+            QrcodeMode::Encode => self.render_encode(cx),
+        };
+
         page()
             .size_full()
             .child(
-                card()
-                    .flex_1()
-                    .child(
-                        div()
-                            .flex()
-                            .items_center()
-                            .justify_between()
-                            .child(label("二维码"))
-                            .child(button(cx, "choose-file").label("选择文件").on_click(cx.listener(
-                                |this, _ev, window, cx| {
-                                    this.choose_file(window, cx);
-                                },
-                            ))),
-                    )
-                    .child(
-                        div()
-                            .flex_1()
-                            .flex()
-                            .items_center()
-                            .justify_center()
-                            .min_w_0()
-                            .min_h_0()
-                            .child(if let Some(path) = &self.path {
-                                img(path.clone())
-                                    .max_w_full()
-                                    .max_h_full()
-                                    .object_fit(ObjectFit::Contain)
-                            } else {
-                                img("")
-                            }),
+                card().child(
+                    div().flex().gap_4().items_center().child(label("模式")).child(
+                        RadioGroup::horizontal("qrcode-mode")
+                            .selected_index(Some(match self.mode {
+                                QrcodeMode::Decode => 0,
+                                QrcodeMode::Encode => 1,
+                            }))
+                            .child("解析")
+                            .child("生成")
+                            .on_click(cx.listener(|this, index: &usize, _window, cx| {
+                                this.mode = if *index == 1 {
+                                    QrcodeMode::Encode
+                                } else {
+                                    QrcodeMode::Decode
+                                };
+                                cx.notify();
+                            })),
                     ),
+                ),
             )
-            .child(
-                card()
-                    .h_64()
-                    .child(label("解析结果"))
-                    .child(textarea(&self.output, |input| input)),
-            )
+            .children(cards)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use image::DynamicImage;
+
+    use super::{
+        DecodedBarcode, MAX_IMAGE_PIXELS, QrcodeStyle, Symbology, decode_barcodes, encode_barcode, fit_module_size,
+    };
+
+    fn round_trip(
+        text: &str,
+        symbology: Symbology,
+    ) -> Vec<DecodedBarcode> {
+        let img = encode_barcode(text, symbology, &QrcodeStyle::default()).unwrap();
+        decode_barcodes(&DynamicImage::ImageRgba8(img))
+    }
+
+    fn assert_round_trip(
+        text: &str,
+        symbology: Symbology,
+        expected: &str,
+    ) {
+        let decoded = round_trip(text, symbology);
+        assert_eq!(decoded.len(), 1, "{}", symbology);
+        assert_eq!(decoded[0].symbology, symbology);
+        assert_eq!(decoded[0].content.as_deref(), Ok(expected));
+    }
+
+    #[test]
+    fn module_size_fits_pixel_limit() {
+        assert_eq!(fit_module_size(185, 185, 8).unwrap(), 8);
+        assert_eq!(fit_module_size(185, 185, 64).unwrap(), 22);
+        assert_eq!(fit_module_size(10, 10, 0).unwrap(), 1);
+        assert!(fit_module_size(5000, 5000, 1).is_err());
+    }
+
+    #[test]
+    fn largest_style_is_clamped() {
+        let style = QrcodeStyle {
+            module_size: 64,
+            margin: 32,
+            ..Default::default()
+        };
+        let text = "x".repeat(2000);
+        let img = encode_barcode(&text, Symbology::QrCode, &style).unwrap();
+        assert!(img.width() as u64 * img.height() as u64 <= MAX_IMAGE_PIXELS);
+        assert_eq!(img.width(), img.height());
+    }
+
+    #[test]
+    fn encoded_qrcode_decodes() {
+        let img = encode_barcode(
+            "https://example.com/?q=测试",
+            Symbology::QrCode,
+            &QrcodeStyle::default(),
+        )
+        .unwrap();
+        let decoded = decode_barcodes(&DynamicImage::ImageRgba8(img));
+        assert_eq!(decoded.len(), 1);
+        assert_eq!(decoded[0].symbology, Symbology::QrCode);
+        assert_eq!(decoded[0].content.as_deref(), Ok("https://example.com/?q=测试"));
+    }
+
+    #[test]
+    fn linear_barcodes_decode() {
+        assert_round_trip("Sidecar-128 {ok}", Symbology::Code128, "Sidecar-128 {ok}");
+        assert_round_trip("SIDECAR-39", Symbology::Code39, "SIDECAR-39");
+    }
+
+    #[test]
+    fn ean13_checks_digit() {
+        assert_round_trip("5901234123457", Symbology::Ean13, "5901234123457");
+        // 只给出 12 位时自动补上校验位
+        assert_round_trip("590123412345", Symbology::Ean13, "5901234123457");
+        assert!(encode_barcode("5901234123458", Symbology::Ean13, &QrcodeStyle::default()).is_err());
+        assert!(encode_barcode("59012341234", Symbology::Ean13, &QrcodeStyle::default()).is_err());
+    }
+
+    #[test]
+    fn data_matrix_decodes() {
+        assert_round_trip(
+            "https://example.com/sidecar",
+            Symbology::DataMatrix,
+            "https://example.com/sidecar",
+        );
+    }
+
+    #[test]
+    fn linear_style_is_clamped() {
+        let style = QrcodeStyle {
+            module_size: 64,
+            margin: 32,
+            ..Default::default()
+        };
+        let text = "x".repeat(60);
+        let img = encode_barcode(&text, Symbology::Code128, &style).unwrap();
+        assert!(img.width() as u64 * img.height() as u64 <= MAX_IMAGE_PIXELS);
+        assert!(img.width() > img.height());
     }
 }
@@ -0,0 +1,672 @@
+use std::fmt;
+
+use chrono::{DateTime, Local, NaiveDate, NaiveDateTime};
+
+use crate::pages::utils::{percent_decode, percent_encode};
+
+const DATETIME_FORMAT: &str = "%Y-%m-%d %H:%M";
+const ICAL_FORMAT: &str = "%Y%m%dT%H%M%S";
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum WifiSecurity {
+    Wpa,
+    Wep,
+    None,
+}
+
+impl WifiSecurity {
+    pub const ALL: [WifiSecurity; 3] = [WifiSecurity::Wpa, WifiSecurity::Wep, WifiSecurity::None];
+
+    fn code(self) -> &'static str {
+        match self {
+            WifiSecurity::Wpa => "WPA",
+            WifiSecurity::Wep => "WEP",
+            WifiSecurity::None => "nopass",
+        }
+    }
+
+    fn from_code(code: &str) -> Self {
+        match code.to_ascii_uppercase().as_str() {
+            "WEP" => WifiSecurity::Wep,
+            "" | "NOPASS" => WifiSecurity::None,
+            _ => WifiSecurity::Wpa,
+        }
+    }
+}
+
+impl fmt::Display for WifiSecurity {
+    fn fmt(
+        &self,
+        f: &mut fmt::Formatter<'_>,
+    ) -> fmt::Result {
+        let name = match self {
+            WifiSecurity::Wpa => "WPA/WPA2",
+            WifiSecurity::Wep => "WEP",
+            WifiSecurity::None => "无密码",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Contact {
+    pub name: String,
+    pub phone: String,
+    pub email: String,
+    pub org: String,
+    pub title: String,
+    pub url: String,
+    pub address: String,
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Event {
+    pub summary: String,
+    /// 本地时间
+    pub start: Option<NaiveDateTime>,
+    pub end: Option<NaiveDateTime>,
+    pub location: String,
+    pub description: String,
+}
+
+/// 二维码中常见的结构化内容
+#[derive(Clone, Debug, PartialEq)]
+pub enum Payload {
+    Text(String),
+    Url(String),
+    Wifi {
+        ssid: String,
+        password: String,
+        security: WifiSecurity,
+        hidden: bool,
+    },
+    Contact(Contact),
+    Event(Event),
+    Geo {
+        latitude: f64,
+        longitude: f64,
+        query: String,
+    },
+}
+
+impl Payload {
+    pub fn kind(&self) -> &'static str {
+        match self {
+            Payload::Text(_) => "文本",
+            Payload::Url(_) => "链接",
+            Payload::Wifi { .. } => "Wi-Fi",
+            Payload::Contact(_) => "名片",
+            Payload::Event(_) => "日程",
+            Payload::Geo { .. } => "位置",
+        }
+    }
+
+    /// 生成写入二维码的文本
+    pub fn encode(&self) -> String {
+        match self {
+            Payload::Text(text) => text.clone(),
+            Payload::Url(url) => {
+                let url = url.trim();
+                if url.contains("://") {
+                    url.to_string()
+                } else {
+                    format!("https://{}", url)
+                }
+            }
+            Payload::Wifi {
+                ssid,
+                password,
+                security,
+                hidden,
+            } => {
+                let mut text = format!("WIFI:T:{};S:{};", security.code(), wifi_escape(ssid));
+                if *security != WifiSecurity::None {
+                    text.push_str(&format!("P:{};", wifi_escape(password)));
+                }
+                if *hidden {
+                    text.push_str("H:true;");
+                }
+                text.push(';');
+                text
+            }
+            Payload::Contact(contact) => {
+                let mut lines = vec!["BEGIN:VCARD".to_string(), "VERSION:3.0".to_string()];
+                // 只有一个完整姓名，作为名写入，姓留空
+                let name = ical_escape(&contact.name);
+                lines.push(format!("N:;{};;;", name));
+                lines.push(format!("FN:{}", name));
+                let properties = [
+                    ("ORG", &contact.org),
+                    ("TITLE", &contact.title),
+                    ("TEL", &contact.phone),
+                    ("EMAIL", &contact.email),
+                    ("URL", &contact.url),
+                ];
+                for (name, value) in properties {
+                    if !value.is_empty() {
+                        lines.push(format!("{}:{}", name, ical_escape(value)));
+                    }
+                }
+                if !contact.address.is_empty() {
+                    lines.push(format!("ADR:;;{};;;;", ical_escape(&contact.address)));
+                }
+                lines.push("END:VCARD".to_string());
+                lines.join("\n")
+            }
+            Payload::Event(event) => {
+                let mut lines = vec![
+                    "BEGIN:VEVENT".to_string(),
+                    format!("SUMMARY:{}", ical_escape(&event.summary)),
+                ];
+                if let Some(start) = event.start {
+                    lines.push(format!("DTSTART:{}", start.format(ICAL_FORMAT)));
+                }
+                if let Some(end) = event.end {
+                    lines.push(format!("DTEND:{}", end.format(ICAL_FORMAT)));
+                }
+                if !event.location.is_empty() {
+                    lines.push(format!("LOCATION:{}", ical_escape(&event.location)));
+                }
+                if !event.description.is_empty() {
+                    lines.push(format!("DESCRIPTION:{}", ical_escape(&event.description)));
+                }
+                lines.push("END:VEVENT".to_string());
+                lines.join("\n")
+            }
+            Payload::Geo {
+                latitude,
+                longitude,
+                query,
+            } => {
+                let mut text = format!("geo:{},{}", latitude, longitude);
+                if !query.is_empty() {
+                    text.push_str(&format!("?q={}", percent_encode(query)));
+                }
+                text
+            }
+        }
+    }
+
+    /// 识别二维码文本的格式，无法识别时作为普通文本
+    pub fn parse(text: &str) -> Self {
+        let trimmed = text.trim();
+        let upper = trimmed.to_ascii_uppercase();
+        let parsed = if upper.starts_with("WIFI:") {
+            parse_wifi(&trimmed[5..])
+        } else if upper.starts_with("MECARD:") {
+            Some(parse_mecard(&trimmed[7..]))
+        } else if upper.starts_with("BEGIN:VCARD") {
+            Some(parse_vcard(trimmed))
+        } else if upper.contains("BEGIN:VEVENT") {
+            Some(parse_event(trimmed))
+        } else if upper.starts_with("GEO:") {
+            parse_geo(&trimmed[4..])
+        } else if (upper.starts_with("HTTP://") || upper.starts_with("HTTPS://"))
+            && !trimmed.contains(char::is_whitespace)
+        {
+            Some(Payload::Url(trimmed.to_string()))
+        } else {
+            None
+        };
+        parsed.unwrap_or_else(|| Payload::Text(text.to_string()))
+    }
+
+    /// 用于表格展示的字段，省略空值
+    pub fn fields(&self) -> Vec<(&'static str, String)> {
+        let fields = match self {
+            Payload::Text(text) => vec![("内容", text.clone())],
+            Payload::Url(url) => vec![("链接", url.clone())],
+            Payload::Wifi {
+                ssid,
+                password,
+                security,
+                hidden,
+            } => vec![
+                ("网络名称", ssid.clone()),
+                ("加密方式", security.to_string()),
+                ("密码", password.clone()),
+                ("隐藏网络", if *hidden { "是" } else { "否" }.to_string()),
+            ],
+            Payload::Contact(contact) => vec![
+                ("姓名", contact.name.clone()),
+                ("电话", contact.phone.clone()),
+                ("邮箱", contact.email.clone()),
+                ("公司", contact.org.clone()),
+                ("职位", contact.title.clone()),
+                ("网址", contact.url.clone()),
+                ("地址", contact.address.clone()),
+            ],
+            Payload::Event(event) => vec![
+                ("标题", event.summary.clone()),
+                ("开始", format_datetime(event.start)),
+                ("结束", format_datetime(event.end)),
+                ("地点", event.location.clone()),
+                ("描述", event.description.clone()),
+            ],
+            Payload::Geo {
+                latitude,
+                longitude,
+                query,
+            } => vec![
+                ("纬度", latitude.to_string()),
+                ("经度", longitude.to_string()),
+                ("名称", query.clone()),
+            ],
+        };
+        fields.into_iter().filter(|(_, value)| !value.is_empty()).collect()
+    }
+}
+
+/// 解析 `2026-01-01 09:00` 格式的时间，也接受秒和只有日期的写法
+pub fn parse_datetime(s: &str) -> Option<NaiveDateTime> {
+    let s = s.trim();
+    NaiveDateTime::parse_from_str(s, DATETIME_FORMAT)
+        .or_else(|_| NaiveDateTime::parse_from_str(s, "%Y-%m-%d %H:%M:%S"))
+        .ok()
+        .or_else(|| {
+            NaiveDate::parse_from_str(s, "%Y-%m-%d")
+                .ok()
+                .and_then(|date| date.and_hms_opt(0, 0, 0))
+        })
+}
+
+fn format_datetime(time: Option<NaiveDateTime>) -> String {
+    time.map(|time| time.format(DATETIME_FORMAT).to_string())
+        .unwrap_or_default()
+}
+
+fn wifi_escape(s: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+    for c in s.chars() {
+        if matches!(c, '\\' | ';' | ',' | ':' | '"') {
+            out.push('\\');
+        }
+        out.push(c);
+    }
+    out
+}
+
+/// 按未转义的 `;` 拆分 `K:V;K:V;;` 形式的字段，并去掉值中的转义
+fn split_fields(s: &str) -> Vec<(String, String)> {
+    split_unescaped(s)
+        .into_iter()
+        .filter_map(|field| {
+            let (key, value) = field.split_once(':')?;
+            Some((key.trim().to_ascii_uppercase(), unescape(value)))
+        })
+        .collect()
+}
+
+/// 按未转义的 `;` 拆分，保留各部分中的转义
+fn split_unescaped(s: &str) -> Vec<String> {
+    let mut parts = Vec::new();
+    let mut current = String::new();
+    let mut chars = s.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => {
+                current.push(c);
+                if let Some(next) = chars.next() {
+                    current.push(next);
+                }
+            }
+            ';' => parts.push(std::mem::take(&mut current)),
+            _ => current.push(c),
+        }
+    }
+    parts.push(current);
+    parts
+}
+
+fn unescape(s: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+    let mut chars = s.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            out.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('n' | 'N') => out.push('\n'),
+            Some(next) => out.push(next),
+            None => {}
+        }
+    }
+    out
+}
+
+fn parse_wifi(s: &str) -> Option<Payload> {
+    let fields = split_fields(s);
+    let get = |key: &str| {
+        fields
+            .iter()
+            .find(|(k, _)| k == key)
+            .map(|(_, v)| v.clone())
+            .unwrap_or_default()
+    };
+    let ssid = get("S");
+    if ssid.is_empty() {
+        return None;
+    }
+    Some(Payload::Wifi {
+        ssid,
+        password: get("P"),
+        security: WifiSecurity::from_code(&get("T")),
+        hidden: get("H").eq_ignore_ascii_case("true"),
+    })
+}
+
+fn parse_mecard(s: &str) -> Payload {
+    let mut contact = Contact::default();
+    for (key, value) in split_fields(s) {
+        let target = match key.as_str() {
+            "N" => &mut contact.name,
+            "TEL" => &mut contact.phone,
+            "EMAIL" => &mut contact.email,
+            "ORG" => &mut contact.org,
+            "URL" => &mut contact.url,
+            "ADR" => &mut contact.address,
+            _ => continue,
+        };
+        // MECARD 的姓名为 `姓,名`
+        let value = if key == "N" {
+            value.split(',').map(str::trim).collect::<Vec<_>>().join(" ")
+        } else {
+            value
+        };
+        append(target, value);
+    }
+    Payload::Contact(contact)
+}
+
+/// 多个同名属性（例如多个电话）用逗号连接
+fn append(
+    target: &mut String,
+    value: String,
+) {
+    if value.is_empty() {
+        return;
+    }
+    if !target.is_empty() {
+        target.push_str(", ");
+    }
+    target.push_str(&value);
+}
+
+/// 拆分 vCard / iCalendar 的内容行，返回属性名（不含参数）和原始值
+fn content_lines(s: &str) -> Vec<(String, String)> {
+    // 以空白开头的行是上一行的折行
+    let unfolded = s.replace("\r\n", "\n").replace("\n ", "").replace("\n\t", "");
+    unfolded
+        .lines()
+        .filter_map(|line| {
+            let (name, value) = line.split_once(':')?;
+            let name = name.split(';').next().unwrap_or_default();
+            Some((name.trim().to_ascii_uppercase(), value.to_string()))
+        })
+        .collect()
+}
+
+/// 拆分结构化的属性值，例如 `N:姓;名;;;`
+fn components(value: &str) -> Vec<String> {
+    split_unescaped(value).iter().map(|part| unescape(part)).collect()
+}
+
+fn join_components(value: &str) -> String {
+    components(value)
+        .into_iter()
+        .filter(|part| !part.trim().is_empty())
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+fn parse_vcard(s: &str) -> Payload {
+    let mut contact = Contact::default();
+    let mut structured_name = String::new();
+    for (name, value) in content_lines(s) {
+        match name.as_str() {
+            "FN" => append(&mut contact.name, unescape(&value)),
+            "N" => {
+                // 姓;名;中间名;前缀;后缀，按“名 姓”显示
+                let parts = components(&value);
+                let given = parts.get(1).cloned().unwrap_or_default();
+                let family = parts.first().cloned().unwrap_or_default();
+                structured_name = [given, family]
+                    .into_iter()
+                    .filter(|part| !part.is_empty())
+                    .collect::<Vec<_>>()
+                    .join(" ");
+            }
+            "TEL" => append(&mut contact.phone, unescape(&value)),
+            "EMAIL" => append(&mut contact.email, unescape(&value)),
+            "ORG" => append(&mut contact.org, join_components(&value)),
+            "TITLE" => append(&mut contact.title, unescape(&value)),
+            "URL" => append(&mut contact.url, unescape(&value)),
+            "ADR" => append(&mut contact.address, join_components(&value)),
+            _ => {}
+        }
+    }
+    if contact.name.is_empty() {
+        contact.name = structured_name;
+    }
+    Payload::Contact(contact)
+}
+
+fn parse_event(s: &str) -> Payload {
+    let mut event = Event::default();
+    let mut inside = false;
+    for (name, value) in content_lines(s) {
+        match (name.as_str(), value.trim().to_ascii_uppercase().as_str()) {
+            ("BEGIN", "VEVENT") => inside = true,
+            ("END", "VEVENT") => break,
+            _ if !inside => {}
+            ("SUMMARY", _) => event.summary = unescape(&value),
+            ("DTSTART", _) => event.start = parse_ical_time(&value),
+            ("DTEND", _) => event.end = parse_ical_time(&value),
+            ("LOCATION", _) => event.location = unescape(&value),
+            ("DESCRIPTION", _) => event.description = unescape(&value),
+            _ => {}
+        }
+    }
+    Payload::Event(event)
+}
+
+/// 解析 iCalendar 时间，UTC 时间转换为本地时间
+fn parse_ical_time(value: &str) -> Option<NaiveDateTime> {
+    let value = value.trim();
+    if let Some(utc) = value.strip_suffix('Z') {
+        let time = NaiveDateTime::parse_from_str(utc, ICAL_FORMAT).ok()?;
+        return Some(DateTime::<Local>::from(time.and_utc()).naive_local());
+    }
+    NaiveDateTime::parse_from_str(value, ICAL_FORMAT).ok().or_else(|| {
+        NaiveDate::parse_from_str(value, "%Y%m%d")
+            .ok()
+            .and_then(|date| date.and_hms_opt(0, 0, 0))
+    })
+}
+
+fn parse_geo(s: &str) -> Option<Payload> {
+    let (coordinates, params) = s.split_once('?').unwrap_or((s, ""));
+    let mut parts = coordinates.split(',');
+    let latitude = parts.next()?.trim().parse::<f64>().ok()?;
+    let longitude = parts.next()?.trim().parse::<f64>().ok()?;
+    if !(-90.0..=90.0).contains(&latitude) || !(-180.0..=180.0).contains(&longitude) {
+        return None;
+    }
+    let query = params
+        .split('&')
+        .find_map(|param| param.strip_prefix("q="))
+        .and_then(|query| percent_decode(&query.replace('+', " ")))
+        .unwrap_or_default();
+    Some(Payload::Geo {
+        latitude,
+        longitude,
+        query,
+    })
+}
+
+fn ical_escape(s: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+    for c in s.chars() {
+        match c {
+            '\\' | ';' | ',' => {
+                out.push('\\');
+                out.push(c);
+            }
+            '\n' => out.push_str("\\n"),
+            '\r' => {}
+            _ => out.push(c),
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn round_trip(payload: Payload) {
+        assert_eq!(Payload::parse(&payload.encode()), payload);
+    }
+
+    #[test]
+    fn wifi_round_trip() {
+        round_trip(Payload::Wifi {
+            ssid: r#"home;net,5G:"a"\b"#.to_string(),
+            password: r"p;a,s:s\".to_string(),
+            security: WifiSecurity::Wpa,
+            hidden: false,
+        });
+        round_trip(Payload::Wifi {
+            ssid: "guest".to_string(),
+            password: String::new(),
+            security: WifiSecurity::None,
+            hidden: true,
+        });
+
+        let open = Payload::Wifi {
+            ssid: "cafe".to_string(),
+            password: "ignored".to_string(),
+            security: WifiSecurity::None,
+            hidden: true,
+        };
+        assert_eq!(open.encode(), "WIFI:T:nopass;S:cafe;H:true;;");
+        assert_eq!(
+            Payload::parse("WIFI:S:cafe;;"),
+            Payload::Wifi {
+                ssid: "cafe".to_string(),
+                password: String::new(),
+                security: WifiSecurity::None,
+                hidden: false,
+            }
+        );
+        assert_eq!(
+            Payload::parse("WIFI:T:WPA;;"),
+            Payload::Text("WIFI:T:WPA;;".to_string())
+        );
+    }
+
+    #[test]
+    fn mecard_unescapes_fields() {
+        let Payload::Contact(contact) =
+            Payload::parse(r"MECARD:N:Doe,John;TEL:+1 555;ORG:A\;B\,C\:D\\E;ADR:1 Main St;;")
+        else {
+            panic!("not a contact");
+        };
+        assert_eq!(contact.name, "Doe John");
+        assert_eq!(contact.phone, "+1 555");
+        assert_eq!(contact.org, r"A;B,C:D\E");
+        assert_eq!(contact.address, "1 Main St");
+    }
+
+    #[test]
+    fn vcard_round_trip() {
+        let contact = Contact {
+            name: "张三".to_string(),
+            phone: "+86 10 1234".to_string(),
+            email: "zhang@example.com".to_string(),
+            org: r"Acme; Inc, \Ltd".to_string(),
+            title: "工程师".to_string(),
+            url: "https://example.com".to_string(),
+            address: "北京市".to_string(),
+        };
+        let text = Payload::Contact(contact.clone()).encode();
+        assert!(text.contains("\nN:;张三;;;\n"), "{}", text);
+        round_trip(Payload::Contact(contact));
+
+        // 没有 FN 时由 N 的“名 姓”组成
+        let Payload::Contact(contact) = Payload::parse("BEGIN:VCARD\nN:Doe;John;;;\nEND:VCARD") else {
+            panic!("not a contact");
+        };
+        assert_eq!(contact.name, "John Doe");
+    }
+
+    #[test]
+    fn event_times() {
+        let start = parse_datetime("2026-01-01 09:00");
+        let end = parse_datetime("2026-01-01 10:30:00");
+        assert_eq!(start, NaiveDate::from_ymd_opt(2026, 1, 1).unwrap().and_hms_opt(9, 0, 0));
+        assert_eq!(end, NaiveDate::from_ymd_opt(2026, 1, 1).unwrap().and_hms_opt(10, 30, 0));
+        assert_eq!(
+            parse_datetime("2026-01-01"),
+            NaiveDate::from_ymd_opt(2026, 1, 1).unwrap().and_hms_opt(0, 0, 0)
+        );
+        assert_eq!(parse_datetime("01/01/2026"), None);
+
+        round_trip(Payload::Event(Event {
+            summary: "周会; 例会, 第1次".to_string(),
+            start,
+            end,
+            location: "会议室 A".to_string(),
+            description: "第一行\n第二行".to_string(),
+        }));
+
+        let Payload::Event(all_day) =
+            Payload::parse("BEGIN:VEVENT\nSUMMARY:假期\nDTSTART;VALUE=DATE:20260501\nEND:VEVENT")
+        else {
+            panic!("not an event");
+        };
+        assert_eq!(
+            all_day.start,
+            NaiveDate::from_ymd_opt(2026, 5, 1).unwrap().and_hms_opt(0, 0, 0)
+        );
+
+        let Payload::Event(utc) = Payload::parse("BEGIN:VEVENT\nDTSTART:20260501T080000Z\nEND:VEVENT") else {
+            panic!("not an event");
+        };
+        let expected = NaiveDate::from_ymd_opt(2026, 5, 1)
+            .unwrap()
+            .and_hms_opt(8, 0, 0)
+            .unwrap()
+            .and_utc();
+        assert_eq!(utc.start, Some(DateTime::<Local>::from(expected).naive_local()));
+    }
+
+    #[test]
+    fn geo_round_trip() {
+        let geo = Payload::Geo {
+            latitude: 31.2304,
+            longitude: 121.4737,
+            query: "上海 外滩&1".to_string(),
+        };
+        assert_eq!(
+            geo.encode(),
+            "geo:31.2304,121.4737?q=%E4%B8%8A%E6%B5%B7%20%E5%A4%96%E6%BB%A9%261"
+        );
+        round_trip(geo);
+        round_trip(Payload::Geo {
+            latitude: -33.8688,
+            longitude: 151.2093,
+            query: String::new(),
+        });
+        assert_eq!(
+            Payload::parse("geo:0,0?z=3&q=a+b"),
+            Payload::Geo {
+                latitude: 0.0,
+                longitude: 0.0,
+                query: "a b".to_string(),
+            }
+        );
+        assert_eq!(Payload::parse("geo:91,0"), Payload::Text("geo:91,0".to_string()));
+    }
+}
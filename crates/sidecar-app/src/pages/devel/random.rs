@@ -15,11 +15,11 @@ fn gen_mac() -> String {
         .unwrap_or_default()
         .as_nanos() as u64;
     let mut b = [0u8; 6];
-    for i in 0..6 {
+    for byte in b.iter_mut() {
         x ^= x << 13;
         x ^= x >> 7;
         x ^= x << 17;
-        b[i] = x as u8;
+        *byte = x as u8;
     }
     b[0] = (b[0] | 0x02) & 0xFE;
     format!(
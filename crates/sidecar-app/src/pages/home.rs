@@ -1,4 +1,8 @@
-use std::{env, net::UdpSocket, process::Command};
+use std::{
+    env,
+    net::{IpAddr, UdpSocket},
+    process::Command,
+};
 
 use gpui::*;
 use gpui_component::StyledExt;
@@ -101,26 +105,25 @@ pub fn collect_basic_info() -> BasicInfo {
 
     // 网卡 信息
     let mut network_info = None;
-    if let Ok(socket) = UdpSocket::bind("0.0.0.0:0") {
-        if socket.connect("8.8.8.8:80").is_ok() {
-            if let Ok(local_addr) = socket.local_addr() {
-                let local_ip = local_addr.ip();
+    if let Ok(socket) = UdpSocket::bind("0.0.0.0:0")
+        && socket.connect("8.8.8.8:80").is_ok()
+        && let Ok(local_addr) = socket.local_addr()
+    {
+        let local_ip = local_addr.ip();
 
-                // Find the network interface that has this IP
-                use sysinfo::Networks;
-                let networks = Networks::new_with_refreshed_list();
+        // Find the network interface that has this IP
+        use sysinfo::Networks;
+        let networks = Networks::new_with_refreshed_list();
 
-                for (interface_name, network) in &networks {
-                    // Check if this interface has the matching IP
-                    for ip_network in network.ip_networks() {
-                        if ip_network.addr == local_ip {
-                            network_info = Some(NetworkInfo {
-                                name: interface_name.clone(),
-                                mac_address: network.mac_address().to_string(),
-                                ip_address: local_ip.to_string(),
-                            });
-                        }
-                    }
+        for (interface_name, network) in &networks {
+            // Check if this interface has the matching IP
+            for ip_network in network.ip_networks() {
+                if ip_network.addr == local_ip {
+                    network_info = Some(NetworkInfo {
+                        name: interface_name.clone(),
+                        mac_address: network.mac_address().to_string(),
+                        ip_address: local_ip.to_string(),
+                    });
                 }
             }
         }
@@ -150,6 +153,24 @@ pub fn collect_basic_info() -> BasicInfo {
     }
 }
 
+/// 列出所有非回环网卡的地址，IPv4 在前
+pub fn interface_addresses() -> Vec<(String, IpAddr)> {
+    let networks = sysinfo::Networks::new_with_refreshed_list();
+    let mut addresses = Vec::new();
+    for (interface_name, network) in &networks {
+        for ip_network in network.ip_networks() {
+            let ip = ip_network.addr;
+            // 链路本地的 IPv6 地址需要带接口标识才能访问，直接跳过
+            if ip.is_loopback() || matches!(ip, IpAddr::V6(v6) if v6.is_unicast_link_local()) {
+                continue;
+            }
+            addresses.push((interface_name.clone(), ip));
+        }
+    }
+    addresses.sort_by_key(|(name, ip)| (ip.is_ipv6(), name.clone()));
+    addresses
+}
+
 fn get_system_serial_number() -> String {
     #[cfg(target_os = "macos")]
     {
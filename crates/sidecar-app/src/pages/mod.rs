@@ -13,6 +13,7 @@ pub mod devel {
     pub mod crypto;
     pub mod hash;
     pub mod json;
+    pub mod jwt;
     pub mod qrcode;
     pub mod random;
 }
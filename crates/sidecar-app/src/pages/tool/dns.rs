@@ -1,14 +1,104 @@
+use std::{io, net::IpAddr};
+
 use gpui::*;
-use gpui_component::{Disableable, input::InputState};
+use gpui_component::{
+    Disableable, WindowExt, accordion::Accordion, checkbox::Checkbox, input::InputState, radio::RadioGroup,
+};
+use sidecar_dns::{RecordType, Resolver, Response, Trace, Tracer, Transport, parse_server, reverse_name};
 
 use crate::{
     MainView,
     comps::{button, card, label, page, textarea},
 };
 
+const TRANSPORTS: [(Transport, &str); 3] = [
+    (Transport::Auto, "UDP（截断时改用 TCP）"),
+    (Transport::Udp, "仅 UDP"),
+    (Transport::Tcp, "仅 TCP"),
+];
+
+const BULK_CONCURRENCY: usize = 16;
+
+/// 输入为 IP 地址时自动转换为 PTR 反向查询
+fn to_query(
+    entry: &str,
+    record_type: RecordType,
+) -> (String, RecordType) {
+    match entry.parse::<IpAddr>() {
+        Ok(ip) => (reverse_name(ip), RecordType::PTR),
+        Err(_) => (entry.to_string(), record_type),
+    }
+}
+
+fn build_resolver(
+    server: &str,
+    transport: Transport,
+) -> io::Result<Resolver> {
+    let resolver = if server.is_empty() {
+        Resolver::system()
+    } else {
+        Resolver::new(parse_server(server)?)
+    };
+    Ok(resolver.transport(transport))
+}
+
+struct BulkRow {
+    entry: String,
+    record_type: RecordType,
+    status: String,
+    answers: String,
+    elapsed: String,
+}
+
+impl BulkRow {
+    fn new(
+        entry: String,
+        record_type: RecordType,
+        result: io::Result<Response>,
+    ) -> Self {
+        match result {
+            Ok(response) => {
+                let answers: Vec<String> = response
+                    .message
+                    .answers
+                    .iter()
+                    .filter(|r| r.rtype == record_type)
+                    .map(|r| r.data.to_string())
+                    .collect();
+                Self {
+                    entry,
+                    record_type,
+                    status: response.message.header.rcode.to_string(),
+                    answers: answers.join(", "),
+                    elapsed: format!("{} ms", response.elapsed.as_millis()),
+                }
+            }
+            Err(e) => Self {
+                entry,
+                record_type,
+                status: "失败".to_string(),
+                answers: e.to_string(),
+                elapsed: String::new(),
+            },
+        }
+    }
+}
+
+enum QueryView {
+    Text,
+    Trace(Trace),
+    Bulk(Vec<BulkRow>),
+}
+
 pub struct DnsPage {
     input: Entity<InputState>,
+    server: Entity<InputState>,
     output: Entity<InputState>,
+    record_type: RecordType,
+    transport: Transport,
+    trace_mode: bool,
+    view: QueryView,
+    open_hops: Vec<usize>,
     running: bool,
 }
 
@@ -18,11 +108,23 @@ impl DnsPage {
         cx: &mut Context<MainView>,
     ) -> AnyView {
         AnyView::from(cx.new(|cx| {
-            let input = cx.new(|cx| InputState::new(window, cx).placeholder("输入域名，例如: example.com"));
+            let input = cx.new(|cx| {
+                InputState::new(window, cx)
+                    .multi_line(true)
+                    .placeholder("输入域名或 IP 地址，例如: example.com，多个请每行一个")
+            });
+            let server = cx
+                .new(|cx| InputState::new(window, cx).placeholder("留空使用系统 DNS，例如: 8.8.8.8 或 10.0.0.1:5353"));
             let output = cx.new(|cx| InputState::new(window, cx).multi_line(true));
             Self {
                 input,
+                server,
                 output,
+                record_type: RecordType::A,
+                transport: Transport::Auto,
+                trace_mode: false,
+                view: QueryView::Text,
+                open_hops: Vec::new(),
                 running: false,
             }
         }))
@@ -30,12 +132,245 @@ impl DnsPage {
 
     fn start_query(
         &mut self,
-        _window: &mut Window,
-        _cx: &mut Context<Self>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        if self.running {
+            return;
+        }
+        let entries: Vec<String> = self
+            .input
+            .read(cx)
+            .value()
+            .lines()
+            .map(|line| line.trim().to_string())
+            .filter(|line| !line.is_empty())
+            .collect();
+        if entries.is_empty() {
+            return;
+        }
+        let server = self.server.read(cx).value().trim().to_string();
+        let record_type = self.record_type;
+        let transport = self.transport;
+
+        if self.trace_mode && entries.len() > 1 {
+            window.push_notification("追踪模式一次只能查询一个域名", cx);
+            return;
+        }
+
+        self.running = true;
+        cx.notify();
+
+        if self.trace_mode {
+            let (name, record_type) = to_query(&entries[0], record_type);
+            self.start_trace(name, record_type, transport, window, cx);
+            return;
+        }
+        if entries.len() > 1 {
+            self.start_bulk(entries, server, record_type, transport, window, cx);
+            return;
+        }
+
+        let (name, record_type) = to_query(&entries[0], record_type);
+        cx.spawn_in(window, async move |this, cx| {
+            let out_text = cx
+                .background_executor()
+                .spawn(async move {
+                    let resolver = match build_resolver(&server, transport) {
+                        Ok(resolver) => resolver,
+                        Err(e) => return format!("服务器地址无效: {}", e),
+                    };
+                    resolver
+                        .query(&name, record_type)
+                        .map(|response| response.to_string())
+                        .unwrap_or_else(|e| format!("查询失败: {}", e))
+                })
+                .await;
+
+            let _ = cx.update(|window, cx| {
+                let _ = this.update(cx, |this, cx| {
+                    this.output.update(cx, |state, cx2| {
+                        state.set_value(out_text, window, cx2);
+                    });
+                    this.view = QueryView::Text;
+                    this.running = false;
+                    cx.notify();
+                });
+            });
+        })
+        .detach();
+    }
+
+    fn start_bulk(
+        &mut self,
+        entries: Vec<String>,
+        server: String,
+        record_type: RecordType,
+        transport: Transport,
+        window: &mut Window,
+        cx: &mut Context<Self>,
     ) {
+        cx.spawn_in(window, async move |this, cx| {
+            let result = cx
+                .background_executor()
+                .spawn(async move {
+                    let resolver = build_resolver(&server, transport)?;
+                    let queries: Vec<(String, RecordType)> =
+                        entries.iter().map(|entry| to_query(entry, record_type)).collect();
+                    let results = resolver.query_all(&queries, BULK_CONCURRENCY);
+                    Ok::<_, io::Error>(
+                        entries
+                            .into_iter()
+                            .zip(queries)
+                            .zip(results)
+                            .map(|((entry, (_, qtype)), result)| BulkRow::new(entry, qtype, result))
+                            .collect::<Vec<_>>(),
+                    )
+                })
+                .await;
+
+            let _ = cx.update(|window, cx| {
+                let _ = this.update(cx, |this, cx| {
+                    match result {
+                        Ok(rows) => this.view = QueryView::Bulk(rows),
+                        Err(e) => {
+                            this.output.update(cx, |state, cx2| {
+                                state.set_value(format!("服务器地址无效: {}", e), window, cx2);
+                            });
+                            this.view = QueryView::Text;
+                        }
+                    }
+                    this.running = false;
+                    cx.notify();
+                });
+            });
+        })
+        .detach();
+    }
+
+    fn start_trace(
+        &mut self,
+        name: String,
+        record_type: RecordType,
+        transport: Transport,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        cx.spawn_in(window, async move |this, cx| {
+            let result = cx
+                .background_executor()
+                .spawn(async move { Tracer::new().transport(transport).trace(&name, record_type) })
+                .await;
+
+            let _ = cx.update(|window, cx| {
+                let _ = this.update(cx, |this, cx| {
+                    match result {
+                        Ok(trace) => {
+                            this.output.update(cx, |state, cx2| {
+                                state.set_value(trace.to_string(), window, cx2);
+                            });
+                            // 默认展开最后一跳，即解析停止的位置
+                            this.open_hops = trace.hops.len().checked_sub(1).into_iter().collect();
+                            this.view = QueryView::Trace(trace);
+                        }
+                        Err(e) => {
+                            this.output.update(cx, |state, cx2| {
+                                state.set_value(format!("追踪失败: {}", e), window, cx2);
+                            });
+                            this.view = QueryView::Text;
+                        }
+                    }
+                    this.running = false;
+                    cx.notify();
+                });
+            });
+        })
+        .detach();
+    }
+
+    fn render_trace(
+        &self,
+        trace: &Trace,
+        cx: &mut Context<Self>,
+    ) -> impl IntoElement {
+        let view = cx.entity().downgrade();
+        let hops = trace.hops.iter().enumerate().fold(
+            Accordion::new("trace-hops")
+                .multiple(true)
+                .on_toggle_click(move |open, _window, cx| {
+                    let _ = view.update(cx, |this, cx| {
+                        this.open_hops = open.to_vec();
+                        cx.notify();
+                    });
+                }),
+            |acc, (i, hop)| {
+                let target = match &hop.referral {
+                    Some(referral) => format!("委派至 {}", referral.zone),
+                    None => "权威应答".to_string(),
+                };
+                let title = format!(
+                    "{}. {} @ {} ({})  {}  {} ms",
+                    i + 1,
+                    hop.zone,
+                    hop.server_name,
+                    hop.response.server,
+                    target,
+                    hop.response.elapsed.as_millis()
+                );
+                let records = hop.records_text();
+                acc.item(|item| {
+                    item.open(self.open_hops.contains(&i))
+                        .title(div().text_sm().text_color(white()).child(title))
+                        .child(
+                            div()
+                                .p_2()
+                                .text_sm()
+                                .font_family("monospace")
+                                .text_color(white())
+                                .child(records),
+                        )
+                })
+            },
+        );
+
+        div()
+            .flex_1()
+            .min_h_0()
+            .flex()
+            .flex_col()
+            .gap_2()
+            .child(
+                div()
+                    .id("dns-trace-hops")
+                    .flex_1()
+                    .min_h_0()
+                    .overflow_y_scroll()
+                    .child(hops),
+            )
+            .child(label(format!("追踪结束: {}", trace.outcome)))
     }
 }
 
+fn bulk_row(
+    cells: [String; 5],
+    header: bool,
+) -> Div {
+    let [entry, record_type, status, answers, elapsed] = cells;
+    div()
+        .flex()
+        .gap_4()
+        .py_1()
+        .border_b_1()
+        .border_color(rgb(0x404040))
+        .text_sm()
+        .text_color(if header { rgb(0x808080) } else { rgb(0xffffff) })
+        .child(div().w_64().truncate().child(entry))
+        .child(div().w_16().child(record_type))
+        .child(div().w_24().child(status))
+        .child(div().flex_1().min_w_0().child(answers))
+        .child(div().w_20().child(elapsed))
+}
+
 impl Render for DnsPage {
     fn render(
         &mut self,
@@ -43,16 +378,75 @@ impl Render for DnsPage {
         cx: &mut Context<Self>,
     ) -> impl IntoElement {
         let running = self.running;
+        let record_index = RecordType::ALL.iter().position(|t| *t == self.record_type);
+        let transport_index = TRANSPORTS.iter().position(|(t, _)| *t == self.transport);
 
         page()
             .size_full()
             .child(
-                card().child(
+                card()
+                    .child(
+                        div()
+                            .flex()
+                            .items_center()
+                            .justify_between()
+                            .child(label("记录类型"))
+                            .child(
+                                RadioGroup::horizontal("record-type")
+                                    .selected_index(record_index)
+                                    .children(RecordType::ALL.iter().map(|t| t.to_string()))
+                                    .on_click(cx.listener(|this, index: &usize, _window, cx| {
+                                        this.record_type = RecordType::ALL[*index];
+                                        cx.notify();
+                                    })),
+                            ),
+                    )
+                    .child(
+                        div()
+                            .flex()
+                            .items_center()
+                            .justify_between()
+                            .child(label("传输协议"))
+                            .child(
+                                RadioGroup::horizontal("transport")
+                                    .selected_index(transport_index)
+                                    .children(TRANSPORTS.iter().map(|(_, name)| *name))
+                                    .on_click(cx.listener(|this, index: &usize, _window, cx| {
+                                        this.transport = TRANSPORTS[*index].0;
+                                        cx.notify();
+                                    })),
+                            ),
+                    )
+                    .child(
+                        div()
+                            .flex()
+                            .gap_4()
+                            .items_center()
+                            .child(label("DNS 服务器"))
+                            .child(textarea(&self.server, |input| input))
+                            .child(
+                                Checkbox::new("trace-mode")
+                                    .label("追踪模式（+trace）")
+                                    .checked(self.trace_mode)
+                                    .on_click(cx.listener(|this, checked: &bool, _window, cx| {
+                                        // 关闭追踪模式后回到文本输出，保留最近一次的追踪结果
+                                        this.trace_mode = *checked;
+                                        if !this.trace_mode && matches!(this.view, QueryView::Trace(_)) {
+                                            this.view = QueryView::Text;
+                                        }
+                                        cx.notify();
+                                    })),
+                            ),
+                    ),
+            )
+            .child(
+                card().h_32().child(
                     div()
                         .flex()
+                        .flex_1()
                         .gap_4()
                         .items_center()
-                        .child(textarea(&self.input, |input| input))
+                        .child(textarea(&self.input, |input| input).h_full())
                         .child(
                             button(cx, "dns-query")
                                 .disabled(running)
@@ -63,13 +457,51 @@ impl Render for DnsPage {
                         ),
                 ),
             )
-            .child(
-                card()
+            .child(match &self.view {
+                QueryView::Trace(trace) => card()
+                    .flex_1()
+                    .child(label("追踪过程"))
+                    .child(self.render_trace(trace, cx)),
+                QueryView::Bulk(rows) => card()
+                    .flex_1()
+                    .child(label(format!("批量查询结果（{} 条）", rows.len())))
+                    .child(bulk_row(
+                        [
+                            "查询".into(),
+                            "类型".into(),
+                            "状态".into(),
+                            "结果".into(),
+                            "耗时".into(),
+                        ],
+                        true,
+                    ))
+                    .child(
+                        div()
+                            .id("dns-bulk-rows")
+                            .flex_1()
+                            .min_h_0()
+                            .overflow_y_scroll()
+                            .flex()
+                            .flex_col()
+                            .children(rows.iter().map(|row| {
+                                bulk_row(
+                                    [
+                                        row.entry.clone(),
+                                        row.record_type.to_string(),
+                                        row.status.clone(),
+                                        row.answers.clone(),
+                                        row.elapsed.clone(),
+                                    ],
+                                    false,
+                                )
+                            })),
+                    ),
+                QueryView::Text => card()
                     .flex_1()
                     .child(label("输出"))
                     .child(textarea(&self.output, |input| {
                         input.font_family("monospace").disabled(true)
                     })),
-            )
+            })
     }
 }
@@ -1,15 +1,870 @@
+#[cfg(target_os = "linux")]
+use std::{collections::HashMap, fs, path::Path};
+use std::{
+    fmt,
+    io::{Error, ErrorKind},
+    net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr, TcpListener, TcpStream, ToSocketAddrs},
+    ops::RangeInclusive,
+    sync::{
+        Mutex,
+        atomic::{AtomicUsize, Ordering},
+    },
+    thread,
+    time::{Duration, Instant},
+};
+
 use gpui::*;
+use gpui_component::{Disableable, WindowExt, input::InputState, radio::RadioGroup};
+use sysinfo::{Pid, ProcessRefreshKind, ProcessesToUpdate, Signal, System};
+
+use crate::{
+    MainView,
+    comps::{button, card, label, page, textarea},
+};
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Protocol {
+    Tcp,
+    Udp,
+}
+
+impl fmt::Display for Protocol {
+    fn fmt(
+        &self,
+        f: &mut fmt::Formatter<'_>,
+    ) -> fmt::Result {
+        match self {
+            Protocol::Tcp => f.write_str("TCP"),
+            Protocol::Udp => f.write_str("UDP"),
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct SocketEntry {
+    pub protocol: Protocol,
+    pub local: SocketAddr,
+    pub remote: Option<SocketAddr>,
+    pub state: &'static str,
+    pub inode: u64,
+    pub pid: Option<u32>,
+    pub process: String,
+}
+
+/// `/proc/net/*` 中的地址按 32 位小端字存放，需要逐字反转
+fn parse_proc_addr(s: &str) -> Option<SocketAddr> {
+    let (ip, port) = s.split_once(':')?;
+    let port = u16::from_str_radix(port, 16).ok()?;
+    let bytes = hex::decode(ip).ok()?;
+
+    let ip = match bytes.len() {
+        4 => IpAddr::V4(Ipv4Addr::new(bytes[3], bytes[2], bytes[1], bytes[0])),
+        16 => {
+            let mut octets = [0u8; 16];
+            for (i, word) in bytes.chunks(4).enumerate() {
+                for (j, b) in word.iter().rev().enumerate() {
+                    octets[i * 4 + j] = *b;
+                }
+            }
+            IpAddr::V6(Ipv6Addr::from(octets))
+        }
+        _ => return None,
+    };
+    Some(SocketAddr::new(ip, port))
+}
+
+fn tcp_state(code: u8) -> &'static str {
+    match code {
+        0x01 => "ESTABLISHED",
+        0x02 => "SYN_SENT",
+        0x03 => "SYN_RECV",
+        0x04 => "FIN_WAIT1",
+        0x05 => "FIN_WAIT2",
+        0x06 => "TIME_WAIT",
+        0x07 => "CLOSE",
+        0x08 => "CLOSE_WAIT",
+        0x09 => "LAST_ACK",
+        0x0A => "LISTEN",
+        0x0B => "CLOSING",
+        _ => "UNKNOWN",
+    }
+}
+
+/// 解析 `/proc/net/{tcp,udp}[6]` 的内容，只保留监听和已建立连接的套接字
+fn parse_proc_net(
+    content: &str,
+    protocol: Protocol,
+) -> Vec<SocketEntry> {
+    let mut entries = Vec::new();
+    for line in content.lines().skip(1) {
+        let fields: Vec<&str> = line.split_whitespace().collect();
+        if fields.len() < 10 {
+            continue;
+        }
+        let (Some(local), Some(remote)) = (parse_proc_addr(fields[1]), parse_proc_addr(fields[2])) else {
+            continue;
+        };
+        let Ok(code) = u8::from_str_radix(fields[3], 16) else {
+            continue;
+        };
+
+        let state = match (protocol, code) {
+            (Protocol::Tcp, 0x01 | 0x0A) => tcp_state(code),
+            (Protocol::Udp, 0x01) => "ESTABLISHED",
+            (Protocol::Udp, 0x07) => "UNCONN",
+            _ => continue,
+        };
+
+        entries.push(SocketEntry {
+            protocol,
+            local,
+            remote: (remote.port() != 0).then_some(remote),
+            state,
+            inode: fields[9].parse().unwrap_or(0),
+            pid: None,
+            process: String::new(),
+        });
+    }
+    entries
+}
+
+/// 遍历 `/proc/<pid>/fd` 建立 socket inode 到进程的映射，无权限读取的进程会被跳过
+#[cfg(target_os = "linux")]
+fn socket_inodes() -> HashMap<u64, u32> {
+    let mut inodes = HashMap::new();
+    let Ok(procs) = fs::read_dir("/proc") else {
+        return inodes;
+    };
+    for proc in procs.flatten() {
+        let Some(pid) = proc.file_name().to_str().and_then(|s| s.parse::<u32>().ok()) else {
+            continue;
+        };
+        let Ok(fds) = fs::read_dir(proc.path().join("fd")) else {
+            continue;
+        };
+        for fd in fds.flatten() {
+            let Ok(link) = fs::read_link(fd.path()) else {
+                continue;
+            };
+            let link = link.to_string_lossy();
+            if let Some(inode) = link
+                .strip_prefix("socket:[")
+                .and_then(|s| s.strip_suffix(']'))
+                .and_then(|s| s.parse::<u64>().ok())
+            {
+                inodes.entry(inode).or_insert(pid);
+            }
+        }
+    }
+    inodes
+}
+
+#[cfg(target_os = "linux")]
+pub fn list_sockets() -> std::io::Result<Vec<SocketEntry>> {
+    let mut entries = Vec::new();
+    for (file, protocol) in [
+        ("tcp", Protocol::Tcp),
+        ("tcp6", Protocol::Tcp),
+        ("udp", Protocol::Udp),
+        ("udp6", Protocol::Udp),
+    ] {
+        match fs::read_to_string(Path::new("/proc/net").join(file)) {
+            Ok(content) => entries.extend(parse_proc_net(&content, protocol)),
+            // 内核未启用 IPv6 时 tcp6/udp6 不存在
+            Err(e) if e.kind() == ErrorKind::NotFound => {}
+            Err(e) => return Err(e),
+        }
+    }
+
+    let inodes = socket_inodes();
+    let mut sys = System::new();
+    sys.refresh_processes_specifics(ProcessesToUpdate::All, true, ProcessRefreshKind::nothing());
+    for entry in &mut entries {
+        entry.pid = inodes.get(&entry.inode).copied();
+        if let Some(process) = entry.pid.and_then(|pid| sys.process(Pid::from_u32(pid))) {
+            entry.process = process.name().to_string_lossy().into_owned();
+        }
+    }
+
+    entries.sort_by_key(|e| (e.state != "LISTEN", e.local.port(), e.protocol == Protocol::Udp));
+    Ok(entries)
+}
+
+#[cfg(not(target_os = "linux"))]
+pub fn list_sockets() -> std::io::Result<Vec<SocketEntry>> {
+    Err(Error::new(ErrorKind::Unsupported, "当前系统暂不支持端口占用查询"))
+}
+
+fn signal_name(signal: Signal) -> &'static str {
+    match signal {
+        Signal::Kill => "SIGKILL",
+        _ => "SIGTERM",
+    }
+}
+
+/// 向指定进程发送信号
+pub fn signal_process(
+    pid: u32,
+    signal: Signal,
+) -> std::io::Result<()> {
+    let pid = Pid::from_u32(pid);
+    let mut sys = System::new();
+    sys.refresh_processes_specifics(ProcessesToUpdate::Some(&[pid]), true, ProcessRefreshKind::nothing());
 
-use crate::{MainView, comps::page};
+    let Some(process) = sys.process(pid) else {
+        return Err(Error::new(ErrorKind::NotFound, format!("进程 {} 不存在", pid)));
+    };
+    match process.kill_with(signal) {
+        Some(true) => Ok(()),
+        Some(false) => Err(Error::new(ErrorKind::PermissionDenied, "发送信号失败，可能没有权限")),
+        None => Err(Error::new(ErrorKind::Unsupported, "当前系统不支持该信号")),
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum PortState {
+    Open,
+    Closed,
+    /// 连接超时，通常是被防火墙丢弃
+    Filtered,
+}
+
+impl fmt::Display for PortState {
+    fn fmt(
+        &self,
+        f: &mut fmt::Formatter<'_>,
+    ) -> fmt::Result {
+        match self {
+            PortState::Open => f.write_str("开放"),
+            PortState::Closed => f.write_str("关闭"),
+            PortState::Filtered => f.write_str("超时"),
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct ScanResult {
+    pub port: u16,
+    pub state: PortState,
+    pub elapsed: Duration,
+}
 
-pub struct PortPage;
+/// 解析端口范围，支持 `8080` 和 `1-1024` 两种写法
+pub fn parse_port_range(s: &str) -> std::io::Result<RangeInclusive<u16>> {
+    let s = s.trim();
+    let (start, end) = s.split_once('-').unwrap_or((s, s));
+    let parse = |v: &str| {
+        v.trim()
+            .parse::<u16>()
+            .ok()
+            .filter(|port| *port > 0)
+            .ok_or_else(|| Error::new(ErrorKind::InvalidInput, format!("无效的端口: {}", v.trim())))
+    };
+    let (start, end) = (parse(start)?, parse(end)?);
+    if start > end {
+        return Err(Error::new(ErrorKind::InvalidInput, format!("无效的端口范围: {}", s)));
+    }
+    Ok(start..=end)
+}
+
+pub fn resolve_host(host: &str) -> std::io::Result<IpAddr> {
+    let host = host.trim().trim_start_matches('[').trim_end_matches(']');
+    (host, 0)
+        .to_socket_addrs()?
+        .next()
+        .map(|addr| addr.ip())
+        .ok_or_else(|| Error::new(ErrorKind::NotFound, format!("无法解析主机: {}", host)))
+}
+
+/// 扫描时同时进行的连接数上限，避免大范围扫描耗尽文件描述符
+pub const MAX_SCAN_CONCURRENCY: usize = 512;
+
+/// 以 TCP connect 方式并发扫描端口，结果按端口排序
+pub fn scan_ports(
+    ip: IpAddr,
+    ports: RangeInclusive<u16>,
+    concurrency: usize,
+    timeout: Duration,
+) -> Vec<ScanResult> {
+    let ports: Vec<u16> = ports.collect();
+    let next = AtomicUsize::new(0);
+    let results = Mutex::new(Vec::with_capacity(ports.len()));
+    let workers = concurrency.clamp(1, MAX_SCAN_CONCURRENCY).min(ports.len().max(1));
+
+    thread::scope(|s| {
+        for _ in 0..workers {
+            s.spawn(|| {
+                loop {
+                    let i = next.fetch_add(1, Ordering::Relaxed);
+                    let Some(port) = ports.get(i).copied() else {
+                        break;
+                    };
+                    let started = Instant::now();
+                    let state = match TcpStream::connect_timeout(&SocketAddr::new(ip, port), timeout) {
+                        Ok(_) => PortState::Open,
+                        Err(e) if matches!(e.kind(), ErrorKind::TimedOut | ErrorKind::WouldBlock) => {
+                            PortState::Filtered
+                        }
+                        Err(_) => PortState::Closed,
+                    };
+                    let result = ScanResult {
+                        port,
+                        state,
+                        elapsed: started.elapsed(),
+                    };
+                    results.lock().expect("results lock poisoned").push(result);
+                }
+            });
+        }
+    });
+
+    let mut results = results.into_inner().expect("results lock poisoned");
+    results.sort_by_key(|r| r.port);
+    results
+}
+
+/// 在范围内依次查找可在本机绑定的空闲 TCP 端口，最多返回 `count` 个
+pub fn free_ports(
+    ports: RangeInclusive<u16>,
+    count: usize,
+) -> Vec<u16> {
+    ports.filter(|port| is_port_free(*port)).take(count).collect()
+}
+
+/// 在通配地址上试绑定，任意地址上已被监听的端口都会绑定失败
+fn is_port_free(port: u16) -> bool {
+    if TcpListener::bind((Ipv4Addr::UNSPECIFIED, port)).is_err() {
+        return false;
+    }
+    // 未启用 IPv6 时只检查 IPv4
+    match TcpListener::bind((Ipv6Addr::UNSPECIFIED, port)) {
+        Ok(_) => true,
+        Err(e) => !matches!(e.kind(), ErrorKind::AddrInUse | ErrorKind::PermissionDenied),
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum PortMode {
+    Sockets,
+    Scan,
+    Free,
+}
+
+pub struct PortPage {
+    mode: PortMode,
+
+    port_filter: Entity<InputState>,
+    process_filter: Entity<InputState>,
+    entries: Vec<SocketEntry>,
+    error: Option<String>,
+    loading: bool,
+
+    scan_host: Entity<InputState>,
+    scan_range: Entity<InputState>,
+    scan_concurrency: Entity<InputState>,
+    scan_timeout: Entity<InputState>,
+    scan_results: Vec<ScanResult>,
+    scan_summary: Option<String>,
+    scanning: bool,
+
+    free_range: Entity<InputState>,
+    free_count: Entity<InputState>,
+    free_result: Option<String>,
+    finding: bool,
+}
 
 impl PortPage {
     pub fn build(
-        _window: &mut Window,
+        window: &mut Window,
         cx: &mut Context<MainView>,
     ) -> AnyView {
-        AnyView::from(cx.new(|_| PortPage))
+        AnyView::from(cx.new(|cx| {
+            let port_filter = cx.new(|cx| InputState::new(window, cx).placeholder("端口号，例如: 8080"));
+            let process_filter = cx.new(|cx| InputState::new(window, cx).placeholder("进程名称，例如: java"));
+
+            let scan_host = cx.new(|cx| InputState::new(window, cx).default_value("127.0.0.1"));
+            let scan_range = cx.new(|cx| InputState::new(window, cx).default_value("1-1024"));
+            let scan_concurrency = cx.new(|cx| InputState::new(window, cx).default_value("200"));
+            let scan_timeout = cx.new(|cx| InputState::new(window, cx).default_value("500"));
+
+            let free_range = cx.new(|cx| InputState::new(window, cx).default_value("8000-9000"));
+            let free_count = cx.new(|cx| InputState::new(window, cx).default_value("5"));
+
+            let mut page = Self {
+                mode: PortMode::Sockets,
+
+                port_filter,
+                process_filter,
+                entries: Vec::new(),
+                error: None,
+                loading: false,
+
+                scan_host,
+                scan_range,
+                scan_concurrency,
+                scan_timeout,
+                scan_results: Vec::new(),
+                scan_summary: None,
+                scanning: false,
+
+                free_range,
+                free_count,
+                free_result: None,
+                finding: false,
+            };
+            page.refresh(window, cx);
+            page
+        }))
+    }
+
+    fn refresh(
+        &mut self,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        if self.loading {
+            return;
+        }
+        self.loading = true;
+        cx.notify();
+
+        cx.spawn_in(window, async move |this, cx| {
+            let result = cx.background_executor().spawn(async move { list_sockets() }).await;
+
+            let _ = cx.update(|_window, cx| {
+                let _ = this.update(cx, |this, cx| {
+                    match result {
+                        Ok(entries) => {
+                            this.entries = entries;
+                            this.error = None;
+                        }
+                        Err(e) => {
+                            this.entries.clear();
+                            this.error = Some(e.to_string());
+                        }
+                    }
+                    this.loading = false;
+                    cx.notify();
+                });
+            });
+        })
+        .detach();
+    }
+
+    fn confirm_signal(
+        &mut self,
+        pid: u32,
+        process: String,
+        signal: Signal,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let page = cx.entity().downgrade();
+        window.open_dialog(cx, move |dialog, _window, _cx| {
+            let page = page.clone();
+            dialog
+                .confirm()
+                .title(format!("发送 {}", signal_name(signal)))
+                .child(format!(
+                    "确定向进程 {}（PID {}）发送 {} 吗？",
+                    process,
+                    pid,
+                    signal_name(signal)
+                ))
+                .on_ok(move |_ev, window, cx| {
+                    let _ = page.update(cx, |this, cx| {
+                        this.send_signal(pid, signal, window, cx);
+                    });
+                    true
+                })
+        });
+    }
+
+    fn send_signal(
+        &mut self,
+        pid: u32,
+        signal: Signal,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        cx.spawn_in(window, async move |this, cx| {
+            let result = cx
+                .background_executor()
+                .spawn(async move { signal_process(pid, signal) })
+                .await;
+            // 留出进程退出和释放端口的时间再刷新
+            cx.background_executor().timer(Duration::from_millis(300)).await;
+
+            let _ = cx.update(|window, cx| {
+                match result {
+                    Ok(()) => window.push_notification(format!("已向 PID {} 发送 {}", pid, signal_name(signal)), cx),
+                    Err(e) => window.push_notification(format!("发送失败: {}", e), cx),
+                }
+                let _ = this.update(cx, |this, cx| {
+                    this.refresh(window, cx);
+                });
+            });
+        })
+        .detach();
+    }
+
+    fn start_scan(
+        &mut self,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        if self.scanning {
+            return;
+        }
+        let host = self.scan_host.read(cx).value().to_string();
+        let range = match parse_port_range(&self.scan_range.read(cx).value()) {
+            Ok(range) => range,
+            Err(e) => {
+                window.push_notification(e.to_string(), cx);
+                return;
+            }
+        };
+        let (Ok(concurrency), Ok(timeout)) = (
+            self.scan_concurrency.read(cx).value().trim().parse::<usize>(),
+            self.scan_timeout.read(cx).value().trim().parse::<u64>(),
+        ) else {
+            window.push_notification("请输入有效的并发数和超时时间", cx);
+            return;
+        };
+        if !(1..=MAX_SCAN_CONCURRENCY).contains(&concurrency) {
+            window.push_notification(format!("并发数需在 1 到 {} 之间", MAX_SCAN_CONCURRENCY), cx);
+            return;
+        }
+
+        self.scanning = true;
+        self.scan_results.clear();
+        self.scan_summary = None;
+        cx.notify();
+
+        cx.spawn_in(window, async move |this, cx| {
+            let result = cx
+                .background_executor()
+                .spawn(async move {
+                    let ip = resolve_host(&host)?;
+                    let started = Instant::now();
+                    let results = scan_ports(ip, range, concurrency, Duration::from_millis(timeout));
+                    Ok::<_, Error>((ip, results, started.elapsed()))
+                })
+                .await;
+
+            let _ = cx.update(|_window, cx| {
+                let _ = this.update(cx, |this, cx| {
+                    match result {
+                        Ok((ip, results, elapsed)) => {
+                            let open = results.iter().filter(|r| r.state == PortState::Open).count();
+                            this.scan_summary = Some(format!(
+                                "{} 共扫描 {} 个端口，开放 {} 个，用时 {:.2} 秒",
+                                ip,
+                                results.len(),
+                                open,
+                                elapsed.as_secs_f64()
+                            ));
+                            this.scan_results = results;
+                        }
+                        Err(e) => this.scan_summary = Some(format!("扫描失败: {}", e)),
+                    }
+                    this.scanning = false;
+                    cx.notify();
+                });
+            });
+        })
+        .detach();
+    }
+
+    fn find_free(
+        &mut self,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        if self.finding {
+            return;
+        }
+        let range = match parse_port_range(&self.free_range.read(cx).value()) {
+            Ok(range) => range,
+            Err(e) => {
+                window.push_notification(e.to_string(), cx);
+                return;
+            }
+        };
+        let Ok(count) = self.free_count.read(cx).value().trim().parse::<usize>() else {
+            window.push_notification("请输入有效的数量", cx);
+            return;
+        };
+
+        self.finding = true;
+        cx.notify();
+
+        cx.spawn_in(window, async move |this, cx| {
+            let ports = cx
+                .background_executor()
+                .spawn(async move { free_ports(range, count) })
+                .await;
+
+            let _ = cx.update(|_window, cx| {
+                let _ = this.update(cx, |this, cx| {
+                    this.free_result = Some(if ports.is_empty() {
+                        "范围内没有空闲端口".to_string()
+                    } else {
+                        ports.iter().map(|p| p.to_string()).collect::<Vec<_>>().join(", ")
+                    });
+                    this.finding = false;
+                    cx.notify();
+                });
+            });
+        })
+        .detach();
+    }
+
+    fn filtered(
+        &self,
+        cx: &App,
+    ) -> Vec<SocketEntry> {
+        let port = self.port_filter.read(cx).value().trim().parse::<u16>().ok();
+        let process = self.process_filter.read(cx).value().trim().to_lowercase();
+
+        self.entries
+            .iter()
+            .filter(|e| match port {
+                Some(port) => e.local.port() == port || e.remote.is_some_and(|r| r.port() == port),
+                None => true,
+            })
+            .filter(|e| process.is_empty() || e.process.to_lowercase().contains(&process))
+            .cloned()
+            .collect()
+    }
+}
+
+fn socket_row(
+    cells: [String; 6],
+    header: bool,
+) -> Div {
+    let [protocol, local, remote, state, pid, process] = cells;
+    div()
+        .flex()
+        .gap_4()
+        .py_1()
+        .border_b_1()
+        .border_color(rgb(0x404040))
+        .text_sm()
+        .text_color(if header { rgb(0x808080) } else { rgb(0xffffff) })
+        .child(div().w_12().child(protocol))
+        .child(div().w_64().truncate().child(local))
+        .child(div().w_64().truncate().child(remote))
+        .child(div().w_32().child(state))
+        .child(div().w_16().child(pid))
+        .child(div().flex_1().min_w_0().truncate().child(process))
+}
+
+impl PortPage {
+    fn render_sockets(
+        &mut self,
+        cx: &mut Context<Self>,
+    ) -> Div {
+        let loading = self.loading;
+        let entries = self.filtered(cx);
+
+        let mut table = card()
+            .flex_1()
+            .child(label(format!("套接字列表（{} 条）", entries.len())));
+        if let Some(err) = self.error.as_ref() {
+            table = table.child(label(format!("读取失败: {}", err)));
+        } else {
+            table = table
+                .child(
+                    socket_row(
+                        [
+                            "协议".into(),
+                            "本地地址".into(),
+                            "远程地址".into(),
+                            "状态".into(),
+                            "PID".into(),
+                            "进程".into(),
+                        ],
+                        true,
+                    )
+                    .child(div().w_40().child("操作")),
+                )
+                .child(
+                    div()
+                        .id("port-sockets")
+                        .flex_1()
+                        .min_h_0()
+                        .overflow_y_scroll()
+                        .flex()
+                        .flex_col()
+                        .children(entries.into_iter().enumerate().map(|(i, e)| {
+                            let actions = e.pid.map(|pid| {
+                                div()
+                                    .flex()
+                                    .gap_2()
+                                    .children([Signal::Term, Signal::Kill].into_iter().map(|signal| {
+                                        let process = e.process.clone();
+                                        button(cx, (signal_name(signal), i))
+                                            .h_6()
+                                            .label(if signal == Signal::Kill {
+                                                "强制结束"
+                                            } else {
+                                                "结束"
+                                            })
+                                            .on_click(cx.listener(move |this, _ev, window, cx| {
+                                                this.confirm_signal(pid, process.clone(), signal, window, cx);
+                                            }))
+                                    }))
+                            });
+                            socket_row(
+                                [
+                                    e.protocol.to_string(),
+                                    e.local.to_string(),
+                                    e.remote.map(|r| r.to_string()).unwrap_or_else(|| "*".to_string()),
+                                    e.state.to_string(),
+                                    e.pid.map(|pid| pid.to_string()).unwrap_or_else(|| "-".to_string()),
+                                    e.process,
+                                ],
+                                false,
+                            )
+                            .items_center()
+                            .child(div().w_40().children(actions))
+                        })),
+                );
+        }
+
+        div()
+            .flex()
+            .flex_col()
+            .flex_1()
+            .gap_4()
+            .min_h_0()
+            .child(
+                card().child(
+                    div()
+                        .flex()
+                        .gap_4()
+                        .items_center()
+                        .child(label("端口"))
+                        .child(textarea(&self.port_filter, |input| input))
+                        .child(label("进程"))
+                        .child(textarea(&self.process_filter, |input| input))
+                        .child(
+                            button(cx, "port-refresh")
+                                .disabled(loading)
+                                .label(if loading { "刷新中..." } else { "刷新" })
+                                .on_click(cx.listener(|this, _ev, window, cx| {
+                                    this.refresh(window, cx);
+                                })),
+                        ),
+                ),
+            )
+            .child(table)
+    }
+
+    fn render_scan(
+        &mut self,
+        cx: &mut Context<Self>,
+    ) -> Div {
+        let scanning = self.scanning;
+        let open: Vec<&ScanResult> = self
+            .scan_results
+            .iter()
+            .filter(|r| r.state == PortState::Open)
+            .collect();
+
+        div()
+            .flex()
+            .flex_col()
+            .flex_1()
+            .gap_4()
+            .min_h_0()
+            .child(
+                card().child(
+                    div()
+                        .flex()
+                        .gap_4()
+                        .items_center()
+                        .child(label("主机"))
+                        .child(textarea(&self.scan_host, |input| input))
+                        .child(label("端口范围"))
+                        .child(textarea(&self.scan_range, |input| input))
+                        .child(label("并发数"))
+                        .child(textarea(&self.scan_concurrency, |input| input))
+                        .child(label("超时（毫秒）"))
+                        .child(textarea(&self.scan_timeout, |input| input))
+                        .child(
+                            button(cx, "port-scan")
+                                .disabled(scanning)
+                                .label(if scanning { "扫描中..." } else { "扫描" })
+                                .on_click(cx.listener(|this, _ev, window, cx| {
+                                    this.start_scan(window, cx);
+                                })),
+                        ),
+                ),
+            )
+            .child(
+                card()
+                    .flex_1()
+                    .child(label(
+                        self.scan_summary.clone().unwrap_or_else(|| "开放端口".to_string()),
+                    ))
+                    .child(
+                        div()
+                            .id("port-scan-results")
+                            .flex_1()
+                            .min_h_0()
+                            .overflow_y_scroll()
+                            .flex()
+                            .flex_col()
+                            .children(open.into_iter().map(|r| {
+                                div()
+                                    .flex()
+                                    .gap_4()
+                                    .py_1()
+                                    .border_b_1()
+                                    .border_color(rgb(0x404040))
+                                    .text_sm()
+                                    .text_color(rgb(0xffffff))
+                                    .child(div().w_16().child(r.port.to_string()))
+                                    .child(div().w_16().child(r.state.to_string()))
+                                    .child(div().flex_1().child(format!("{} ms", r.elapsed.as_millis())))
+                            })),
+                    ),
+            )
+    }
+
+    fn render_free(
+        &mut self,
+        cx: &mut Context<Self>,
+    ) -> Div {
+        let finding = self.finding;
+        div().flex().flex_col().flex_1().gap_4().min_h_0().child(
+            card()
+                .child(
+                    div()
+                        .flex()
+                        .gap_4()
+                        .items_center()
+                        .child(label("端口范围"))
+                        .child(textarea(&self.free_range, |input| input))
+                        .child(label("数量"))
+                        .child(textarea(&self.free_count, |input| input))
+                        .child(
+                            button(cx, "port-free")
+                                .disabled(finding)
+                                .label(if finding { "查找中..." } else { "查找" })
+                                .on_click(cx.listener(|this, _ev, window, cx| {
+                                    this.find_free(window, cx);
+                                })),
+                        ),
+                )
+                .children(
+                    self.free_result
+                        .clone()
+                        .map(|result| label(format!("空闲端口: {}", result))),
+                ),
+        )
     }
 }
 
@@ -17,8 +872,151 @@ impl Render for PortPage {
     fn render(
         &mut self,
         _window: &mut Window,
-        _cx: &mut Context<Self>,
+        cx: &mut Context<Self>,
     ) -> impl IntoElement {
+        let mode = self.mode;
+        let content = match mode {
+            PortMode::Sockets => self.render_sockets(cx),
+            PortMode::Scan => self.render_scan(cx),
+            PortMode::Free => self.render_free(cx),
+        };
+
         page()
+            .size_full()
+            .child(
+                card().child(
+                    div()
+                        .flex()
+                        .items_center()
+                        .justify_between()
+                        .child(label("功能"))
+                        .child(
+                            RadioGroup::horizontal("port-mode")
+                                .selected_index(match mode {
+                                    PortMode::Sockets => Some(0),
+                                    PortMode::Scan => Some(1),
+                                    PortMode::Free => Some(2),
+                                })
+                                .child("占用查询")
+                                .child("端口扫描")
+                                .child("空闲端口")
+                                .on_click(cx.listener(|this, index: &usize, _window, cx| {
+                                    this.mode = match *index {
+                                        1 => PortMode::Scan,
+                                        2 => PortMode::Free,
+                                        _ => PortMode::Sockets,
+                                    };
+                                    cx.notify();
+                                })),
+                        ),
+                ),
+            )
+            .child(content)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        net::{Ipv4Addr, Ipv6Addr, TcpListener},
+        time::Duration,
+    };
+
+    use super::{PortState, free_ports, parse_port_range, parse_proc_addr, scan_ports};
+
+    /// 找一个当前没有被监听的端口
+    fn closed_port() -> u16 {
+        TcpListener::bind((Ipv4Addr::LOCALHOST, 0))
+            .and_then(|listener| listener.local_addr())
+            .map(|addr| addr.port())
+            .unwrap()
+    }
+
+    #[test]
+    fn scan_reports_listening_port_open() {
+        let listener = TcpListener::bind((Ipv4Addr::LOCALHOST, 0)).unwrap();
+        let open = listener.local_addr().unwrap().port();
+        let closed = closed_port();
+
+        let results = scan_ports(Ipv4Addr::LOCALHOST.into(), open..=open, 4, Duration::from_millis(500));
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].port, open);
+        assert_eq!(results[0].state, PortState::Open);
+
+        let results = scan_ports(
+            Ipv4Addr::LOCALHOST.into(),
+            closed..=closed,
+            4,
+            Duration::from_millis(500),
+        );
+        assert_eq!(results[0].state, PortState::Closed);
+    }
+
+    #[test]
+    fn scan_results_are_sorted_and_complete() {
+        let listener = TcpListener::bind((Ipv4Addr::LOCALHOST, 0)).unwrap();
+        let open = listener.local_addr().unwrap().port();
+        let start = open.saturating_sub(10).max(1);
+        let end = open.saturating_add(10);
+
+        let results = scan_ports(Ipv4Addr::LOCALHOST.into(), start..=end, 3, Duration::from_millis(500));
+        let ports: Vec<u16> = results.iter().map(|r| r.port).collect();
+        assert_eq!(ports, (start..=end).collect::<Vec<_>>());
+        assert!(results.iter().any(|r| r.port == open && r.state == PortState::Open));
+    }
+
+    #[test]
+    fn free_ports_skip_listening_ports() {
+        // 只监听回环地址的端口也不能算作空闲
+        let loopback = TcpListener::bind((Ipv4Addr::LOCALHOST, 0)).unwrap();
+        let port = loopback.local_addr().unwrap().port();
+        assert!(free_ports(port..=port, 1).is_empty());
+
+        let any = TcpListener::bind((Ipv4Addr::UNSPECIFIED, 0)).unwrap();
+        let port = any.local_addr().unwrap().port();
+        assert!(free_ports(port..=port, 1).is_empty());
+
+        if let Ok(v6) = TcpListener::bind((Ipv6Addr::UNSPECIFIED, 0)) {
+            let port = v6.local_addr().unwrap().port();
+            assert!(free_ports(port..=port, 1).is_empty());
+        }
+    }
+
+    #[test]
+    fn free_ports_returns_bindable_ports() {
+        let listener = TcpListener::bind((Ipv4Addr::UNSPECIFIED, 0)).unwrap();
+        let held = listener.local_addr().unwrap().port();
+        let start = held.saturating_sub(20).max(1024);
+        let end = held.saturating_add(20);
+
+        let ports = free_ports(start..=end, 5);
+        assert!(!ports.is_empty() && ports.len() <= 5);
+        assert!(!ports.contains(&held));
+        assert!(ports.windows(2).all(|w| w[0] < w[1]));
+        for port in ports {
+            TcpListener::bind((Ipv4Addr::UNSPECIFIED, port)).unwrap();
+        }
+    }
+
+    #[test]
+    fn port_ranges() {
+        assert_eq!(parse_port_range("8080").unwrap(), 8080..=8080);
+        assert_eq!(parse_port_range(" 1 - 1024 ").unwrap(), 1..=1024);
+        assert!(parse_port_range("0-10").is_err());
+        assert!(parse_port_range("100-10").is_err());
+        assert!(parse_port_range("1-70000").is_err());
+    }
+
+    #[test]
+    fn proc_addresses() {
+        assert_eq!(
+            parse_proc_addr("0100007F:1F90"),
+            Some("127.0.0.1:8080".parse().unwrap())
+        );
+        assert_eq!(
+            parse_proc_addr("00000000000000000000000001000000:0050"),
+            Some("[::1]:80".parse().unwrap())
+        );
+        assert_eq!(parse_proc_addr("zz:0050"), None);
     }
 }
@@ -1,15 +1,625 @@
+use std::{
+    net::SocketAddr,
+    path::PathBuf,
+    sync::Arc,
+    time::{Duration, SystemTime},
+};
+
+use chrono::{DateTime, Local};
 use gpui::*;
+use gpui_component::{Disableable, WindowExt, input::InputState, radio::RadioGroup};
+use sidecar_share::{AccessLog, DEFAULT_PORT, ShareAuth, ShareHandle, ShareServer, UploadEvent, format_size};
+
+use crate::{
+    MainView,
+    comps::{button, card, label, page, textarea},
+    pages::{
+        devel::qrcode::{QrcodeStyle, encode_qrcode_png},
+        home::interface_addresses,
+    },
+};
 
-use crate::{MainView, comps::page};
+const MAX_LOG_LINES: usize = 500;
+const POLL_INTERVAL: Duration = Duration::from_millis(500);
+const DEFAULT_MAX_SIZE_MB: u64 = 1024;
 
-pub struct SharePage;
+fn format_log(log: &AccessLog) -> String {
+    format!("{}  {}", format_time(log.time), log)
+}
+
+fn format_time(time: SystemTime) -> String {
+    DateTime::<Local>::from(time).format("%H:%M:%S").to_string()
+}
+
+/// 解析可选的正整数，留空表示不限制
+fn parse_limit(
+    state: &Entity<InputState>,
+    cx: &App,
+) -> Option<Option<u64>> {
+    let value = state.read(cx).value();
+    let value = value.trim();
+    if value.is_empty() {
+        return Some(None);
+    }
+    value.parse::<u64>().ok().filter(|n| *n > 0).map(Some)
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum ShareMode {
+    Serve,
+    Receive,
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum AuthMode {
+    None,
+    Basic,
+    Token,
+}
+
+enum UploadStatus {
+    Receiving,
+    Finished(PathBuf),
+    Failed(String),
+}
+
+struct UploadRow {
+    id: u64,
+    name: String,
+    size: u64,
+    received: u64,
+    status: UploadStatus,
+}
+
+impl UploadRow {
+    fn status_text(&self) -> String {
+        match &self.status {
+            UploadStatus::Receiving => {
+                let percent = (self.received * 100).checked_div(self.size).unwrap_or(100);
+                format!(
+                    "{} / {}（{}%）",
+                    format_size(self.received),
+                    format_size(self.size),
+                    percent
+                )
+            }
+            UploadStatus::Finished(path) => format!("已保存到 {}", path.display()),
+            UploadStatus::Failed(error) => format!("接收失败: {}", error),
+        }
+    }
+}
+
+pub struct SharePage {
+    mode: ShareMode,
+    paths: Vec<PathBuf>,
+    receive_dir: Option<PathBuf>,
+    max_size: Entity<InputState>,
+    auth_mode: AuthMode,
+    username: Entity<InputState>,
+    password: Entity<InputState>,
+    /// 有效期，单位分钟
+    expires: Entity<InputState>,
+    max_downloads: Entity<InputState>,
+    port: Entity<InputState>,
+    handle: Option<ShareHandle>,
+    /// 每次启动递增，用于结束上一次的日志轮询
+    session: usize,
+    urls: Vec<(String, String)>,
+    selected_url: usize,
+    qrcode: Option<Arc<Image>>,
+    uploads: Vec<UploadRow>,
+    logs: Vec<String>,
+}
 
 impl SharePage {
     pub fn build(
-        _window: &mut Window,
+        window: &mut Window,
         cx: &mut Context<MainView>,
     ) -> AnyView {
-        AnyView::from(cx.new(|_| SharePage))
+        AnyView::from(cx.new(|cx| {
+            let port = cx.new(|cx| InputState::new(window, cx).default_value(DEFAULT_PORT.to_string()));
+            let max_size = cx.new(|cx| InputState::new(window, cx).default_value(DEFAULT_MAX_SIZE_MB.to_string()));
+            let username = cx.new(|cx| InputState::new(window, cx).default_value("sidecar"));
+            let password = cx.new(|cx| InputState::new(window, cx).masked(true).placeholder("访问密码"));
+            let expires = cx.new(|cx| InputState::new(window, cx).placeholder("留空不限制"));
+            let max_downloads = cx.new(|cx| InputState::new(window, cx).placeholder("留空不限制"));
+
+            Self {
+                mode: ShareMode::Serve,
+                paths: Vec::new(),
+                receive_dir: None,
+                max_size,
+                auth_mode: AuthMode::None,
+                username,
+                password,
+                expires,
+                max_downloads,
+                port,
+                handle: None,
+                session: 0,
+                urls: Vec::new(),
+                selected_url: 0,
+                qrcode: None,
+                uploads: Vec::new(),
+                logs: Vec::new(),
+            }
+        }))
+    }
+
+    fn choose_paths(
+        &mut self,
+        directories: bool,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let receive = self.mode == ShareMode::Receive;
+        let rx = cx.prompt_for_paths(PathPromptOptions {
+            files: !directories,
+            multiple: !receive,
+            directories,
+            prompt: Some(if directories { "选择文件夹" } else { "选择文件" }.into()),
+        });
+        cx.spawn_in(window, async move |this, cx| {
+            if let Ok(Ok(Some(paths))) = rx.await {
+                let _ = cx.update(|_window, cx| {
+                    let _ = this.update(cx, |this, cx| {
+                        if receive {
+                            this.receive_dir = paths.into_iter().next();
+                        } else {
+                            for path in paths {
+                                if !this.paths.contains(&path) {
+                                    this.paths.push(path);
+                                }
+                            }
+                        }
+                        cx.notify();
+                    });
+                });
+            }
+        })
+        .detach();
+    }
+
+    fn server(
+        &self,
+        cx: &App,
+    ) -> Result<ShareServer, String> {
+        let server = match self.mode {
+            ShareMode::Serve => {
+                if self.paths.is_empty() {
+                    return Err("请先选择要分享的文件或文件夹".to_string());
+                }
+                ShareServer::new(self.paths.clone())
+            }
+            ShareMode::Receive => {
+                let Some(dir) = self.receive_dir.clone() else {
+                    return Err("请先选择保存目录".to_string());
+                };
+                let Ok(max_size) = self.max_size.read(cx).value().trim().parse::<u64>() else {
+                    return Err("请输入有效的大小上限".to_string());
+                };
+                // 每次启动生成新的令牌，旧链接随之失效
+                let token = uuid::Uuid::new_v4().simple().to_string();
+                ShareServer::receive(dir, token)
+                    .map(|server| server.max_upload_size(max_size.saturating_mul(1024 * 1024)))
+            }
+        }
+        .map_err(|e| format!("启动失败: {}", e))?;
+
+        let auth = match self.auth_mode {
+            AuthMode::None => ShareAuth::None,
+            AuthMode::Basic => {
+                let username = self.username.read(cx).value().trim().to_string();
+                let password = self.password.read(cx).value().to_string();
+                if username.is_empty() || username.contains(':') || password.is_empty() {
+                    return Err("请输入有效的用户名和密码".to_string());
+                }
+                ShareAuth::Basic { username, password }
+            }
+            AuthMode::Token => ShareAuth::Token(uuid::Uuid::new_v4().simple().to_string()),
+        };
+        let server = server.auth(auth);
+
+        let Some(expires) = parse_limit(&self.expires, cx) else {
+            return Err("请输入有效的有效期".to_string());
+        };
+        let Some(max_downloads) = parse_limit(&self.max_downloads, cx) else {
+            return Err("请输入有效的下载次数".to_string());
+        };
+        let server = match expires {
+            Some(minutes) => server.expires_in(Duration::from_secs(minutes.saturating_mul(60))),
+            None => server,
+        };
+        let server = match max_downloads {
+            Some(count) if self.mode == ShareMode::Serve => server.max_downloads(count),
+            _ => server,
+        };
+        Ok(server)
+    }
+
+    fn start(
+        &mut self,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let server = match self.server(cx) {
+            Ok(server) => server,
+            Err(e) => {
+                window.push_notification(e, cx);
+                return;
+            }
+        };
+        let Ok(port) = self.port.read(cx).value().trim().parse::<u16>() else {
+            window.push_notification("请输入有效的端口", cx);
+            return;
+        };
+
+        let handle = match server.port(port).start() {
+            Ok(handle) => handle,
+            Err(e) => {
+                window.push_notification(format!("启动失败: {}", e), cx);
+                return;
+            }
+        };
+
+        let port = handle.local_addr().port();
+        let entry_path = handle.entry_path();
+        self.urls = interface_addresses()
+            .into_iter()
+            .map(|(name, ip)| (name, format!("http://{}{}", SocketAddr::new(ip, port), entry_path)))
+            .collect();
+        self.handle = Some(handle);
+        self.select_url(0);
+        self.session += 1;
+        self.poll_events(self.session, window, cx);
+        cx.notify();
+    }
+
+    fn stop(
+        &mut self,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        if let Some(mut handle) = self.handle.take() {
+            self.append_logs(handle.drain_logs());
+            self.apply_uploads(handle.drain_uploads(), window, cx);
+            // 等待监听线程退出放到后台，避免卡住界面
+            cx.background_executor().spawn(async move { handle.stop() }).detach();
+        }
+        self.urls.clear();
+        self.qrcode = None;
+        cx.notify();
+    }
+
+    fn select_url(
+        &mut self,
+        index: usize,
+    ) {
+        self.selected_url = index;
+        self.qrcode = self
+            .urls
+            .get(index)
+            .and_then(|(_, url)| encode_qrcode_png(url, &QrcodeStyle::default()).ok())
+            .map(|png| Arc::new(Image::from_bytes(ImageFormat::Png, png)));
+    }
+
+    fn poll_events(
+        &mut self,
+        session: usize,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        cx.spawn_in(window, async move |this, cx| {
+            loop {
+                cx.background_executor().timer(POLL_INTERVAL).await;
+                let running = cx
+                    .update(|window, cx| {
+                        this.update(cx, |this, cx| {
+                            if this.session != session {
+                                return false;
+                            }
+                            let Some(handle) = this.handle.as_ref() else {
+                                return false;
+                            };
+                            if handle.is_expired() {
+                                this.stop(window, cx);
+                                window.push_notification("分享已过期，服务已停止", cx);
+                                return false;
+                            }
+                            let logs = handle.drain_logs();
+                            let uploads = handle.drain_uploads();
+                            if !logs.is_empty() || !uploads.is_empty() {
+                                this.append_logs(logs);
+                                this.apply_uploads(uploads, window, cx);
+                                cx.notify();
+                            }
+                            true
+                        })
+                        .unwrap_or(false)
+                    })
+                    .unwrap_or(false);
+                if !running {
+                    break;
+                }
+            }
+        })
+        .detach();
+    }
+
+    fn append_logs(
+        &mut self,
+        logs: Vec<AccessLog>,
+    ) {
+        self.logs.extend(logs.iter().map(format_log));
+        if self.logs.len() > MAX_LOG_LINES {
+            let overflow = self.logs.len() - MAX_LOG_LINES;
+            self.logs.drain(..overflow);
+        }
+    }
+
+    fn apply_uploads(
+        &mut self,
+        events: Vec<UploadEvent>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        for event in events {
+            match event {
+                UploadEvent::Started { id, peer, name, size } => {
+                    window.push_notification(format!("{} 开始上传 {}（{}）", peer.ip(), name, format_size(size)), cx);
+                    self.uploads.push(UploadRow {
+                        id,
+                        name,
+                        size,
+                        received: 0,
+                        status: UploadStatus::Receiving,
+                    });
+                }
+                UploadEvent::Progress { id, received } => {
+                    if let Some(row) = self.uploads.iter_mut().find(|row| row.id == id) {
+                        row.received = received;
+                    }
+                }
+                UploadEvent::Finished { id, path } => {
+                    if let Some(row) = self.uploads.iter_mut().find(|row| row.id == id) {
+                        window.push_notification(format!("接收完成: {}", row.name), cx);
+                        row.received = row.size;
+                        row.status = UploadStatus::Finished(path);
+                    }
+                }
+                UploadEvent::Failed { id, error } => {
+                    if let Some(row) = self.uploads.iter_mut().find(|row| row.id == id) {
+                        window.push_notification(format!("接收失败: {}，{}", row.name, error), cx);
+                        row.status = UploadStatus::Failed(error);
+                    }
+                }
+            }
+        }
+    }
+
+    fn render_serve(
+        &self,
+        running: bool,
+        cx: &mut Context<Self>,
+    ) -> Div {
+        card()
+            .child(
+                div()
+                    .flex()
+                    .gap_4()
+                    .items_center()
+                    .child(label("分享内容"))
+                    .child(div().flex_1())
+                    .child(
+                        button(cx, "share-files")
+                            .disabled(running)
+                            .label("选择文件")
+                            .on_click(cx.listener(|this, _ev, window, cx| {
+                                this.choose_paths(false, window, cx);
+                            })),
+                    )
+                    .child(
+                        button(cx, "share-dirs")
+                            .disabled(running)
+                            .label("选择文件夹")
+                            .on_click(cx.listener(|this, _ev, window, cx| {
+                                this.choose_paths(true, window, cx);
+                            })),
+                    )
+                    .child(
+                        button(cx, "share-clear")
+                            .disabled(running)
+                            .label("清空")
+                            .on_click(cx.listener(|this, _ev, _window, cx| {
+                                this.paths.clear();
+                                cx.notify();
+                            })),
+                    ),
+            )
+            .children(self.render_items(cx))
+    }
+
+    /// 运行中显示分享条目及撤销按钮，否则显示已选择的路径
+    fn render_items(
+        &self,
+        cx: &mut Context<Self>,
+    ) -> Vec<Div> {
+        let Some(handle) = self.handle.as_ref() else {
+            return self
+                .paths
+                .iter()
+                .map(|path| label(path.display().to_string()).text_color(rgb(0xaaaaaa)))
+                .collect();
+        };
+
+        handle
+            .items()
+            .iter()
+            .enumerate()
+            .map(|(i, item)| {
+                let revoked = handle.is_revoked(&item.name);
+                let name = item.name.clone();
+                div()
+                    .flex()
+                    .gap_4()
+                    .items_center()
+                    .child(label(item.path.display().to_string()).flex_1().text_color(if revoked {
+                        rgb(0x666666)
+                    } else {
+                        rgb(0xaaaaaa)
+                    }))
+                    .child(
+                        button(cx, ("share-revoke", i))
+                            .label(if revoked { "恢复" } else { "撤销" })
+                            .on_click(cx.listener(move |this, _ev, _window, cx| {
+                                if let Some(handle) = this.handle.as_ref() {
+                                    if handle.is_revoked(&name) {
+                                        handle.restore(&name);
+                                    } else {
+                                        handle.revoke(&name);
+                                    }
+                                }
+                                cx.notify();
+                            })),
+                    )
+            })
+            .collect()
+    }
+
+    fn render_access(
+        &self,
+        running: bool,
+        cx: &mut Context<Self>,
+    ) -> Div {
+        card()
+            .child(
+                div().flex().gap_4().items_center().child(label("访问控制")).child(
+                    RadioGroup::horizontal("share-auth")
+                        .disabled(running)
+                        .selected_index(Some(match self.auth_mode {
+                            AuthMode::None => 0,
+                            AuthMode::Basic => 1,
+                            AuthMode::Token => 2,
+                        }))
+                        .child("不需要")
+                        .child("用户名密码")
+                        .child("一次性链接")
+                        .on_click(cx.listener(|this, index: &usize, _window, cx| {
+                            this.auth_mode = match *index {
+                                1 => AuthMode::Basic,
+                                2 => AuthMode::Token,
+                                _ => AuthMode::None,
+                            };
+                            cx.notify();
+                        })),
+                ),
+            )
+            .children((self.auth_mode == AuthMode::Basic).then(|| {
+                div()
+                    .flex()
+                    .gap_4()
+                    .items_center()
+                    .child(label("用户名"))
+                    .child(textarea(&self.username, |input| input.disabled(running)))
+                    .child(label("密码"))
+                    .child(textarea(&self.password, |input| input.disabled(running)))
+            }))
+            .children((self.auth_mode == AuthMode::Token).then(|| {
+                label("链接只能被第一个打开它的浏览器使用，之后再次打开链接会被拒绝").text_color(rgb(0xaaaaaa))
+            }))
+            .child(
+                div()
+                    .flex()
+                    .gap_4()
+                    .items_center()
+                    .child(label("有效期（分钟）"))
+                    .child(textarea(&self.expires, |input| input.disabled(running)))
+                    .children((self.mode == ShareMode::Serve).then(|| label("下载次数")))
+                    .children(
+                        (self.mode == ShareMode::Serve)
+                            .then(|| textarea(&self.max_downloads, |input| input.disabled(running))),
+                    ),
+            )
+    }
+
+    fn status_text(&self) -> Option<String> {
+        let handle = self.handle.as_ref()?;
+        let mut status = Vec::new();
+        if self.mode == ShareMode::Serve {
+            status.push(match handle.max_downloads() {
+                Some(max) => format!("已下载 {} / {} 次", handle.downloads(), max),
+                None => format!("已下载 {} 次", handle.downloads()),
+            });
+        }
+        if let Some(time) = handle.expires_at() {
+            status.push(format!("{} 过期", format_time(time)));
+        }
+        (!status.is_empty()).then(|| status.join("，"))
+    }
+
+    fn render_receive(
+        &self,
+        running: bool,
+        cx: &mut Context<Self>,
+    ) -> Div {
+        let dir = self
+            .receive_dir
+            .as_ref()
+            .map(|dir| dir.display().to_string())
+            .unwrap_or_else(|| "未选择".to_string());
+
+        card()
+            .child(
+                div()
+                    .flex()
+                    .gap_4()
+                    .items_center()
+                    .child(label("保存目录"))
+                    .child(label(dir).flex_1().text_color(rgb(0xaaaaaa)))
+                    .child(
+                        button(cx, "share-receive-dir")
+                            .disabled(running)
+                            .label("选择文件夹")
+                            .on_click(cx.listener(|this, _ev, window, cx| {
+                                this.choose_paths(true, window, cx);
+                            })),
+                    ),
+            )
+            .child(
+                div()
+                    .flex()
+                    .gap_4()
+                    .items_center()
+                    .child(label("大小上限（MB）"))
+                    .child(textarea(&self.max_size, |input| input.disabled(running))),
+            )
+    }
+
+    fn render_uploads(&self) -> Div {
+        card().flex_1().child(label("接收记录")).child(
+            div()
+                .id("share-uploads")
+                .flex_1()
+                .min_h_0()
+                .overflow_y_scroll()
+                .flex()
+                .flex_col()
+                .gap_2()
+                .children(self.uploads.iter().map(|row| {
+                    div()
+                        .flex()
+                        .gap_4()
+                        .text_sm()
+                        .child(div().w_64().truncate().text_color(white()).child(row.name.clone()))
+                        .child(
+                            div()
+                                .flex_1()
+                                .truncate()
+                                .text_color(rgb(0xaaaaaa))
+                                .child(row.status_text()),
+                        )
+                })),
+        )
     }
 }
 
@@ -17,8 +627,111 @@ impl Render for SharePage {
     fn render(
         &mut self,
         _window: &mut Window,
-        _cx: &mut Context<Self>,
+        cx: &mut Context<Self>,
     ) -> impl IntoElement {
-        page().size_full()
+        let running = self.handle.is_some();
+        let source = match self.mode {
+            ShareMode::Serve => self.render_serve(running, cx),
+            ShareMode::Receive => self.render_receive(running, cx),
+        };
+
+        page()
+            .size_full()
+            .child(
+                card().child(
+                    div().flex().gap_4().items_center().child(label("模式")).child(
+                        RadioGroup::horizontal("share-mode")
+                            .disabled(running)
+                            .selected_index(Some(match self.mode {
+                                ShareMode::Serve => 0,
+                                ShareMode::Receive => 1,
+                            }))
+                            .child("分享文件")
+                            .child("接收文件")
+                            .on_click(cx.listener(|this, index: &usize, _window, cx| {
+                                this.mode = if *index == 1 {
+                                    ShareMode::Receive
+                                } else {
+                                    ShareMode::Serve
+                                };
+                                cx.notify();
+                            })),
+                    ),
+                ),
+            )
+            .child(source)
+            .child(self.render_access(running, cx))
+            .child(
+                card()
+                    .child(
+                        div()
+                            .flex()
+                            .gap_4()
+                            .items_center()
+                            .child(label("端口"))
+                            .child(textarea(&self.port, |input| input.disabled(running)))
+                            .child(if running {
+                                button(cx, "share-stop").label("停止服务").on_click(cx.listener(
+                                    |this, _ev, window, cx| {
+                                        this.stop(window, cx);
+                                    },
+                                ))
+                            } else {
+                                button(cx, "share-start").label("启动服务").on_click(cx.listener(
+                                    |this, _ev, window, cx| {
+                                        this.start(window, cx);
+                                    },
+                                ))
+                            }),
+                    )
+                    .children(self.status_text().map(|status| label(status).text_color(rgb(0xaaaaaa))))
+                    .children(running.then(|| {
+                        div()
+                            .flex()
+                            .gap_4()
+                            .child(
+                                div()
+                                    .flex()
+                                    .flex_col()
+                                    .flex_1()
+                                    .gap_1()
+                                    .children(self.urls.iter().enumerate().map(|(i, (name, url))| {
+                                        div()
+                                            .id(("share-url", i))
+                                            .px_2()
+                                            .py_1()
+                                            .rounded_md()
+                                            .text_sm()
+                                            .text_color(white())
+                                            .cursor_pointer()
+                                            .bg(if i == self.selected_url {
+                                                rgb(0x404040)
+                                            } else {
+                                                rgb(0x333333)
+                                            })
+                                            .hover(|this| this.bg(rgb(0x3a3a3a)))
+                                            .child(format!("{}：{}", name, url))
+                                            .on_click(cx.listener(move |this, _ev, _window, cx| {
+                                                this.select_url(i);
+                                                cx.notify();
+                                            }))
+                                    })),
+                            )
+                            .children(self.qrcode.clone().map(|qrcode| img(qrcode).size_48().rounded_md()))
+                    })),
+            )
+            .children((!self.uploads.is_empty()).then(|| self.render_uploads()))
+            .child(
+                card().flex_1().child(label("访问日志")).child(
+                    div()
+                        .id("share-logs")
+                        .flex_1()
+                        .min_h_0()
+                        .overflow_y_scroll()
+                        .text_sm()
+                        .text_color(rgb(0xaaaaaa))
+                        .children(self.logs.iter().map(|line| div().child(line.clone()))),
+                ),
+            )
     }
 }
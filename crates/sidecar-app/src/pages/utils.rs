@@ -21,3 +21,50 @@ pub fn format_bytes(bytes: u64) -> String {
 
     format!("{:.2} {}", size, UNITS[unit_index])
 }
+
+/// 按 URI 组件编码，只保留非保留字符
+pub fn percent_encode(s: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+    for &b in s.as_bytes() {
+        match b {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' => out.push(b as char),
+            _ => out.push_str(&format!("%{:02X}", b)),
+        }
+    }
+    out
+}
+
+/// 解码 `%XX` 转义，转义不完整或结果不是 UTF-8 时返回 None
+pub fn percent_decode(s: &str) -> Option<String> {
+    let bytes = s.as_bytes();
+    let mut out = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes[i] == b'%' {
+            let hex = s.get(i + 1..i + 3)?;
+            out.push(u8::from_str_radix(hex, 16).ok()?);
+            i += 3;
+        } else {
+            out.push(bytes[i]);
+            i += 1;
+        }
+    }
+    String::from_utf8(out).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn percent_round_trip() {
+        assert_eq!(percent_encode("a b/c?d=测"), "a%20b%2Fc%3Fd%3D%E6%B5%8B");
+        assert_eq!(
+            percent_decode("a%20b%2Fc%3Fd%3D%E6%B5%8B").as_deref(),
+            Some("a b/c?d=测")
+        );
+        assert_eq!(percent_decode("100%"), None);
+        assert_eq!(percent_decode("%zz"), None);
+        assert_eq!(percent_decode("%FF"), None);
+    }
+}
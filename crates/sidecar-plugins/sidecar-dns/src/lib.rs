@@ -0,0 +1,15 @@
+mod message;
+mod record;
+mod resolver;
+#[cfg(test)]
+mod testing;
+mod trace;
+mod wire;
+
+pub use message::{Header, Message, Question, Rcode, is_truncated};
+pub use record::{CLASS_IN, Record, RecordData, RecordType, class_name};
+pub use resolver::{
+    DEFAULT_SERVER, DNS_PORT, Resolver, Response, Transport, parse_server, system_server, write_records,
+};
+pub use trace::{Hop, Outcome, Referral, RootHint, Trace, Tracer, default_root_hints};
+pub use wire::{fqdn, reverse_name};
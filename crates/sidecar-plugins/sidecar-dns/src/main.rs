@@ -1,3 +1,60 @@
+use std::{env, net::IpAddr, process};
+
+use sidecar_dns::{RecordType, Resolver, Tracer, Transport, parse_server, reverse_name};
+
+const USAGE: &str = "usage: sidecar-dns [@server] [type] name [+tcp|+notcp|+norecurse|+trace]";
+
 fn main() {
+    let mut resolver = Resolver::system();
+    let mut name = None;
+    let mut qtype = RecordType::A;
+    let mut trace = false;
+
+    for arg in env::args().skip(1) {
+        if let Some(server) = arg.strip_prefix('@') {
+            match parse_server(server) {
+                Ok(addr) => resolver = Resolver::new(addr),
+                Err(e) => fail(&e.to_string()),
+            }
+        } else if let Some(option) = arg.strip_prefix('+') {
+            resolver = match option {
+                "tcp" => resolver.transport(Transport::Tcp),
+                "notcp" => resolver.transport(Transport::Udp),
+                "norecurse" => resolver.recursion(false),
+                "trace" => {
+                    trace = true;
+                    resolver
+                }
+                _ => fail(&format!("unknown option: {}", arg)),
+            };
+        } else if let Ok(t) = arg.parse::<RecordType>() {
+            qtype = t;
+        } else {
+            name = Some(arg);
+        }
+    }
+
+    let Some(mut name) = name else {
+        fail(USAGE);
+    };
+    if let Ok(ip) = name.parse::<IpAddr>() {
+        name = reverse_name(ip);
+        qtype = RecordType::PTR;
+    }
+    if trace {
+        match Tracer::new().trace(&name, qtype) {
+            Ok(trace) => println!("{}", trace),
+            Err(e) => fail(&format!(";; trace failed: {}", e)),
+        }
+        return;
+    }
+    match resolver.query(&name, qtype) {
+        Ok(response) => println!("{}", response),
+        Err(e) => fail(&format!(";; query failed: {}", e)),
+    }
+}
 
-}
\ No newline at end of file
+fn fail(message: &str) -> ! {
+    eprintln!("{}", message);
+    process::exit(1);
+}
@@ -0,0 +1,354 @@
+use std::{
+    fmt,
+    io::{Error, ErrorKind, Result},
+};
+
+use crate::{
+    record::{CLASS_IN, Record, RecordType},
+    wire::{Reader, Writer, fqdn},
+};
+
+pub const HEADER_LEN: usize = 12;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Rcode {
+    NoError,
+    FormErr,
+    ServFail,
+    NXDomain,
+    NotImp,
+    Refused,
+    Other(u8),
+}
+
+impl Rcode {
+    pub fn code(self) -> u8 {
+        match self {
+            Rcode::NoError => 0,
+            Rcode::FormErr => 1,
+            Rcode::ServFail => 2,
+            Rcode::NXDomain => 3,
+            Rcode::NotImp => 4,
+            Rcode::Refused => 5,
+            Rcode::Other(code) => code,
+        }
+    }
+
+    pub fn from_code(code: u8) -> Self {
+        match code {
+            0 => Rcode::NoError,
+            1 => Rcode::FormErr,
+            2 => Rcode::ServFail,
+            3 => Rcode::NXDomain,
+            4 => Rcode::NotImp,
+            5 => Rcode::Refused,
+            code => Rcode::Other(code),
+        }
+    }
+}
+
+impl fmt::Display for Rcode {
+    fn fmt(
+        &self,
+        f: &mut fmt::Formatter<'_>,
+    ) -> fmt::Result {
+        match self {
+            Rcode::NoError => f.write_str("NOERROR"),
+            Rcode::FormErr => f.write_str("FORMERR"),
+            Rcode::ServFail => f.write_str("SERVFAIL"),
+            Rcode::NXDomain => f.write_str("NXDOMAIN"),
+            Rcode::NotImp => f.write_str("NOTIMP"),
+            Rcode::Refused => f.write_str("REFUSED"),
+            Rcode::Other(code) => write!(f, "RCODE{}", code),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Header {
+    pub id: u16,
+    pub qr: bool,
+    pub opcode: u8,
+    pub aa: bool,
+    pub tc: bool,
+    pub rd: bool,
+    pub ra: bool,
+    pub ad: bool,
+    pub cd: bool,
+    pub rcode: Rcode,
+}
+
+impl Header {
+    fn flags(&self) -> u16 {
+        let mut flags = ((self.opcode as u16 & 0x0F) << 11) | (self.rcode.code() as u16 & 0x0F);
+        for (set, bit) in [
+            (self.qr, 15),
+            (self.aa, 10),
+            (self.tc, 9),
+            (self.rd, 8),
+            (self.ra, 7),
+            (self.ad, 5),
+            (self.cd, 4),
+        ] {
+            if set {
+                flags |= 1 << bit;
+            }
+        }
+        flags
+    }
+
+    fn from_flags(
+        id: u16,
+        flags: u16,
+    ) -> Self {
+        Self {
+            id,
+            qr: flags & (1 << 15) != 0,
+            opcode: ((flags >> 11) & 0x0F) as u8,
+            aa: flags & (1 << 10) != 0,
+            tc: flags & (1 << 9) != 0,
+            rd: flags & (1 << 8) != 0,
+            ra: flags & (1 << 7) != 0,
+            ad: flags & (1 << 5) != 0,
+            cd: flags & (1 << 4) != 0,
+            rcode: Rcode::from_code((flags & 0x0F) as u8),
+        }
+    }
+
+    /// dig 风格的标志位列表，例如 `qr rd ra`
+    pub fn flag_names(&self) -> Vec<&'static str> {
+        [
+            (self.qr, "qr"),
+            (self.aa, "aa"),
+            (self.tc, "tc"),
+            (self.rd, "rd"),
+            (self.ra, "ra"),
+            (self.ad, "ad"),
+            (self.cd, "cd"),
+        ]
+        .into_iter()
+        .filter_map(|(set, name)| set.then_some(name))
+        .collect()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Question {
+    pub name: String,
+    pub qtype: RecordType,
+    pub qclass: u16,
+}
+
+impl Question {
+    pub fn new(
+        name: &str,
+        qtype: RecordType,
+    ) -> Self {
+        Self {
+            name: fqdn(name),
+            qtype,
+            qclass: CLASS_IN,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Message {
+    pub header: Header,
+    pub questions: Vec<Question>,
+    pub answers: Vec<Record>,
+    pub authorities: Vec<Record>,
+    pub additionals: Vec<Record>,
+}
+
+impl Message {
+    pub fn query(
+        id: u16,
+        question: Question,
+        recursion_desired: bool,
+    ) -> Self {
+        Self {
+            header: Header {
+                id,
+                qr: false,
+                opcode: 0,
+                aa: false,
+                tc: false,
+                rd: recursion_desired,
+                ra: false,
+                ad: false,
+                cd: false,
+                rcode: Rcode::NoError,
+            },
+            questions: vec![question],
+            answers: Vec::new(),
+            authorities: Vec::new(),
+            additionals: Vec::new(),
+        }
+    }
+
+    /// 以请求为模板构造应答，保留 ID、问题和 RD 标志
+    pub fn response(request: &Message) -> Self {
+        let mut header = request.header.clone();
+        header.qr = true;
+        Self {
+            header,
+            questions: request.questions.clone(),
+            answers: Vec::new(),
+            authorities: Vec::new(),
+            additionals: Vec::new(),
+        }
+    }
+
+    pub fn encode(&self) -> Result<Vec<u8>> {
+        let mut writer = Writer::new();
+        writer.u16(self.header.id);
+        writer.u16(self.header.flags());
+        for n in [
+            self.questions.len(),
+            self.answers.len(),
+            self.authorities.len(),
+            self.additionals.len(),
+        ] {
+            let n = u16::try_from(n).map_err(|_| Error::new(ErrorKind::InvalidInput, "too many records"))?;
+            writer.u16(n);
+        }
+
+        for q in &self.questions {
+            writer.name(&q.name)?;
+            writer.u16(q.qtype.code());
+            writer.u16(q.qclass);
+        }
+        for record in self.answers.iter().chain(&self.authorities).chain(&self.additionals) {
+            record.encode(&mut writer)?;
+        }
+        Ok(writer.into_inner())
+    }
+
+    pub fn decode(buf: &[u8]) -> Result<Self> {
+        if buf.len() < HEADER_LEN {
+            return Err(Error::new(ErrorKind::InvalidData, "message shorter than header"));
+        }
+
+        let mut reader = Reader::new(buf);
+        let id = reader.u16()?;
+        let flags = reader.u16()?;
+        let qdcount = reader.u16()?;
+        let ancount = reader.u16()?;
+        let nscount = reader.u16()?;
+        let arcount = reader.u16()?;
+
+        let mut questions = Vec::with_capacity(qdcount as usize);
+        for _ in 0..qdcount {
+            questions.push(Question {
+                name: reader.name()?,
+                qtype: RecordType::from_code(reader.u16()?),
+                qclass: reader.u16()?,
+            });
+        }
+
+        let mut sections = [Vec::new(), Vec::new(), Vec::new()];
+        for (section, count) in sections.iter_mut().zip([ancount, nscount, arcount]) {
+            for _ in 0..count {
+                section.push(Record::decode(&mut reader)?);
+            }
+        }
+        let [answers, authorities, additionals] = sections;
+
+        Ok(Self {
+            header: Header::from_flags(id, flags),
+            questions,
+            answers,
+            authorities,
+            additionals,
+        })
+    }
+}
+
+/// 直接从报文头读取 TC 标志，避免为截断报文做完整解析
+pub fn is_truncated(buf: &[u8]) -> bool {
+    buf.len() >= HEADER_LEN && buf[2] & 0x02 != 0
+}
+
+#[cfg(test)]
+mod tests {
+    use std::net::Ipv4Addr;
+
+    use super::*;
+    use crate::record::RecordData;
+
+    #[test]
+    fn query_encodes_header_and_question() {
+        let query = Message::query(0x1234, Question::new("example.com", RecordType::AAAA), true);
+        let buf = query.encode().unwrap();
+        let mut expected = vec![0x12, 0x34, 0x01, 0x00, 0, 1, 0, 0, 0, 0, 0, 0];
+        expected.extend_from_slice(b"\x07example\x03com\x00\x00\x1C\x00\x01");
+        assert_eq!(buf, expected);
+        assert_eq!(Message::decode(&buf).unwrap(), query);
+    }
+
+    #[test]
+    fn response_round_trip() {
+        let request = Message::query(7, Question::new("example.com", RecordType::A), true);
+        let mut response = Message::response(&request);
+        response.header.ra = true;
+        response.header.aa = true;
+        response.header.rcode = Rcode::NXDomain;
+        response.answers.push(Record::new(
+            "example.com",
+            60,
+            RecordData::A(Ipv4Addr::new(192, 0, 2, 1)),
+        ));
+        response
+            .authorities
+            .push(Record::new("example.com", 60, RecordData::NS("ns.example.com.".into())));
+        response.additionals.push(Record::new(
+            "ns.example.com",
+            60,
+            RecordData::A(Ipv4Addr::new(192, 0, 2, 53)),
+        ));
+
+        assert_eq!(response.header.id, 7);
+        assert!(response.header.qr && response.header.rd);
+        assert_eq!(response.questions, request.questions);
+
+        let decoded = Message::decode(&response.encode().unwrap()).unwrap();
+        assert_eq!(decoded, response);
+        assert_eq!(decoded.header.flag_names(), ["qr", "aa", "rd", "ra"]);
+        assert_eq!(decoded.header.rcode, Rcode::NXDomain);
+    }
+
+    #[test]
+    fn decode_compressed_response() {
+        let mut buf = vec![0x12, 0x34, 0x81, 0x80, 0, 1, 0, 2, 0, 0, 0, 0];
+        // 问题段的域名位于偏移 12
+        buf.extend_from_slice(b"\x07example\x03com\x00\x00\x01\x00\x01");
+        buf.extend_from_slice(b"\xC0\x0C\x00\x01\x00\x01\x00\x00\x00\x3C\x00\x04");
+        buf.extend_from_slice(&[192, 0, 2, 1]);
+        buf.extend_from_slice(b"\x03www\xC0\x0C\x00\x05\x00\x01\x00\x00\x00\x3C\x00\x02\xC0\x0C");
+
+        let message = Message::decode(&buf).unwrap();
+        assert_eq!(message.header.id, 0x1234);
+        assert_eq!(message.questions[0].name, "example.com.");
+        assert_eq!(message.answers[0].name, "example.com.");
+        assert_eq!(message.answers[0].data, RecordData::A(Ipv4Addr::new(192, 0, 2, 1)));
+        assert_eq!(message.answers[1].name, "www.example.com.");
+        assert_eq!(message.answers[1].data, RecordData::CNAME("example.com.".into()));
+    }
+
+    #[test]
+    fn decode_rejects_short_messages() {
+        assert!(Message::decode(&[0; 11]).is_err());
+        // 声明了一个问题但没有内容
+        assert!(Message::decode(&[0, 0, 0, 0, 0, 1, 0, 0, 0, 0, 0, 0]).is_err());
+    }
+
+    #[test]
+    fn truncated_flag() {
+        let mut response = Message::response(&Message::query(1, Question::new(".", RecordType::NS), false));
+        assert!(!is_truncated(&response.encode().unwrap()));
+        response.header.tc = true;
+        assert!(is_truncated(&response.encode().unwrap()));
+        assert!(!is_truncated(&[0xFF; 4]));
+    }
+}
@@ -0,0 +1,524 @@
+use std::{
+    fmt,
+    io::{Error, ErrorKind, Result},
+    net::{Ipv4Addr, Ipv6Addr},
+    str::FromStr,
+};
+
+use crate::wire::{Reader, Writer};
+
+pub const CLASS_IN: u16 = 1;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum RecordType {
+    A,
+    NS,
+    CNAME,
+    SOA,
+    PTR,
+    MX,
+    TXT,
+    AAAA,
+    SRV,
+    CAA,
+    Unknown(u16),
+}
+
+impl RecordType {
+    /// 支持解析的记录类型，按常用程度排列
+    pub const ALL: [RecordType; 10] = [
+        RecordType::A,
+        RecordType::AAAA,
+        RecordType::CNAME,
+        RecordType::MX,
+        RecordType::TXT,
+        RecordType::NS,
+        RecordType::SOA,
+        RecordType::SRV,
+        RecordType::PTR,
+        RecordType::CAA,
+    ];
+
+    pub fn code(self) -> u16 {
+        match self {
+            RecordType::A => 1,
+            RecordType::NS => 2,
+            RecordType::CNAME => 5,
+            RecordType::SOA => 6,
+            RecordType::PTR => 12,
+            RecordType::MX => 15,
+            RecordType::TXT => 16,
+            RecordType::AAAA => 28,
+            RecordType::SRV => 33,
+            RecordType::CAA => 257,
+            RecordType::Unknown(code) => code,
+        }
+    }
+
+    pub fn from_code(code: u16) -> Self {
+        match code {
+            1 => RecordType::A,
+            2 => RecordType::NS,
+            5 => RecordType::CNAME,
+            6 => RecordType::SOA,
+            12 => RecordType::PTR,
+            15 => RecordType::MX,
+            16 => RecordType::TXT,
+            28 => RecordType::AAAA,
+            33 => RecordType::SRV,
+            257 => RecordType::CAA,
+            code => RecordType::Unknown(code),
+        }
+    }
+}
+
+impl fmt::Display for RecordType {
+    fn fmt(
+        &self,
+        f: &mut fmt::Formatter<'_>,
+    ) -> fmt::Result {
+        match self {
+            RecordType::A => f.write_str("A"),
+            RecordType::NS => f.write_str("NS"),
+            RecordType::CNAME => f.write_str("CNAME"),
+            RecordType::SOA => f.write_str("SOA"),
+            RecordType::PTR => f.write_str("PTR"),
+            RecordType::MX => f.write_str("MX"),
+            RecordType::TXT => f.write_str("TXT"),
+            RecordType::AAAA => f.write_str("AAAA"),
+            RecordType::SRV => f.write_str("SRV"),
+            RecordType::CAA => f.write_str("CAA"),
+            RecordType::Unknown(code) => write!(f, "TYPE{}", code),
+        }
+    }
+}
+
+impl FromStr for RecordType {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let upper = s.trim().to_ascii_uppercase();
+        if let Some(t) = RecordType::ALL.iter().find(|t| t.to_string() == upper) {
+            return Ok(*t);
+        }
+        upper
+            .strip_prefix("TYPE")
+            .and_then(|code| code.parse::<u16>().ok())
+            .map(RecordType::from_code)
+            .ok_or_else(|| Error::new(ErrorKind::InvalidInput, format!("unknown record type: {}", s)))
+    }
+}
+
+pub fn class_name(class: u16) -> String {
+    match class {
+        1 => "IN".to_string(),
+        3 => "CH".to_string(),
+        4 => "HS".to_string(),
+        255 => "ANY".to_string(),
+        class => format!("CLASS{}", class),
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RecordData {
+    A(Ipv4Addr),
+    AAAA(Ipv6Addr),
+    NS(String),
+    CNAME(String),
+    PTR(String),
+    MX {
+        preference: u16,
+        exchange: String,
+    },
+    TXT(Vec<String>),
+    SOA {
+        mname: String,
+        rname: String,
+        serial: u32,
+        refresh: u32,
+        retry: u32,
+        expire: u32,
+        minimum: u32,
+    },
+    SRV {
+        priority: u16,
+        weight: u16,
+        port: u16,
+        target: String,
+    },
+    CAA {
+        flags: u8,
+        tag: String,
+        value: String,
+    },
+    /// 未支持的类型，保留类型代码和原始 RDATA
+    Unknown(u16, Vec<u8>),
+}
+
+impl RecordData {
+    pub(crate) fn decode(
+        rtype: RecordType,
+        reader: &mut Reader,
+        len: usize,
+    ) -> Result<Self> {
+        let end = reader.pos() + len;
+        let data = match rtype {
+            RecordType::A => {
+                let b = reader.bytes(4)?;
+                RecordData::A(Ipv4Addr::new(b[0], b[1], b[2], b[3]))
+            }
+            RecordType::AAAA => {
+                let mut octets = [0u8; 16];
+                octets.copy_from_slice(reader.bytes(16)?);
+                RecordData::AAAA(Ipv6Addr::from(octets))
+            }
+            RecordType::NS => RecordData::NS(reader.name()?),
+            RecordType::CNAME => RecordData::CNAME(reader.name()?),
+            RecordType::PTR => RecordData::PTR(reader.name()?),
+            RecordType::MX => RecordData::MX {
+                preference: reader.u16()?,
+                exchange: reader.name()?,
+            },
+            RecordType::TXT => {
+                let mut strings = Vec::new();
+                while reader.pos() < end {
+                    let n = reader.u8()? as usize;
+                    strings.push(String::from_utf8_lossy(reader.bytes(n)?).into_owned());
+                }
+                RecordData::TXT(strings)
+            }
+            RecordType::SOA => RecordData::SOA {
+                mname: reader.name()?,
+                rname: reader.name()?,
+                serial: reader.u32()?,
+                refresh: reader.u32()?,
+                retry: reader.u32()?,
+                expire: reader.u32()?,
+                minimum: reader.u32()?,
+            },
+            RecordType::SRV => RecordData::SRV {
+                priority: reader.u16()?,
+                weight: reader.u16()?,
+                port: reader.u16()?,
+                target: reader.name()?,
+            },
+            RecordType::CAA => {
+                let flags = reader.u8()?;
+                let n = reader.u8()? as usize;
+                let tag = String::from_utf8_lossy(reader.bytes(n)?).into_owned();
+                let rest = end
+                    .checked_sub(reader.pos())
+                    .ok_or_else(|| Error::new(ErrorKind::InvalidData, "malformed CAA record"))?;
+                let value = String::from_utf8_lossy(reader.bytes(rest)?).into_owned();
+                RecordData::CAA { flags, tag, value }
+            }
+            RecordType::Unknown(code) => RecordData::Unknown(code, reader.bytes(len)?.to_vec()),
+        };
+
+        if reader.pos() != end {
+            return Err(Error::new(
+                ErrorKind::InvalidData,
+                format!("rdata length mismatch for {} record", rtype),
+            ));
+        }
+        Ok(data)
+    }
+
+    pub(crate) fn encode(
+        &self,
+        writer: &mut Writer,
+    ) -> Result<()> {
+        match self {
+            RecordData::A(addr) => writer.bytes(&addr.octets()),
+            RecordData::AAAA(addr) => writer.bytes(&addr.octets()),
+            RecordData::NS(name) | RecordData::CNAME(name) | RecordData::PTR(name) => writer.name(name)?,
+            RecordData::MX { preference, exchange } => {
+                writer.u16(*preference);
+                writer.name(exchange)?;
+            }
+            RecordData::TXT(strings) => {
+                for s in strings {
+                    writer.character_string(s.as_bytes())?;
+                }
+            }
+            RecordData::SOA {
+                mname,
+                rname,
+                serial,
+                refresh,
+                retry,
+                expire,
+                minimum,
+            } => {
+                writer.name(mname)?;
+                writer.name(rname)?;
+                for v in [serial, refresh, retry, expire, minimum] {
+                    writer.u32(*v);
+                }
+            }
+            RecordData::SRV {
+                priority,
+                weight,
+                port,
+                target,
+            } => {
+                writer.u16(*priority);
+                writer.u16(*weight);
+                writer.u16(*port);
+                writer.name(target)?;
+            }
+            RecordData::CAA { flags, tag, value } => {
+                writer.u8(*flags);
+                writer.character_string(tag.as_bytes())?;
+                writer.bytes(value.as_bytes());
+            }
+            RecordData::Unknown(_, bytes) => writer.bytes(bytes),
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for RecordData {
+    fn fmt(
+        &self,
+        f: &mut fmt::Formatter<'_>,
+    ) -> fmt::Result {
+        match self {
+            RecordData::A(addr) => write!(f, "{}", addr),
+            RecordData::AAAA(addr) => write!(f, "{}", addr),
+            RecordData::NS(name) | RecordData::CNAME(name) | RecordData::PTR(name) => f.write_str(name),
+            RecordData::MX { preference, exchange } => write!(f, "{} {}", preference, exchange),
+            RecordData::TXT(strings) => {
+                let quoted: Vec<String> = strings.iter().map(|s| quote(s)).collect();
+                f.write_str(&quoted.join(" "))
+            }
+            RecordData::SOA {
+                mname,
+                rname,
+                serial,
+                refresh,
+                retry,
+                expire,
+                minimum,
+            } => write!(
+                f,
+                "{} {} {} {} {} {} {}",
+                mname, rname, serial, refresh, retry, expire, minimum
+            ),
+            RecordData::SRV {
+                priority,
+                weight,
+                port,
+                target,
+            } => write!(f, "{} {} {} {}", priority, weight, port, target),
+            RecordData::CAA { flags, tag, value } => write!(f, "{} {} {}", flags, tag, quote(value)),
+            RecordData::Unknown(_, bytes) => {
+                let hex: String = bytes.iter().map(|b| format!("{:02X}", b)).collect();
+                write!(f, "\\# {} {}", bytes.len(), hex)
+            }
+        }
+    }
+}
+
+fn quote(s: &str) -> String {
+    let mut out = String::with_capacity(s.len() + 2);
+    out.push('"');
+    for c in s.chars() {
+        match c {
+            '"' | '\\' => {
+                out.push('\\');
+                out.push(c);
+            }
+            c if c.is_control() => out.push_str(&format!("\\{:03}", c as u32)),
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+    out
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Record {
+    pub name: String,
+    pub rtype: RecordType,
+    pub class: u16,
+    pub ttl: u32,
+    pub data: RecordData,
+}
+
+impl Record {
+    pub fn new(
+        name: impl Into<String>,
+        ttl: u32,
+        data: RecordData,
+    ) -> Self {
+        let rtype = match &data {
+            RecordData::A(_) => RecordType::A,
+            RecordData::AAAA(_) => RecordType::AAAA,
+            RecordData::NS(_) => RecordType::NS,
+            RecordData::CNAME(_) => RecordType::CNAME,
+            RecordData::PTR(_) => RecordType::PTR,
+            RecordData::MX { .. } => RecordType::MX,
+            RecordData::TXT(_) => RecordType::TXT,
+            RecordData::SOA { .. } => RecordType::SOA,
+            RecordData::SRV { .. } => RecordType::SRV,
+            RecordData::CAA { .. } => RecordType::CAA,
+            RecordData::Unknown(code, _) => RecordType::Unknown(*code),
+        };
+        Self {
+            name: crate::wire::fqdn(&name.into()),
+            rtype,
+            class: CLASS_IN,
+            ttl,
+            data,
+        }
+    }
+
+    pub(crate) fn decode(reader: &mut Reader) -> Result<Self> {
+        let name = reader.name()?;
+        let rtype = RecordType::from_code(reader.u16()?);
+        let class = reader.u16()?;
+        let ttl = reader.u32()?;
+        let len = reader.u16()? as usize;
+        let data = RecordData::decode(rtype, reader, len)?;
+        Ok(Self {
+            name,
+            rtype,
+            class,
+            ttl,
+            data,
+        })
+    }
+
+    pub(crate) fn encode(
+        &self,
+        writer: &mut Writer,
+    ) -> Result<()> {
+        writer.name(&self.name)?;
+        writer.u16(self.rtype.code());
+        writer.u16(self.class);
+        writer.u32(self.ttl);
+
+        // 先占位 RDLENGTH，写完数据后回填
+        let len_pos = writer.len();
+        writer.u16(0);
+        self.data.encode(writer)?;
+        let len = writer.len() - len_pos - 2;
+        let len = u16::try_from(len).map_err(|_| Error::new(ErrorKind::InvalidInput, "rdata too long"))?;
+        writer.patch_u16(len_pos, len);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn round_trip(record: &Record) -> Record {
+        let mut writer = Writer::new();
+        record.encode(&mut writer).unwrap();
+        let buf = writer.into_inner();
+        let mut reader = Reader::new(&buf);
+        let decoded = Record::decode(&mut reader).unwrap();
+        assert_eq!(reader.pos(), buf.len());
+        decoded
+    }
+
+    #[test]
+    fn records_round_trip() {
+        let records = [
+            Record::new("example.com", 300, RecordData::A(Ipv4Addr::new(192, 0, 2, 1))),
+            Record::new("example.com", 300, RecordData::AAAA("2001:db8::1".parse().unwrap())),
+            Record::new("example.com", 300, RecordData::NS("ns1.example.com.".into())),
+            Record::new("www.example.com", 300, RecordData::CNAME("example.com.".into())),
+            Record::new("1.2.0.192.in-addr.arpa", 300, RecordData::PTR("example.com.".into())),
+            Record::new(
+                "example.com",
+                300,
+                RecordData::MX {
+                    preference: 10,
+                    exchange: "mail.example.com.".into(),
+                },
+            ),
+            Record::new(
+                "example.com",
+                300,
+                RecordData::TXT(vec!["v=spf1 -all".into(), String::new()]),
+            ),
+            Record::new(
+                "example.com",
+                300,
+                RecordData::SOA {
+                    mname: "ns1.example.com.".into(),
+                    rname: "hostmaster.example.com.".into(),
+                    serial: 2024010101,
+                    refresh: 7200,
+                    retry: 3600,
+                    expire: 1209600,
+                    minimum: 300,
+                },
+            ),
+            Record::new(
+                "_sip._tcp.example.com",
+                300,
+                RecordData::SRV {
+                    priority: 1,
+                    weight: 5,
+                    port: 5060,
+                    target: "sip.example.com.".into(),
+                },
+            ),
+            Record::new(
+                "example.com",
+                300,
+                RecordData::CAA {
+                    flags: 0,
+                    tag: "issue".into(),
+                    value: "letsencrypt.org".into(),
+                },
+            ),
+            Record::new("example.com", 300, RecordData::Unknown(65280, vec![1, 2, 3])),
+        ];
+        for record in &records {
+            assert_eq!(&round_trip(record), record);
+        }
+    }
+
+    #[test]
+    fn new_keeps_type_and_class() {
+        let record = Record::new("example.com", 60, RecordData::Unknown(65280, vec![0xAB]));
+        assert_eq!(record.name, "example.com.");
+        assert_eq!(record.rtype, RecordType::Unknown(65280));
+        assert_eq!(record.class, CLASS_IN);
+        assert_eq!(record.data.to_string(), "\\# 1 AB");
+
+        let record = Record::new(
+            "example.com.",
+            60,
+            RecordData::MX {
+                preference: 10,
+                exchange: "mx.example.com.".into(),
+            },
+        );
+        assert_eq!(record.name, "example.com.");
+        assert_eq!(record.rtype, RecordType::MX);
+    }
+
+    #[test]
+    fn rdata_length_mismatch_is_rejected() {
+        // A 记录声明了 5 字节的 RDATA
+        let mut buf = b"\x00\x00\x01\x00\x01\x00\x00\x00\x3C\x00\x05".to_vec();
+        buf.extend_from_slice(&[192, 0, 2, 1, 0]);
+        let err = Record::decode(&mut Reader::new(&buf)).unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::InvalidData);
+    }
+
+    #[test]
+    fn record_type_from_str() {
+        assert_eq!("aaaa".parse::<RecordType>().unwrap(), RecordType::AAAA);
+        assert_eq!("TYPE1".parse::<RecordType>().unwrap(), RecordType::A);
+        assert_eq!("type65280".parse::<RecordType>().unwrap(), RecordType::Unknown(65280));
+        assert!("BOGUS".parse::<RecordType>().is_err());
+        assert_eq!(RecordType::Unknown(65280).to_string(), "TYPE65280");
+    }
+}
@@ -0,0 +1,524 @@
+use std::{
+    fmt, fs,
+    io::{Error, ErrorKind, Read, Result, Write},
+    net::{IpAddr, SocketAddr, TcpStream, ToSocketAddrs, UdpSocket},
+    sync::{
+        Mutex,
+        atomic::{AtomicUsize, Ordering},
+    },
+    thread,
+    time::{Duration, Instant, SystemTime, UNIX_EPOCH},
+};
+
+use crate::{
+    message::{Message, Question, is_truncated},
+    record::{Record, RecordType, class_name},
+};
+
+pub const DNS_PORT: u16 = 53;
+pub const DEFAULT_SERVER: &str = "8.8.8.8:53";
+
+const MAX_UDP_SIZE: usize = 4096;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Transport {
+    /// 优先 UDP，应答被截断时改用 TCP 重试
+    Auto,
+    Udp,
+    Tcp,
+}
+
+impl fmt::Display for Transport {
+    fn fmt(
+        &self,
+        f: &mut fmt::Formatter<'_>,
+    ) -> fmt::Result {
+        match self {
+            Transport::Auto => f.write_str("AUTO"),
+            Transport::Udp => f.write_str("UDP"),
+            Transport::Tcp => f.write_str("TCP"),
+        }
+    }
+}
+
+/// 解析服务器地址，支持 `1.1.1.1`、`1.1.1.1:5353`、`[::1]:53`、`::1` 和主机名
+pub fn parse_server(s: &str) -> Result<SocketAddr> {
+    let s = s.trim();
+    if let Ok(addr) = s.parse::<SocketAddr>() {
+        return Ok(addr);
+    }
+    if let Ok(ip) = s.trim_start_matches('[').trim_end_matches(']').parse::<IpAddr>() {
+        return Ok(SocketAddr::new(ip, DNS_PORT));
+    }
+
+    let resolved = if s.contains(':') {
+        s.to_socket_addrs()
+    } else {
+        (s, DNS_PORT).to_socket_addrs()
+    };
+    resolved?
+        .next()
+        .ok_or_else(|| Error::new(ErrorKind::InvalidInput, format!("can not resolve server: {}", s)))
+}
+
+/// 读取系统配置的首个 DNS 服务器，读取失败时使用 [`DEFAULT_SERVER`]
+pub fn system_server() -> SocketAddr {
+    fs::read_to_string("/etc/resolv.conf")
+        .ok()
+        .and_then(|conf| {
+            conf.lines()
+                .filter_map(|line| line.trim().strip_prefix("nameserver"))
+                .filter_map(|rest| rest.trim().split('%').next()?.parse::<IpAddr>().ok())
+                .map(|ip| SocketAddr::new(ip, DNS_PORT))
+                .next()
+        })
+        .unwrap_or_else(|| DEFAULT_SERVER.parse().expect("valid default server"))
+}
+
+fn next_id() -> u16 {
+    let mut x = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_nanos() as u64;
+    x ^= x << 13;
+    x ^= x >> 7;
+    x ^= x << 17;
+    x as u16
+}
+
+#[derive(Debug, Clone)]
+pub struct Response {
+    pub question: Question,
+    pub message: Message,
+    pub server: SocketAddr,
+    pub transport: Transport,
+    pub elapsed: Duration,
+    pub size: usize,
+}
+
+impl fmt::Display for Response {
+    fn fmt(
+        &self,
+        f: &mut fmt::Formatter<'_>,
+    ) -> fmt::Result {
+        let header = &self.message.header;
+        writeln!(
+            f,
+            "; <<>> sidecar-dns <<>> {} {}",
+            self.question.name, self.question.qtype
+        )?;
+        writeln!(
+            f,
+            ";; ->>HEADER<<- opcode: QUERY, status: {}, id: {}",
+            header.rcode, header.id
+        )?;
+        writeln!(
+            f,
+            ";; flags: {}; QUERY: {}, ANSWER: {}, AUTHORITY: {}, ADDITIONAL: {}",
+            header.flag_names().join(" "),
+            self.message.questions.len(),
+            self.message.answers.len(),
+            self.message.authorities.len(),
+            self.message.additionals.len()
+        )?;
+
+        writeln!(f)?;
+        writeln!(f, ";; QUESTION SECTION:")?;
+        for q in &self.message.questions {
+            writeln!(f, ";{}\t\t{}\t{}", q.name, class_name(q.qclass), q.qtype)?;
+        }
+
+        for (title, records) in [
+            ("ANSWER", &self.message.answers),
+            ("AUTHORITY", &self.message.authorities),
+            ("ADDITIONAL", &self.message.additionals),
+        ] {
+            if records.is_empty() {
+                continue;
+            }
+            writeln!(f)?;
+            writeln!(f, ";; {} SECTION:", title)?;
+            write_records(f, records)?;
+        }
+
+        writeln!(f)?;
+        writeln!(f, ";; Query time: {} msec", self.elapsed.as_millis())?;
+        writeln!(
+            f,
+            ";; SERVER: {}#{}({}) ({})",
+            self.server.ip(),
+            self.server.port(),
+            self.server.ip(),
+            self.transport
+        )?;
+        write!(f, ";; MSG SIZE  rcvd: {}", self.size)
+    }
+}
+
+/// 按列对齐输出记录：名称、TTL、类别、类型、数据
+pub fn write_records(
+    f: &mut impl fmt::Write,
+    records: &[Record],
+) -> fmt::Result {
+    let width = records.iter().map(|r| r.name.len()).max().unwrap_or(0);
+    for r in records {
+        writeln!(
+            f,
+            "{:<width$}  {:>6}  {:<2}  {:<5}  {}",
+            r.name,
+            r.ttl,
+            class_name(r.class),
+            r.rtype,
+            r.data,
+            width = width
+        )?;
+    }
+    Ok(())
+}
+
+#[derive(Debug, Clone)]
+pub struct Resolver {
+    server: SocketAddr,
+    transport: Transport,
+    timeout: Duration,
+    retries: u32,
+    recursion: bool,
+}
+
+impl Resolver {
+    pub fn new(server: SocketAddr) -> Self {
+        Self {
+            server,
+            transport: Transport::Auto,
+            timeout: Duration::from_secs(3),
+            retries: 2,
+            recursion: true,
+        }
+    }
+
+    pub fn system() -> Self {
+        Self::new(system_server())
+    }
+
+    pub fn transport(
+        mut self,
+        transport: Transport,
+    ) -> Self {
+        self.transport = transport;
+        self
+    }
+
+    pub fn timeout(
+        mut self,
+        timeout: Duration,
+    ) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    pub fn retries(
+        mut self,
+        retries: u32,
+    ) -> Self {
+        self.retries = retries;
+        self
+    }
+
+    pub fn recursion(
+        mut self,
+        recursion: bool,
+    ) -> Self {
+        self.recursion = recursion;
+        self
+    }
+
+    pub fn server(&self) -> SocketAddr {
+        self.server
+    }
+
+    pub fn query(
+        &self,
+        name: &str,
+        qtype: RecordType,
+    ) -> Result<Response> {
+        let question = Question::new(name, qtype);
+        let request = Message::query(next_id(), question.clone(), self.recursion);
+        let bytes = request.encode()?;
+
+        let started = Instant::now();
+        let (buf, transport) = match self.transport {
+            Transport::Udp => (self.exchange_udp(&bytes)?, Transport::Udp),
+            Transport::Tcp => (self.exchange_tcp(&bytes)?, Transport::Tcp),
+            Transport::Auto => {
+                let buf = self.exchange_udp(&bytes)?;
+                if is_truncated(&buf) {
+                    (self.exchange_tcp(&bytes)?, Transport::Tcp)
+                } else {
+                    (buf, Transport::Udp)
+                }
+            }
+        };
+        let elapsed = started.elapsed();
+
+        let message = Message::decode(&buf)?;
+        if message.header.id != request.header.id || !message.header.qr {
+            return Err(Error::new(ErrorKind::InvalidData, "response does not match query"));
+        }
+
+        Ok(Response {
+            question,
+            message,
+            server: self.server,
+            transport,
+            elapsed,
+            size: buf.len(),
+        })
+    }
+
+    /// 并发执行多个查询，结果顺序与输入一致
+    pub fn query_all(
+        &self,
+        queries: &[(String, RecordType)],
+        concurrency: usize,
+    ) -> Vec<Result<Response>> {
+        let next = AtomicUsize::new(0);
+        let results: Mutex<Vec<Option<Result<Response>>>> = Mutex::new((0..queries.len()).map(|_| None).collect());
+        let workers = concurrency.clamp(1, queries.len().max(1));
+
+        thread::scope(|s| {
+            for _ in 0..workers {
+                s.spawn(|| {
+                    loop {
+                        let i = next.fetch_add(1, Ordering::Relaxed);
+                        let Some((name, qtype)) = queries.get(i) else {
+                            break;
+                        };
+                        let result = self.query(name, *qtype);
+                        results.lock().expect("results lock poisoned")[i] = Some(result);
+                    }
+                });
+            }
+        });
+
+        results
+            .into_inner()
+            .expect("results lock poisoned")
+            .into_iter()
+            .map(|r| r.unwrap_or_else(|| Err(Error::other("query not executed"))))
+            .collect()
+    }
+
+    fn exchange_udp(
+        &self,
+        request: &[u8],
+    ) -> Result<Vec<u8>> {
+        let bind: SocketAddr = if self.server.is_ipv4() {
+            "0.0.0.0:0".parse().expect("valid bind address")
+        } else {
+            "[::]:0".parse().expect("valid bind address")
+        };
+        let socket = UdpSocket::bind(bind)?;
+        socket.connect(self.server)?;
+        socket.set_read_timeout(Some(self.timeout))?;
+
+        let id = &request[..2];
+        let mut buf = vec![0u8; MAX_UDP_SIZE];
+        for _ in 0..=self.retries {
+            socket.send(request)?;
+            let deadline = Instant::now() + self.timeout;
+            loop {
+                match socket.recv(&mut buf) {
+                    // 丢弃 ID 不匹配的报文，继续等待
+                    Ok(n) if n >= 2 && &buf[..2] == id => {
+                        buf.truncate(n);
+                        return Ok(buf);
+                    }
+                    Ok(_) => {}
+                    Err(e) if matches!(e.kind(), ErrorKind::WouldBlock | ErrorKind::TimedOut) => break,
+                    Err(e) => return Err(e),
+                }
+                let remaining = deadline.saturating_duration_since(Instant::now());
+                if remaining.is_zero() {
+                    break;
+                }
+                socket.set_read_timeout(Some(remaining))?;
+            }
+            socket.set_read_timeout(Some(self.timeout))?;
+        }
+        Err(Error::new(
+            ErrorKind::TimedOut,
+            format!("no response from {} over UDP", self.server),
+        ))
+    }
+
+    fn exchange_tcp(
+        &self,
+        request: &[u8],
+    ) -> Result<Vec<u8>> {
+        let mut stream = TcpStream::connect_timeout(&self.server, self.timeout)?;
+        stream.set_read_timeout(Some(self.timeout))?;
+        stream.set_write_timeout(Some(self.timeout))?;
+
+        let len = u16::try_from(request.len()).map_err(|_| Error::new(ErrorKind::InvalidInput, "query too long"))?;
+        let mut framed = Vec::with_capacity(request.len() + 2);
+        framed.extend_from_slice(&len.to_be_bytes());
+        framed.extend_from_slice(request);
+        stream.write_all(&framed)?;
+
+        let mut len = [0u8; 2];
+        stream.read_exact(&mut len)?;
+        let mut buf = vec![0u8; u16::from_be_bytes(len) as usize];
+        stream.read_exact(&mut buf)?;
+        Ok(buf)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::net::Ipv4Addr;
+
+    use super::*;
+    use crate::{message::Rcode, record::RecordData, testing::serve};
+
+    fn answer(
+        request: &Message,
+        ip: Ipv4Addr,
+    ) -> Message {
+        let mut response = Message::response(request);
+        response.header.ra = true;
+        let name = &request.questions[0].name;
+        response.answers.push(Record::new(name.as_str(), 60, RecordData::A(ip)));
+        response
+    }
+
+    #[test]
+    fn query_over_udp() {
+        let server = serve(|request, _| answer(request, Ipv4Addr::new(192, 0, 2, 1)));
+        let response = Resolver::new(server).query("example.com", RecordType::A).unwrap();
+
+        assert_eq!(response.transport, Transport::Udp);
+        assert_eq!(response.server, server);
+        assert_eq!(response.question.name, "example.com.");
+        assert_eq!(response.message.header.rcode, Rcode::NoError);
+        assert_eq!(
+            response.message.answers[0].data,
+            RecordData::A(Ipv4Addr::new(192, 0, 2, 1))
+        );
+    }
+
+    #[test]
+    fn query_over_tcp() {
+        let server = serve(|request, transport| match transport {
+            Transport::Tcp => answer(request, Ipv4Addr::new(192, 0, 2, 2)),
+            _ => {
+                let mut response = Message::response(request);
+                response.header.rcode = Rcode::Refused;
+                response
+            }
+        });
+        let response = Resolver::new(server)
+            .transport(Transport::Tcp)
+            .query("example.com", RecordType::A)
+            .unwrap();
+        assert_eq!(response.transport, Transport::Tcp);
+        assert_eq!(response.message.header.rcode, Rcode::NoError);
+        assert_eq!(response.message.answers.len(), 1);
+    }
+
+    /// UDP 应答只带 TC 标志，完整应答需要通过 TCP 获取
+    fn truncating_server() -> SocketAddr {
+        serve(|request, transport| match transport {
+            Transport::Tcp => answer(request, Ipv4Addr::new(192, 0, 2, 3)),
+            _ => {
+                let mut response = Message::response(request);
+                response.header.tc = true;
+                response
+            }
+        })
+    }
+
+    #[test]
+    fn truncated_udp_response_retries_over_tcp() {
+        let response = Resolver::new(truncating_server())
+            .query("example.com", RecordType::A)
+            .unwrap();
+        assert_eq!(response.transport, Transport::Tcp);
+        assert!(!response.message.header.tc);
+        assert_eq!(
+            response.message.answers[0].data,
+            RecordData::A(Ipv4Addr::new(192, 0, 2, 3))
+        );
+    }
+
+    #[test]
+    fn udp_only_keeps_truncated_response() {
+        let response = Resolver::new(truncating_server())
+            .transport(Transport::Udp)
+            .query("example.com", RecordType::A)
+            .unwrap();
+        assert_eq!(response.transport, Transport::Udp);
+        assert!(response.message.header.tc);
+        assert!(response.message.answers.is_empty());
+    }
+
+    #[test]
+    fn recursion_flag_is_sent() {
+        let server = serve(|request, _| {
+            let mut response = Message::response(request);
+            if !request.header.rd {
+                response.header.rcode = Rcode::Refused;
+            }
+            response
+        });
+        let resolver = Resolver::new(server);
+        let response = resolver.clone().query("example.com", RecordType::A).unwrap();
+        assert_eq!(response.message.header.rcode, Rcode::NoError);
+        let response = resolver.recursion(false).query("example.com", RecordType::A).unwrap();
+        assert_eq!(response.message.header.rcode, Rcode::Refused);
+    }
+
+    #[test]
+    fn mismatched_id_times_out() {
+        let server = serve(|request, _| {
+            let mut response = Message::response(request);
+            response.header.id = request.header.id.wrapping_add(1);
+            response
+        });
+        let err = Resolver::new(server)
+            .transport(Transport::Udp)
+            .timeout(Duration::from_millis(100))
+            .retries(0)
+            .query("example.com", RecordType::A)
+            .unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::TimedOut);
+    }
+
+    #[test]
+    fn query_all_keeps_order() {
+        let server = serve(|request, _| {
+            let last = request.questions[0].name.as_bytes()[0] - b'0';
+            answer(request, Ipv4Addr::new(192, 0, 2, last))
+        });
+        let queries: Vec<(String, RecordType)> =
+            (0..8).map(|i| (format!("{}.example.com", i), RecordType::A)).collect();
+        let results = Resolver::new(server).query_all(&queries, 3);
+        for (i, result) in results.into_iter().enumerate() {
+            let response = result.unwrap();
+            assert_eq!(response.question.name, format!("{}.example.com.", i));
+            assert_eq!(
+                response.message.answers[0].data,
+                RecordData::A(Ipv4Addr::new(192, 0, 2, i as u8))
+            );
+        }
+    }
+
+    #[test]
+    fn parse_server_forms() {
+        assert_eq!(parse_server("1.1.1.1").unwrap(), "1.1.1.1:53".parse().unwrap());
+        assert_eq!(parse_server("1.1.1.1:5353").unwrap(), "1.1.1.1:5353".parse().unwrap());
+        assert_eq!(parse_server("::1").unwrap(), "[::1]:53".parse().unwrap());
+        assert_eq!(parse_server("[::1]").unwrap(), "[::1]:53".parse().unwrap());
+        assert_eq!(parse_server("[::1]:5353").unwrap(), "[::1]:5353".parse().unwrap());
+        assert_eq!(parse_server("localhost:5353").unwrap().port(), 5353);
+    }
+}
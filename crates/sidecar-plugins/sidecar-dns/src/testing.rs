@@ -0,0 +1,81 @@
+//! 测试使用的本地 DNS 服务器
+
+use std::{
+    io::{Read, Result, Write},
+    net::{IpAddr, SocketAddr, TcpListener, UdpSocket},
+    sync::Arc,
+    thread,
+};
+
+use crate::{message::Message, resolver::Transport};
+
+pub(crate) type Handler = dyn Fn(&Message, Transport) -> Message + Send + Sync;
+
+/// 在 127.0.0.1 的随机端口上启动服务器，UDP 和 TCP 共用同一端口
+pub(crate) fn serve(handler: impl Fn(&Message, Transport) -> Message + Send + Sync + 'static) -> SocketAddr {
+    let handler: Arc<Handler> = Arc::new(handler);
+    loop {
+        if let Ok(addr) = serve_at("127.0.0.1:0".parse().expect("valid address"), handler.clone()) {
+            return addr;
+        }
+    }
+}
+
+/// 在多个回环地址的同一端口上启动服务器，返回共用的端口
+///
+/// 迭代解析时委派服务器都使用同一端口，只能通过地址区分。
+pub(crate) fn serve_group(servers: &[(IpAddr, Arc<Handler>)]) -> u16 {
+    let (first, rest) = servers.split_first().expect("at least one server");
+    'retry: loop {
+        let Ok(addr) = serve_at(SocketAddr::new(first.0, 0), first.1.clone()) else {
+            continue;
+        };
+        for (ip, handler) in rest {
+            if serve_at(SocketAddr::new(*ip, addr.port()), handler.clone()).is_err() {
+                continue 'retry;
+            }
+        }
+        return addr.port();
+    }
+}
+
+/// 在指定地址启动服务器，端口为 0 时由系统分配
+pub(crate) fn serve_at(
+    addr: SocketAddr,
+    handler: Arc<Handler>,
+) -> Result<SocketAddr> {
+    let udp = UdpSocket::bind(addr)?;
+    let addr = udp.local_addr()?;
+    let tcp = TcpListener::bind(addr)?;
+
+    let udp_handler = handler.clone();
+    thread::spawn(move || {
+        let mut buf = [0u8; 4096];
+        while let Ok((n, peer)) = udp.recv_from(&mut buf) {
+            let Ok(request) = Message::decode(&buf[..n]) else {
+                continue;
+            };
+            let reply = udp_handler(&request, Transport::Udp).encode().expect("encode reply");
+            let _ = udp.send_to(&reply, peer);
+        }
+    });
+    thread::spawn(move || {
+        for mut stream in tcp.incoming().flatten() {
+            let mut len = [0u8; 2];
+            if stream.read_exact(&mut len).is_err() {
+                continue;
+            }
+            let mut buf = vec![0u8; u16::from_be_bytes(len) as usize];
+            if stream.read_exact(&mut buf).is_err() {
+                continue;
+            }
+            let Ok(request) = Message::decode(&buf) else {
+                continue;
+            };
+            let reply = handler(&request, Transport::Tcp).encode().expect("encode reply");
+            let _ = stream.write_all(&(reply.len() as u16).to_be_bytes());
+            let _ = stream.write_all(&reply);
+        }
+    });
+    Ok(addr)
+}
@@ -0,0 +1,592 @@
+use std::{
+    fmt,
+    io::{Error, ErrorKind, Result},
+    net::{IpAddr, SocketAddr},
+    time::Duration,
+};
+
+use crate::{
+    message::{Question, Rcode},
+    record::{Record, RecordData, RecordType},
+    resolver::{DNS_PORT, Resolver, Response, Transport, write_records},
+    wire::fqdn,
+};
+
+const MAX_HOPS: usize = 16;
+const MAX_DEPTH: usize = 3;
+
+/// 根服务器列表，来源 https://www.internic.net/domain/named.root
+const ROOT_SERVERS: [(&str, &str); 13] = [
+    ("a.root-servers.net.", "198.41.0.4"),
+    ("b.root-servers.net.", "170.247.170.2"),
+    ("c.root-servers.net.", "192.33.4.12"),
+    ("d.root-servers.net.", "199.7.91.13"),
+    ("e.root-servers.net.", "192.203.230.10"),
+    ("f.root-servers.net.", "192.5.5.241"),
+    ("g.root-servers.net.", "192.112.36.4"),
+    ("h.root-servers.net.", "198.97.190.53"),
+    ("i.root-servers.net.", "192.36.148.17"),
+    ("j.root-servers.net.", "192.58.128.30"),
+    ("k.root-servers.net.", "193.0.14.129"),
+    ("l.root-servers.net.", "199.7.83.42"),
+    ("m.root-servers.net.", "202.12.27.33"),
+];
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RootHint {
+    pub name: String,
+    pub addr: SocketAddr,
+}
+
+impl RootHint {
+    pub fn new(
+        name: &str,
+        addr: SocketAddr,
+    ) -> Self {
+        Self { name: fqdn(name), addr }
+    }
+}
+
+pub fn default_root_hints() -> Vec<RootHint> {
+    ROOT_SERVERS
+        .iter()
+        .map(|(name, ip)| RootHint::new(name, SocketAddr::new(ip.parse().expect("valid root ip"), DNS_PORT)))
+        .collect()
+}
+
+#[derive(Debug, Clone)]
+pub struct Referral {
+    /// 被委派的子区域
+    pub zone: String,
+    pub nameservers: Vec<Record>,
+    /// 应答附加段中与 NS 对应的地址记录
+    pub glue: Vec<Record>,
+    /// 没有 glue 时另行迭代解析得到的 NS 地址
+    pub resolved: Vec<(String, IpAddr)>,
+}
+
+#[derive(Debug, Clone)]
+pub struct Hop {
+    /// 本跳所询问服务器负责的区域
+    pub zone: String,
+    pub server_name: String,
+    pub response: Response,
+    pub referral: Option<Referral>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Outcome {
+    Answer,
+    NoData,
+    Rcode(Rcode),
+    Failed(String),
+}
+
+impl fmt::Display for Outcome {
+    fn fmt(
+        &self,
+        f: &mut fmt::Formatter<'_>,
+    ) -> fmt::Result {
+        match self {
+            Outcome::Answer => f.write_str("answer received"),
+            Outcome::NoData => f.write_str("no data (name exists, no records of this type)"),
+            Outcome::Rcode(rcode) => write!(f, "server returned {}", rcode),
+            Outcome::Failed(reason) => write!(f, "failed: {}", reason),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Trace {
+    pub question: Question,
+    pub hops: Vec<Hop>,
+    pub outcome: Outcome,
+}
+
+impl fmt::Display for Trace {
+    fn fmt(
+        &self,
+        f: &mut fmt::Formatter<'_>,
+    ) -> fmt::Result {
+        writeln!(
+            f,
+            "; <<>> sidecar-dns <<>> +trace {} {}",
+            self.question.name, self.question.qtype
+        )?;
+        for hop in &self.hops {
+            writeln!(f)?;
+            f.write_str(&hop.records_text())?;
+            writeln!(
+                f,
+                ";; Received {} bytes from {}({}) in {} ms",
+                hop.response.size,
+                hop.response.server,
+                hop.server_name,
+                hop.response.elapsed.as_millis()
+            )?;
+        }
+        writeln!(f)?;
+        write!(f, ";; Trace stopped: {}", self.outcome)
+    }
+}
+
+impl Hop {
+    /// 本跳的关键记录：应答段，或委派的 NS 及 glue，或权威段
+    pub fn records_text(&self) -> String {
+        let mut out = String::new();
+        let message = &self.response.message;
+        if let Some(referral) = &self.referral {
+            let _ = write_records(&mut out, &referral.nameservers);
+            if !referral.glue.is_empty() {
+                out.push_str(";; glue:\n");
+                let _ = write_records(&mut out, &referral.glue);
+            }
+            for (name, ip) in &referral.resolved {
+                out.push_str(&format!(";; resolved without glue: {} {}\n", name, ip));
+            }
+        } else if !message.answers.is_empty() {
+            let _ = write_records(&mut out, &message.answers);
+        } else {
+            let _ = write_records(&mut out, &message.authorities);
+        }
+        out
+    }
+}
+
+fn is_subdomain(
+    name: &str,
+    zone: &str,
+) -> bool {
+    let name = name.to_ascii_lowercase();
+    let zone = zone.to_ascii_lowercase();
+    zone == "." || name == zone || name.ends_with(&format!(".{}", zone))
+}
+
+fn label_count(name: &str) -> usize {
+    name.trim_end_matches('.').split('.').filter(|l| !l.is_empty()).count()
+}
+
+#[derive(Debug, Clone)]
+pub struct Tracer {
+    root_hints: Vec<RootHint>,
+    port: u16,
+    transport: Transport,
+    timeout: Duration,
+}
+
+impl Default for Tracer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Tracer {
+    pub fn new() -> Self {
+        Self {
+            root_hints: default_root_hints(),
+            port: DNS_PORT,
+            transport: Transport::Auto,
+            timeout: Duration::from_secs(2),
+        }
+    }
+
+    pub fn root_hints(
+        mut self,
+        root_hints: Vec<RootHint>,
+    ) -> Self {
+        self.root_hints = root_hints;
+        self
+    }
+
+    /// 向委派服务器发起查询时使用的端口
+    pub fn port(
+        mut self,
+        port: u16,
+    ) -> Self {
+        self.port = port;
+        self
+    }
+
+    pub fn transport(
+        mut self,
+        transport: Transport,
+    ) -> Self {
+        self.transport = transport;
+        self
+    }
+
+    pub fn timeout(
+        mut self,
+        timeout: Duration,
+    ) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    pub fn trace(
+        &self,
+        name: &str,
+        qtype: RecordType,
+    ) -> Result<Trace> {
+        if self.root_hints.is_empty() {
+            return Err(Error::new(ErrorKind::InvalidInput, "no root hints configured"));
+        }
+        Ok(self.walk(name, qtype, 0))
+    }
+
+    fn walk(
+        &self,
+        name: &str,
+        qtype: RecordType,
+        depth: usize,
+    ) -> Trace {
+        let question = Question::new(name, qtype);
+        let mut hops = Vec::new();
+        let mut zone = ".".to_string();
+        let mut servers: Vec<(String, SocketAddr)> = self.root_hints.iter().map(|h| (h.name.clone(), h.addr)).collect();
+
+        let outcome = loop {
+            if hops.len() >= MAX_HOPS {
+                break Outcome::Failed(format!("too many referrals (>{})", MAX_HOPS));
+            }
+
+            let Some((server_name, response)) = self.ask(&servers, &question) else {
+                break Outcome::Failed(format!("no server for zone {} responded", zone));
+            };
+
+            let message = &response.message;
+            let mut hop = Hop {
+                zone: zone.clone(),
+                server_name,
+                response: response.clone(),
+                referral: None,
+            };
+
+            if message.header.rcode != Rcode::NoError {
+                hops.push(hop);
+                break Outcome::Rcode(message.header.rcode);
+            }
+            if !message.answers.is_empty() {
+                hops.push(hop);
+                break Outcome::Answer;
+            }
+
+            let nameservers: Vec<Record> = message
+                .authorities
+                .iter()
+                .filter(|r| r.rtype == RecordType::NS && is_subdomain(&question.name, &r.name))
+                .cloned()
+                .collect();
+            let Some(child) = nameservers.first().map(|r| r.name.clone()) else {
+                hops.push(hop);
+                break Outcome::NoData;
+            };
+            if label_count(&child) <= label_count(&zone) {
+                hops.push(hop);
+                break Outcome::Failed(format!("lame referral from {} to {}", zone, child));
+            }
+
+            let ns_names: Vec<String> = nameservers
+                .iter()
+                .filter_map(|r| match &r.data {
+                    RecordData::NS(ns) => Some(ns.to_ascii_lowercase()),
+                    _ => None,
+                })
+                .collect();
+            let glue: Vec<Record> = message
+                .additionals
+                .iter()
+                .filter(|r| {
+                    matches!(r.rtype, RecordType::A | RecordType::AAAA)
+                        && ns_names.contains(&r.name.to_ascii_lowercase())
+                })
+                .cloned()
+                .collect();
+
+            let mut next = Vec::new();
+            // 优先使用 IPv4 地址，IPv6 不通的网络环境更常见
+            for v4 in [true, false] {
+                for r in &glue {
+                    match r.data {
+                        RecordData::A(ip) if v4 => next.push((r.name.clone(), SocketAddr::new(ip.into(), self.port))),
+                        RecordData::AAAA(ip) if !v4 => {
+                            next.push((r.name.clone(), SocketAddr::new(ip.into(), self.port)))
+                        }
+                        _ => {}
+                    }
+                }
+            }
+
+            let mut resolved = Vec::new();
+            if next.is_empty() && depth < MAX_DEPTH {
+                for ns in &ns_names {
+                    if let Some(ip) = self.resolve_address(ns, depth + 1) {
+                        resolved.push((ns.clone(), ip));
+                        next.push((ns.clone(), SocketAddr::new(ip, self.port)));
+                        break;
+                    }
+                }
+            }
+
+            hop.referral = Some(Referral {
+                zone: child.clone(),
+                nameservers,
+                glue,
+                resolved,
+            });
+            hops.push(hop);
+
+            if next.is_empty() {
+                break Outcome::Failed(format!("no address found for nameservers of {}", child));
+            }
+            zone = child;
+            servers = next;
+        };
+
+        Trace {
+            question,
+            hops,
+            outcome,
+        }
+    }
+
+    fn ask(
+        &self,
+        servers: &[(String, SocketAddr)],
+        question: &Question,
+    ) -> Option<(String, Response)> {
+        servers.iter().find_map(|(name, addr)| {
+            Resolver::new(*addr)
+                .transport(self.transport)
+                .timeout(self.timeout)
+                .retries(0)
+                .recursion(false)
+                .query(&question.name, question.qtype)
+                .ok()
+                .map(|response| (name.clone(), response))
+        })
+    }
+
+    fn resolve_address(
+        &self,
+        name: &str,
+        depth: usize,
+    ) -> Option<IpAddr> {
+        let trace = self.walk(name, RecordType::A, depth);
+        if trace.outcome != Outcome::Answer {
+            return None;
+        }
+        trace
+            .hops
+            .last()?
+            .response
+            .message
+            .answers
+            .iter()
+            .find_map(|r| match r.data {
+                RecordData::A(ip) => Some(IpAddr::V4(ip)),
+                _ => None,
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{net::Ipv4Addr, sync::Arc};
+
+    use super::*;
+    use crate::{
+        message::Message,
+        testing::{Handler, serve_group},
+    };
+
+    const ROOT: Ipv4Addr = Ipv4Addr::new(127, 0, 0, 1);
+    const COM: Ipv4Addr = Ipv4Addr::new(127, 0, 0, 2);
+    const EXAMPLE: Ipv4Addr = Ipv4Addr::new(127, 0, 0, 3);
+    const NET: Ipv4Addr = Ipv4Addr::new(127, 0, 0, 4);
+    const WWW: Ipv4Addr = Ipv4Addr::new(192, 0, 2, 80);
+
+    fn referral(
+        request: &Message,
+        zone: &str,
+        ns: &str,
+        glue: Option<Ipv4Addr>,
+    ) -> Message {
+        let mut response = Message::response(request);
+        response.header.rd = false;
+        response
+            .authorities
+            .push(Record::new(zone, 3600, RecordData::NS(fqdn(ns))));
+        if let Some(ip) = glue {
+            response.additionals.push(Record::new(ns, 3600, RecordData::A(ip)));
+        }
+        response
+    }
+
+    fn answer(
+        request: &Message,
+        ip: Ipv4Addr,
+    ) -> Message {
+        let mut response = Message::response(request);
+        response.header.aa = true;
+        let name = request.questions[0].name.as_str();
+        response.answers.push(Record::new(name, 300, RecordData::A(ip)));
+        response
+    }
+
+    fn nxdomain(request: &Message) -> Message {
+        let mut response = Message::response(request);
+        response.header.rcode = Rcode::NXDomain;
+        response
+    }
+
+    fn handler(f: impl Fn(&Message) -> Message + Send + Sync + 'static) -> Arc<Handler> {
+        Arc::new(move |request: &Message, _: Transport| f(request))
+    }
+
+    /// 根服务器把 com. 委派给 COM，把 net. 委派给 NET，都带 glue
+    fn root() -> (IpAddr, Arc<Handler>) {
+        let handler = handler(|request| {
+            let name = request.questions[0].name.as_str();
+            if name.ends_with(".com.") {
+                referral(request, "com.", "a.gtld.test.", Some(COM))
+            } else if name.ends_with(".net.") {
+                referral(request, "net.", "b.gtld.test.", Some(NET))
+            } else {
+                nxdomain(request)
+            }
+        });
+        (ROOT.into(), handler)
+    }
+
+    fn example() -> (IpAddr, Arc<Handler>) {
+        (EXAMPLE.into(), handler(|request| answer(request, WWW)))
+    }
+
+    fn tracer(port: u16) -> Tracer {
+        Tracer::new()
+            .root_hints(vec![RootHint::new("root.test", SocketAddr::new(ROOT.into(), port))])
+            .port(port)
+            .timeout(Duration::from_millis(500))
+    }
+
+    fn names(trace: &Trace) -> Vec<(&str, &str)> {
+        trace
+            .hops
+            .iter()
+            .map(|hop| (hop.zone.as_str(), hop.server_name.as_str()))
+            .collect()
+    }
+
+    #[test]
+    fn follows_referrals_with_glue() {
+        let com = handler(|request| referral(request, "example.com.", "ns1.example.com.", Some(EXAMPLE)));
+        let port = serve_group(&[root(), (COM.into(), com), example()]);
+
+        let trace = tracer(port).trace("www.example.com", RecordType::A).unwrap();
+        assert_eq!(trace.outcome, Outcome::Answer);
+        assert_eq!(
+            names(&trace),
+            [
+                (".", "root.test."),
+                ("com.", "a.gtld.test."),
+                ("example.com.", "ns1.example.com.")
+            ]
+        );
+
+        let referral = trace.hops[1].referral.as_ref().unwrap();
+        assert_eq!(referral.zone, "example.com.");
+        assert_eq!(referral.glue.len(), 1);
+        assert!(referral.resolved.is_empty());
+        assert_eq!(trace.hops[1].response.server, SocketAddr::new(COM.into(), port));
+
+        let last = trace.hops.last().unwrap();
+        assert!(last.referral.is_none());
+        assert_eq!(last.response.message.answers[0].data, RecordData::A(WWW));
+        assert!(!last.response.message.header.rd);
+    }
+
+    #[test]
+    fn resolves_nameserver_without_glue() {
+        // example.com. 的 NS 位于 net. 下，com. 的委派不带 glue
+        let com = handler(|request| referral(request, "example.com.", "ns.other.net.", None));
+        let net = handler(|request| match request.questions[0].name.as_str() {
+            "ns.other.net." => answer(request, EXAMPLE),
+            _ => nxdomain(request),
+        });
+        let port = serve_group(&[root(), (COM.into(), com), (NET.into(), net), example()]);
+
+        let trace = tracer(port).trace("www.example.com", RecordType::A).unwrap();
+        assert_eq!(trace.outcome, Outcome::Answer);
+        assert_eq!(
+            names(&trace),
+            [
+                (".", "root.test."),
+                ("com.", "a.gtld.test."),
+                ("example.com.", "ns.other.net.")
+            ]
+        );
+
+        let referral = trace.hops[1].referral.as_ref().unwrap();
+        assert!(referral.glue.is_empty());
+        assert_eq!(
+            referral.resolved,
+            [("ns.other.net.".to_string(), IpAddr::from(EXAMPLE))]
+        );
+        assert!(
+            trace.hops[1]
+                .records_text()
+                .contains(";; resolved without glue: ns.other.net. 127.0.0.3")
+        );
+        assert_eq!(
+            trace.hops.last().unwrap().response.message.answers[0].data,
+            RecordData::A(WWW)
+        );
+    }
+
+    #[test]
+    fn stops_when_nameserver_has_no_address() {
+        let com = handler(|request| referral(request, "example.com.", "ns.missing.org.", None));
+        let port = serve_group(&[root(), (COM.into(), com)]);
+
+        let trace = tracer(port).trace("www.example.com", RecordType::A).unwrap();
+        assert_eq!(
+            trace.outcome,
+            Outcome::Failed("no address found for nameservers of example.com.".to_string())
+        );
+        assert_eq!(trace.hops.len(), 2);
+    }
+
+    #[test]
+    fn stops_on_error_rcode() {
+        let com = handler(nxdomain);
+        let port = serve_group(&[root(), (COM.into(), com)]);
+
+        let trace = tracer(port).trace("www.example.com", RecordType::A).unwrap();
+        assert_eq!(trace.outcome, Outcome::Rcode(Rcode::NXDomain));
+        assert_eq!(trace.hops.len(), 2);
+    }
+
+    #[test]
+    fn rejects_lame_referral() {
+        // com. 的服务器又把查询委派回 com.
+        let com = handler(|request| referral(request, "com.", "a.gtld.test.", Some(COM)));
+        let port = serve_group(&[root(), (COM.into(), com)]);
+
+        let trace = tracer(port).trace("www.example.com", RecordType::A).unwrap();
+        assert_eq!(
+            trace.outcome,
+            Outcome::Failed("lame referral from com. to com.".to_string())
+        );
+    }
+
+    #[test]
+    fn requires_root_hints() {
+        assert!(
+            Tracer::new()
+                .root_hints(vec![])
+                .trace("example.com", RecordType::A)
+                .is_err()
+        );
+    }
+}
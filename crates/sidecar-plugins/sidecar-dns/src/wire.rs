@@ -0,0 +1,334 @@
+use std::{
+    io::{Error, ErrorKind, Result},
+    net::IpAddr,
+};
+
+const MAX_LABEL_LEN: usize = 63;
+const MAX_NAME_LEN: usize = 255;
+const MAX_POINTERS: usize = 64;
+
+/// 将域名规范为以 `.` 结尾的完整形式
+pub fn fqdn(name: &str) -> String {
+    let name = name.trim();
+    if name.ends_with('.') {
+        name.to_string()
+    } else {
+        format!("{}.", name)
+    }
+}
+
+/// 生成反向解析使用的 `in-addr.arpa` / `ip6.arpa` 域名
+pub fn reverse_name(ip: IpAddr) -> String {
+    match ip {
+        IpAddr::V4(v4) => {
+            let o = v4.octets();
+            format!("{}.{}.{}.{}.in-addr.arpa.", o[3], o[2], o[1], o[0])
+        }
+        IpAddr::V6(v6) => {
+            let mut out = String::with_capacity(72);
+            for b in v6.octets().iter().rev() {
+                out.push_str(&format!("{:x}.{:x}.", b & 0x0F, b >> 4));
+            }
+            out.push_str("ip6.arpa.");
+            out
+        }
+    }
+}
+
+pub(crate) struct Reader<'a> {
+    buf: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> Reader<'a> {
+    pub fn new(buf: &'a [u8]) -> Self {
+        Self { buf, pos: 0 }
+    }
+
+    pub fn pos(&self) -> usize {
+        self.pos
+    }
+
+    pub fn bytes(
+        &mut self,
+        n: usize,
+    ) -> Result<&'a [u8]> {
+        let end = self
+            .pos
+            .checked_add(n)
+            .filter(|end| *end <= self.buf.len())
+            .ok_or_else(|| Error::new(ErrorKind::UnexpectedEof, "message truncated"))?;
+        let bytes = &self.buf[self.pos..end];
+        self.pos = end;
+        Ok(bytes)
+    }
+
+    pub fn u8(&mut self) -> Result<u8> {
+        Ok(self.bytes(1)?[0])
+    }
+
+    pub fn u16(&mut self) -> Result<u16> {
+        let b = self.bytes(2)?;
+        Ok(u16::from_be_bytes([b[0], b[1]]))
+    }
+
+    pub fn u32(&mut self) -> Result<u32> {
+        let b = self.bytes(4)?;
+        Ok(u32::from_be_bytes([b[0], b[1], b[2], b[3]]))
+    }
+
+    /// 读取域名，支持压缩指针
+    pub fn name(&mut self) -> Result<String> {
+        let mut labels: Vec<String> = Vec::new();
+        let mut pos = self.pos;
+        let mut jumped = false;
+        let mut pointers = 0;
+        let mut total = 0;
+
+        loop {
+            let len = *self
+                .buf
+                .get(pos)
+                .ok_or_else(|| Error::new(ErrorKind::UnexpectedEof, "message truncated"))?
+                as usize;
+
+            match len & 0xC0 {
+                0x00 => {
+                    if len == 0 {
+                        pos += 1;
+                        break;
+                    }
+                    let label = self
+                        .buf
+                        .get(pos + 1..pos + 1 + len)
+                        .ok_or_else(|| Error::new(ErrorKind::UnexpectedEof, "message truncated"))?;
+                    total += len + 1;
+                    if total > MAX_NAME_LEN {
+                        return Err(Error::new(ErrorKind::InvalidData, "domain name too long"));
+                    }
+                    labels.push(escape_label(label));
+                    pos += len + 1;
+                }
+                0xC0 => {
+                    let low = *self
+                        .buf
+                        .get(pos + 1)
+                        .ok_or_else(|| Error::new(ErrorKind::UnexpectedEof, "message truncated"))?
+                        as usize;
+                    pointers += 1;
+                    if pointers > MAX_POINTERS {
+                        return Err(Error::new(ErrorKind::InvalidData, "compression pointer loop"));
+                    }
+                    if !jumped {
+                        self.pos = pos + 2;
+                        jumped = true;
+                    }
+                    pos = ((len & 0x3F) << 8) | low;
+                }
+                _ => {
+                    return Err(Error::new(ErrorKind::InvalidData, "unsupported label type"));
+                }
+            }
+        }
+
+        if !jumped {
+            self.pos = pos;
+        }
+        if labels.is_empty() {
+            return Ok(".".to_string());
+        }
+        Ok(format!("{}.", labels.join(".")))
+    }
+}
+
+fn escape_label(label: &[u8]) -> String {
+    let mut out = String::with_capacity(label.len());
+    for &b in label {
+        match b {
+            b'.' | b'\\' => {
+                out.push('\\');
+                out.push(b as char);
+            }
+            0x21..=0x7E => out.push(b as char),
+            _ => out.push_str(&format!("\\{:03}", b)),
+        }
+    }
+    out
+}
+
+#[derive(Default)]
+pub(crate) struct Writer {
+    buf: Vec<u8>,
+}
+
+impl Writer {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn len(&self) -> usize {
+        self.buf.len()
+    }
+
+    pub fn into_inner(self) -> Vec<u8> {
+        self.buf
+    }
+
+    pub fn u8(
+        &mut self,
+        v: u8,
+    ) {
+        self.buf.push(v);
+    }
+
+    pub fn u16(
+        &mut self,
+        v: u16,
+    ) {
+        self.buf.extend_from_slice(&v.to_be_bytes());
+    }
+
+    pub fn u32(
+        &mut self,
+        v: u32,
+    ) {
+        self.buf.extend_from_slice(&v.to_be_bytes());
+    }
+
+    pub fn bytes(
+        &mut self,
+        v: &[u8],
+    ) {
+        self.buf.extend_from_slice(v);
+    }
+
+    pub fn patch_u16(
+        &mut self,
+        pos: usize,
+        v: u16,
+    ) {
+        self.buf[pos..pos + 2].copy_from_slice(&v.to_be_bytes());
+    }
+
+    pub fn character_string(
+        &mut self,
+        v: &[u8],
+    ) -> Result<()> {
+        let len = u8::try_from(v.len()).map_err(|_| Error::new(ErrorKind::InvalidInput, "string too long"))?;
+        self.u8(len);
+        self.bytes(v);
+        Ok(())
+    }
+
+    /// 写入域名，不做压缩
+    pub fn name(
+        &mut self,
+        name: &str,
+    ) -> Result<()> {
+        let name = name.trim().trim_end_matches('.');
+        let mut total = 1;
+        if !name.is_empty() {
+            for label in name.split('.') {
+                if label.is_empty() {
+                    return Err(Error::new(
+                        ErrorKind::InvalidInput,
+                        format!("empty label in domain name: {}", name),
+                    ));
+                }
+                if !label.is_ascii() {
+                    return Err(Error::new(
+                        ErrorKind::InvalidInput,
+                        format!("non-ascii domain name is not supported: {}", name),
+                    ));
+                }
+                if label.len() > MAX_LABEL_LEN {
+                    return Err(Error::new(
+                        ErrorKind::InvalidInput,
+                        format!("label too long in domain name: {}", name),
+                    ));
+                }
+                total += label.len() + 1;
+                self.u8(label.len() as u8);
+                self.bytes(label.as_bytes());
+            }
+        }
+        if total > MAX_NAME_LEN {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                format!("domain name too long: {}", name),
+            ));
+        }
+        self.u8(0);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn name_round_trip() {
+        let mut writer = Writer::new();
+        writer.name("www.example.com").unwrap();
+        writer.name(".").unwrap();
+        let buf = writer.into_inner();
+        assert_eq!(&buf[..17], b"\x03www\x07example\x03com\x00");
+
+        let mut reader = Reader::new(&buf);
+        assert_eq!(reader.name().unwrap(), "www.example.com.");
+        assert_eq!(reader.name().unwrap(), ".");
+        assert_eq!(reader.pos(), buf.len());
+    }
+
+    #[test]
+    fn name_rejects_invalid_labels() {
+        assert!(Writer::new().name("a..b").is_err());
+        assert!(Writer::new().name(&"a".repeat(64)).is_err());
+        assert!(Writer::new().name(&vec!["a".repeat(63); 5].join(".")).is_err());
+    }
+
+    #[test]
+    fn name_follows_compression_pointers() {
+        // 0: example.com.  13: www -> 0  19: mail -> 13
+        let mut buf = b"\x07example\x03com\x00".to_vec();
+        buf.extend_from_slice(b"\x03www\xC0\x00");
+        buf.extend_from_slice(b"\x04mail\xC0\x0D");
+        buf.push(0xFF);
+
+        let mut reader = Reader::new(&buf);
+        assert_eq!(reader.name().unwrap(), "example.com.");
+        assert_eq!(reader.name().unwrap(), "www.example.com.");
+        assert_eq!(reader.name().unwrap(), "mail.www.example.com.");
+        // 指针之后的数据从指针结束处继续读取
+        assert_eq!(reader.u8().unwrap(), 0xFF);
+    }
+
+    #[test]
+    fn name_rejects_pointer_loop() {
+        let buf = b"\x03www\xC0\x00";
+        let err = Reader::new(buf).name().unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::InvalidData);
+    }
+
+    #[test]
+    fn name_rejects_truncated_input() {
+        let err = Reader::new(b"\x07exam").name().unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::UnexpectedEof);
+    }
+
+    #[test]
+    fn name_escapes_special_bytes() {
+        let mut reader = Reader::new(b"\x03a.b\x02\x00\\\x00");
+        assert_eq!(reader.name().unwrap(), "a\\.b.\\000\\\\.");
+    }
+
+    #[test]
+    fn reverse_names() {
+        assert_eq!(reverse_name("192.0.2.1".parse().unwrap()), "1.2.0.192.in-addr.arpa.");
+        assert_eq!(
+            reverse_name("2001:db8::1".parse().unwrap()),
+            "1.0.0.0.0.0.0.0.0.0.0.0.0.0.0.0.0.0.0.0.0.0.0.0.8.b.d.0.1.0.0.2.ip6.arpa."
+        );
+    }
+}
@@ -0,0 +1,322 @@
+use std::{
+    collections::HashSet,
+    fmt::Write,
+    sync::{
+        Mutex,
+        atomic::{AtomicU64, Ordering},
+    },
+    time::{Duration, SystemTime},
+};
+
+use openssl::{memcmp, rand::rand_bytes};
+
+use crate::http::{Request, Response, percent_encode};
+
+const REALM: &str = "sidecar-share";
+const SESSION_COOKIE: &str = "sidecar_share_session";
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub enum ShareAuth {
+    #[default]
+    None,
+    /// HTTP Basic 认证
+    Basic { username: String, password: String },
+    /// 一次性令牌：首次使用后作废，换发会话 Cookie，之后只有持有该 Cookie 的浏览器可以访问
+    Token(String),
+}
+
+/// 分享的访问控制：认证、有效期、下载次数和撤销的条目
+pub(crate) struct Access {
+    auth: ShareAuth,
+    expires_at: Option<SystemTime>,
+    max_downloads: Option<u64>,
+    downloads: AtomicU64,
+    /// 一次性令牌被使用后换发的会话标识，令牌随之失效
+    session: Mutex<Option<String>>,
+    revoked: Mutex<HashSet<String>>,
+}
+
+impl Access {
+    pub fn new(
+        auth: ShareAuth,
+        expires_in: Option<Duration>,
+        max_downloads: Option<u64>,
+    ) -> Self {
+        Self {
+            auth,
+            expires_at: expires_in.map(|duration| SystemTime::now() + duration),
+            max_downloads,
+            downloads: AtomicU64::new(0),
+            session: Mutex::new(None),
+            revoked: Mutex::new(HashSet::new()),
+        }
+    }
+
+    /// 检查有效期和认证信息，不允许访问时返回对应的响应
+    pub fn check(
+        &self,
+        request: &Request,
+    ) -> Option<Response> {
+        if self.is_expired() {
+            return Some(Response::text(410, "share expired"));
+        }
+
+        match &self.auth {
+            ShareAuth::None => None,
+            ShareAuth::Basic { username, password } => {
+                let authorized = request
+                    .header("Authorization")
+                    .and_then(|value| value.strip_prefix("Basic "))
+                    .and_then(|value| base64_decode(value.trim()))
+                    .is_some_and(|credential| {
+                        constant_time_eq(&credential, format!("{}:{}", username, password).as_bytes())
+                    });
+                (!authorized).then(|| {
+                    Response::text(401, "unauthorized").header(
+                        "WWW-Authenticate",
+                        format!("Basic realm=\"{}\", charset=\"UTF-8\"", REALM),
+                    )
+                })
+            }
+            ShareAuth::Token(token) => {
+                let mut session = self.session.lock().expect("session lock poisoned");
+                if let Some(session) = session.as_ref() {
+                    let cookie = request_cookie(request, SESSION_COOKIE);
+                    return match cookie {
+                        Some(cookie) if constant_time_eq(cookie.as_bytes(), session.as_bytes()) => None,
+                        _ => Some(Response::text(401, "link already used")),
+                    };
+                }
+                let valid = request
+                    .query_param("token")
+                    .is_some_and(|value| constant_time_eq(value.as_bytes(), token.as_bytes()));
+                if !valid {
+                    return Some(Response::text(401, "token required"));
+                }
+
+                let Some(id) = session_id() else {
+                    return Some(Response::text(500, "failed to create session"));
+                };
+                // 换发 Cookie 后重定向到不带令牌的地址
+                let response = Response::new(303)
+                    .header("Location", percent_encode(&request.path))
+                    .header(
+                        "Set-Cookie",
+                        format!("{}={}; Path=/; HttpOnly; SameSite=Lax", SESSION_COOKIE, id),
+                    );
+                *session = Some(id);
+                Some(response)
+            }
+        }
+    }
+
+    /// 入口链接需要携带的查询参数
+    pub fn entry_query(&self) -> String {
+        match &self.auth {
+            ShareAuth::Token(token) => format!("?token={}", percent_encode(token)),
+            _ => String::new(),
+        }
+    }
+
+    pub fn expires_at(&self) -> Option<SystemTime> {
+        self.expires_at
+    }
+
+    pub fn is_expired(&self) -> bool {
+        self.expires_at.is_some_and(|time| SystemTime::now() >= time)
+    }
+
+    pub fn downloads(&self) -> u64 {
+        self.downloads.load(Ordering::SeqCst)
+    }
+
+    pub fn max_downloads(&self) -> Option<u64> {
+        self.max_downloads
+    }
+
+    /// 占用一次下载次数，已达到上限时返回 false
+    pub fn take_download(&self) -> bool {
+        self.downloads
+            .fetch_update(Ordering::SeqCst, Ordering::SeqCst, |n| match self.max_downloads {
+                Some(max) if n >= max => None,
+                _ => Some(n + 1),
+            })
+            .is_ok()
+    }
+
+    pub fn revoke(
+        &self,
+        name: &str,
+    ) {
+        self.revoked
+            .lock()
+            .expect("revoked lock poisoned")
+            .insert(name.to_string());
+    }
+
+    pub fn restore(
+        &self,
+        name: &str,
+    ) {
+        self.revoked.lock().expect("revoked lock poisoned").remove(name);
+    }
+
+    pub fn is_revoked(
+        &self,
+        name: &str,
+    ) -> bool {
+        self.revoked.lock().expect("revoked lock poisoned").contains(name)
+    }
+}
+
+/// 长度不同时直接返回 false，长度本身不属于需要保密的信息
+fn constant_time_eq(
+    a: &[u8],
+    b: &[u8],
+) -> bool {
+    a.len() == b.len() && memcmp::eq(a, b)
+}
+
+/// 256 位随机数的十六进制形式
+fn session_id() -> Option<String> {
+    let mut bytes = [0u8; 32];
+    rand_bytes(&mut bytes).ok()?;
+    Some(bytes.iter().fold(String::with_capacity(64), |mut out, b| {
+        let _ = write!(out, "{:02x}", b);
+        out
+    }))
+}
+
+fn request_cookie<'a>(
+    request: &'a Request,
+    name: &str,
+) -> Option<&'a str> {
+    request
+        .header("Cookie")?
+        .split(';')
+        .filter_map(|pair| pair.trim().split_once('='))
+        .find_map(|(key, value)| (key == name).then_some(value))
+}
+
+fn base64_decode(s: &str) -> Option<Vec<u8>> {
+    let s = s.trim_end_matches('=');
+    let mut out = Vec::with_capacity(s.len() * 3 / 4);
+    let mut buf = 0u32;
+    let mut bits = 0;
+    for c in s.bytes() {
+        let value = match c {
+            b'A'..=b'Z' => c - b'A',
+            b'a'..=b'z' => c - b'a' + 26,
+            b'0'..=b'9' => c - b'0' + 52,
+            b'+' => 62,
+            b'/' => 63,
+            _ => return None,
+        };
+        buf = (buf << 6) | value as u32;
+        bits += 6;
+        if bits >= 8 {
+            bits -= 8;
+            out.push((buf >> bits) as u8);
+            buf &= (1 << bits) - 1;
+        }
+    }
+    Some(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::{BufReader, Cursor};
+
+    use super::*;
+
+    fn request(
+        target: &str,
+        headers: &[(&str, &str)],
+    ) -> Request {
+        let mut raw = format!("GET {} HTTP/1.1\r\n", target);
+        for (name, value) in headers {
+            raw.push_str(&format!("{}: {}\r\n", name, value));
+        }
+        raw.push_str("\r\n");
+        Request::read(&mut BufReader::new(Cursor::new(raw.into_bytes()))).unwrap()
+    }
+
+    /// 序列化后的响应头
+    fn head(response: Response) -> String {
+        let mut raw = Vec::new();
+        response.write_to(&mut raw, true, &mut 0).unwrap();
+        String::from_utf8(raw).unwrap()
+    }
+
+    #[test]
+    fn basic_auth() {
+        let access = Access::new(
+            ShareAuth::Basic {
+                username: "user".to_string(),
+                password: "p:ss".to_string(),
+            },
+            None,
+            None,
+        );
+        // user:p:ss
+        assert!(
+            access
+                .check(&request("/", &[("Authorization", "Basic dXNlcjpwOnNz")]))
+                .is_none()
+        );
+        // user:p:s
+        let denied = access.check(&request("/", &[("Authorization", "Basic dXNlcjpwOnM=")]));
+        assert_eq!(denied.map(|r| r.status), Some(401));
+        assert_eq!(access.check(&request("/", &[])).map(|r| r.status), Some(401));
+    }
+
+    #[test]
+    fn token_is_exchanged_for_a_session_once() {
+        let access = Access::new(ShareAuth::Token("secret".to_string()), None, None);
+        assert_eq!(access.check(&request("/", &[])).map(|r| r.status), Some(401));
+        assert_eq!(
+            access.check(&request("/?token=wrong", &[])).map(|r| r.status),
+            Some(401)
+        );
+
+        let response = access.check(&request("/a%20b/?token=secret", &[])).unwrap();
+        assert_eq!(response.status, 303);
+        let head = head(response);
+        assert!(head.contains("Location: /a%20b/\r\n"));
+        let cookie = head.lines().find_map(|line| line.strip_prefix("Set-Cookie: ")).unwrap();
+        let session = cookie.split(';').next().unwrap().to_string();
+        assert_eq!(session.len(), SESSION_COOKIE.len() + 1 + 64);
+        assert!(cookie.contains("HttpOnly"));
+
+        // 令牌已经作废，只有会话 Cookie 可以继续访问
+        assert_eq!(
+            access.check(&request("/?token=secret", &[])).map(|r| r.status),
+            Some(401)
+        );
+        assert!(
+            access
+                .check(&request("/", &[("Cookie", &format!("a=1; {}", session))]))
+                .is_none()
+        );
+        let forged = format!("{}={}", SESSION_COOKIE, "0".repeat(64));
+        assert_eq!(
+            access.check(&request("/", &[("Cookie", &forged)])).map(|r| r.status),
+            Some(401)
+        );
+    }
+
+    #[test]
+    fn expired_share_is_gone() {
+        let access = Access::new(ShareAuth::None, Some(Duration::ZERO), None);
+        assert_eq!(access.check(&request("/", &[])).map(|r| r.status), Some(410));
+    }
+
+    #[test]
+    fn download_limit() {
+        let access = Access::new(ShareAuth::None, None, Some(2));
+        assert!(access.take_download());
+        assert!(access.take_download());
+        assert!(!access.take_download());
+        assert_eq!(access.downloads(), 2);
+    }
+}
@@ -0,0 +1,392 @@
+use std::{
+    fs::File,
+    io::{BufRead, Error, ErrorKind, Read, Result, Seek, SeekFrom, Write},
+    path::Path,
+};
+
+const MAX_HEAD_SIZE: usize = 16 * 1024;
+const CHUNK_SIZE: usize = 64 * 1024;
+
+#[derive(Debug, Clone)]
+pub struct Request {
+    pub method: String,
+    /// 解码后的路径，不含查询参数
+    pub path: String,
+    /// 原始查询参数，未解码
+    pub query: String,
+    pub headers: Vec<(String, String)>,
+}
+
+impl Request {
+    /// 读取请求行和请求头，请求体留在 `reader` 中
+    pub fn read(reader: &mut impl BufRead) -> Result<Self> {
+        let mut total = 0;
+        let mut line = String::new();
+        let mut read_line = |line: &mut String| -> Result<()> {
+            line.clear();
+            let n = reader.read_line(line)?;
+            if n == 0 {
+                return Err(Error::new(ErrorKind::UnexpectedEof, "connection closed"));
+            }
+            total += n;
+            if total > MAX_HEAD_SIZE {
+                return Err(Error::new(ErrorKind::InvalidData, "request head too large"));
+            }
+            Ok(())
+        };
+
+        read_line(&mut line)?;
+        let mut parts = line.split_whitespace();
+        let (Some(method), Some(target), Some(_version)) = (parts.next(), parts.next(), parts.next()) else {
+            return Err(Error::new(ErrorKind::InvalidData, "malformed request line"));
+        };
+        let method = method.to_string();
+        let (path, query) = target.split_once('?').unwrap_or((target, ""));
+        let path = percent_decode(path).ok_or_else(|| Error::new(ErrorKind::InvalidData, "malformed request path"))?;
+        let query = query.to_string();
+
+        let mut headers = Vec::new();
+        loop {
+            read_line(&mut line)?;
+            let header = line.trim_end();
+            if header.is_empty() {
+                break;
+            }
+            if let Some((name, value)) = header.split_once(':') {
+                headers.push((name.trim().to_string(), value.trim().to_string()));
+            }
+        }
+
+        Ok(Self {
+            method,
+            path,
+            query,
+            headers,
+        })
+    }
+
+    pub fn header(
+        &self,
+        name: &str,
+    ) -> Option<&str> {
+        self.headers
+            .iter()
+            .find(|(n, _)| n.eq_ignore_ascii_case(name))
+            .map(|(_, v)| v.as_str())
+    }
+
+    pub fn query_param(
+        &self,
+        name: &str,
+    ) -> Option<String> {
+        self.query.split('&').find_map(|pair| {
+            let (key, value) = pair.split_once('=').unwrap_or((pair, ""));
+            (key == name).then(|| percent_decode(&value.replace('+', " ")))?
+        })
+    }
+}
+
+#[derive(Debug)]
+pub enum Body {
+    Empty,
+    Bytes(Vec<u8>),
+    File { file: File, offset: u64, len: u64 },
+}
+
+#[derive(Debug)]
+pub struct Response {
+    pub status: u16,
+    headers: Vec<(String, String)>,
+    body: Body,
+}
+
+impl Response {
+    pub fn new(status: u16) -> Self {
+        Self {
+            status,
+            headers: Vec::new(),
+            body: Body::Empty,
+        }
+    }
+
+    pub fn text(
+        status: u16,
+        text: impl Into<String>,
+    ) -> Self {
+        Self::new(status).bytes(text.into().into_bytes(), "text/plain; charset=utf-8")
+    }
+
+    pub fn html(
+        status: u16,
+        html: impl Into<String>,
+    ) -> Self {
+        Self::new(status).bytes(html.into().into_bytes(), "text/html; charset=utf-8")
+    }
+
+    pub fn header(
+        mut self,
+        name: &str,
+        value: impl Into<String>,
+    ) -> Self {
+        self.headers.push((name.to_string(), value.into()));
+        self
+    }
+
+    pub fn bytes(
+        self,
+        bytes: Vec<u8>,
+        content_type: &str,
+    ) -> Self {
+        let mut response = self.header("Content-Type", content_type);
+        response.body = Body::Bytes(bytes);
+        response
+    }
+
+    pub fn file(
+        self,
+        file: File,
+        offset: u64,
+        len: u64,
+        content_type: &str,
+    ) -> Self {
+        let mut response = self.header("Content-Type", content_type);
+        response.body = Body::File { file, offset, len };
+        response
+    }
+
+    /// 文件响应体的起始偏移，非文件响应返回 None
+    pub fn file_offset(&self) -> Option<u64> {
+        match &self.body {
+            Body::File { offset, .. } => Some(*offset),
+            _ => None,
+        }
+    }
+
+    pub fn content_length(&self) -> u64 {
+        match &self.body {
+            Body::Empty => 0,
+            Body::Bytes(bytes) => bytes.len() as u64,
+            Body::File { len, .. } => *len,
+        }
+    }
+
+    /// 写出完整响应，`sent` 累计已发送的响应体字节数，连接中断时也能得到实际发送量
+    pub fn write_to(
+        self,
+        w: &mut impl Write,
+        head_only: bool,
+        sent: &mut u64,
+    ) -> Result<()> {
+        let mut head = format!("HTTP/1.1 {} {}\r\n", self.status, reason(self.status));
+        for (name, value) in &self.headers {
+            head.push_str(&format!("{}: {}\r\n", name, value));
+        }
+        head.push_str(&format!("Content-Length: {}\r\n", self.content_length()));
+        head.push_str("Connection: close\r\n\r\n");
+        w.write_all(head.as_bytes())?;
+        if head_only {
+            return w.flush();
+        }
+
+        match self.body {
+            Body::Empty => {}
+            Body::Bytes(bytes) => {
+                w.write_all(&bytes)?;
+                *sent += bytes.len() as u64;
+            }
+            Body::File { mut file, offset, len } => {
+                file.seek(SeekFrom::Start(offset))?;
+                let mut file = file.take(len);
+                let mut buf = vec![0u8; CHUNK_SIZE];
+                loop {
+                    let n = file.read(&mut buf)?;
+                    if n == 0 {
+                        break;
+                    }
+                    w.write_all(&buf[..n])?;
+                    *sent += n as u64;
+                }
+            }
+        }
+        w.flush()
+    }
+}
+
+pub fn reason(status: u16) -> &'static str {
+    match status {
+        200 => "OK",
+        201 => "Created",
+        206 => "Partial Content",
+        301 => "Moved Permanently",
+        303 => "See Other",
+        304 => "Not Modified",
+        400 => "Bad Request",
+        401 => "Unauthorized",
+        403 => "Forbidden",
+        404 => "Not Found",
+        405 => "Method Not Allowed",
+        410 => "Gone",
+        411 => "Length Required",
+        413 => "Payload Too Large",
+        416 => "Range Not Satisfiable",
+        500 => "Internal Server Error",
+        _ => "Unknown",
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ByteRange {
+    /// 没有或无法识别 Range 头，返回完整内容
+    Full,
+    /// 闭区间 `[start, end]`
+    Partial(u64, u64),
+    Unsatisfiable,
+}
+
+/// 解析 `Range: bytes=...` 请求头，只支持单个区间，多区间按完整内容处理
+pub fn parse_range(
+    value: &str,
+    size: u64,
+) -> ByteRange {
+    let Some(spec) = value.trim().strip_prefix("bytes=") else {
+        return ByteRange::Full;
+    };
+    if spec.contains(',') {
+        return ByteRange::Full;
+    }
+    let Some((start, end)) = spec.trim().split_once('-') else {
+        return ByteRange::Full;
+    };
+
+    let (start, end) = match (start.trim(), end.trim()) {
+        ("", "") => return ByteRange::Full,
+        // 后缀区间：最后 N 个字节
+        ("", suffix) => {
+            let Ok(suffix) = suffix.parse::<u64>() else {
+                return ByteRange::Full;
+            };
+            if suffix == 0 || size == 0 {
+                return ByteRange::Unsatisfiable;
+            }
+            (size.saturating_sub(suffix), size - 1)
+        }
+        (start, end) => {
+            let Ok(start) = start.parse::<u64>() else {
+                return ByteRange::Full;
+            };
+            let end = if end.is_empty() {
+                size.saturating_sub(1)
+            } else {
+                let Ok(end) = end.parse::<u64>() else {
+                    return ByteRange::Full;
+                };
+                if end < start {
+                    return ByteRange::Full;
+                }
+                end.min(size.saturating_sub(1))
+            };
+            (start, end)
+        }
+    };
+
+    if start >= size {
+        return ByteRange::Unsatisfiable;
+    }
+    ByteRange::Partial(start, end)
+}
+
+pub fn percent_decode(s: &str) -> Option<String> {
+    let bytes = s.as_bytes();
+    let mut out = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes[i] == b'%' {
+            let hex = s.get(i + 1..i + 3)?;
+            out.push(u8::from_str_radix(hex, 16).ok()?);
+            i += 3;
+        } else {
+            out.push(bytes[i]);
+            i += 1;
+        }
+    }
+    String::from_utf8(out).ok()
+}
+
+/// 编码 URL 路径，保留 `/` 和非保留字符
+pub fn percent_encode(s: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+    for &b in s.as_bytes() {
+        match b {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' | b'/' => out.push(b as char),
+            _ => out.push_str(&format!("%{:02X}", b)),
+        }
+    }
+    out
+}
+
+pub fn html_escape(s: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+    for c in s.chars() {
+        match c {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '"' => out.push_str("&quot;"),
+            '\'' => out.push_str("&#39;"),
+            _ => out.push(c),
+        }
+    }
+    out
+}
+
+pub fn content_type(path: &Path) -> &'static str {
+    let ext = path
+        .extension()
+        .and_then(|e| e.to_str())
+        .unwrap_or_default()
+        .to_ascii_lowercase();
+    match ext.as_str() {
+        "html" | "htm" => "text/html; charset=utf-8",
+        "css" => "text/css; charset=utf-8",
+        "js" | "mjs" => "text/javascript; charset=utf-8",
+        "json" => "application/json",
+        "txt" | "log" | "md" | "rs" | "toml" | "yaml" | "yml" | "ini" | "conf" => "text/plain; charset=utf-8",
+        "xml" => "application/xml",
+        "csv" => "text/csv; charset=utf-8",
+        "svg" => "image/svg+xml",
+        "png" => "image/png",
+        "jpg" | "jpeg" => "image/jpeg",
+        "gif" => "image/gif",
+        "webp" => "image/webp",
+        "ico" => "image/x-icon",
+        "bmp" => "image/bmp",
+        "pdf" => "application/pdf",
+        "zip" => "application/zip",
+        "gz" | "tgz" => "application/gzip",
+        "tar" => "application/x-tar",
+        "7z" => "application/x-7z-compressed",
+        "mp3" => "audio/mpeg",
+        "wav" => "audio/wav",
+        "ogg" => "audio/ogg",
+        "mp4" => "video/mp4",
+        "webm" => "video/webm",
+        "mov" => "video/quicktime",
+        "wasm" => "application/wasm",
+        _ => "application/octet-stream",
+    }
+}
+
+pub fn format_size(bytes: u64) -> String {
+    const UNITS: [&str; 5] = ["B", "KB", "MB", "GB", "TB"];
+    let mut size = bytes as f64;
+    let mut unit = 0;
+    while size >= 1024.0 && unit < UNITS.len() - 1 {
+        size /= 1024.0;
+        unit += 1;
+    }
+    if unit == 0 {
+        format!("{} B", bytes)
+    } else {
+        format!("{:.1} {}", size, UNITS[unit])
+    }
+}
@@ -0,0 +1,9 @@
+mod access;
+mod http;
+mod receive;
+mod server;
+
+pub use access::ShareAuth;
+pub use http::{ByteRange, format_size, parse_range};
+pub use receive::UploadEvent;
+pub use server::{AccessLog, DEFAULT_PORT, ShareHandle, ShareItem, ShareServer};
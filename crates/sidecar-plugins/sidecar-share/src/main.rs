@@ -0,0 +1,112 @@
+use std::{env, path::PathBuf, process, thread, time::Duration};
+
+use openssl::rand::rand_bytes;
+use sidecar_share::{DEFAULT_PORT, ShareAuth, ShareServer, UploadEvent};
+
+const USAGE: &str = "usage: sidecar-share [-p port] [options] path...
+       sidecar-share [-p port] [options] [-m max_bytes] -r dir
+options:
+  -u user:password  require basic auth
+  -t                require a one-time token
+  -e seconds        expire after the given time
+  -n count          limit the number of downloads";
+
+fn main() {
+    let mut port = DEFAULT_PORT;
+    let mut paths = Vec::new();
+    let mut receive = None;
+    let mut max_size = None;
+    let mut auth = ShareAuth::None;
+    let mut expires_in = None;
+    let mut max_downloads = None;
+
+    let mut args = env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "-p" | "--port" => match args.next().and_then(|p| p.parse().ok()) {
+                Some(p) => port = p,
+                None => fail(USAGE),
+            },
+            "-r" | "--receive" => match args.next() {
+                Some(dir) => receive = Some(PathBuf::from(dir)),
+                None => fail(USAGE),
+            },
+            "-m" | "--max-size" => match args.next().and_then(|m| m.parse().ok()) {
+                Some(m) => max_size = Some(m),
+                None => fail(USAGE),
+            },
+            "-u" | "--user" => match args.next().as_deref().and_then(|u| u.split_once(':')) {
+                Some((username, password)) => {
+                    auth = ShareAuth::Basic {
+                        username: username.to_string(),
+                        password: password.to_string(),
+                    }
+                }
+                None => fail(USAGE),
+            },
+            "-t" | "--token" => auth = ShareAuth::Token(random_token()),
+            "-e" | "--expires" => match args.next().and_then(|e| e.parse().ok()) {
+                Some(e) => expires_in = Some(Duration::from_secs(e)),
+                None => fail(USAGE),
+            },
+            "-n" | "--downloads" => match args.next().and_then(|n| n.parse().ok()) {
+                Some(n) => max_downloads = Some(n),
+                None => fail(USAGE),
+            },
+            "-h" | "--help" => fail(USAGE),
+            _ => paths.push(PathBuf::from(arg)),
+        }
+    }
+    let server = match receive {
+        Some(dir) => ShareServer::receive(dir, random_token()).map(|server| match max_size {
+            Some(max) => server.max_upload_size(max),
+            None => server,
+        }),
+        None if paths.is_empty() => fail(USAGE),
+        None => ShareServer::new(paths),
+    };
+    let server = match server {
+        Ok(server) => server.port(port).auth(auth),
+        Err(e) => fail(&format!("share failed: {}", e)),
+    };
+    let server = match expires_in {
+        Some(duration) => server.expires_in(duration),
+        None => server,
+    };
+    let server = match max_downloads {
+        Some(count) => server.max_downloads(count),
+        None => server,
+    };
+    let handle = match server.start() {
+        Ok(handle) => handle,
+        Err(e) => fail(&format!("start failed: {}", e)),
+    };
+    println!("serving on http://{}{}", handle.local_addr(), handle.entry_path());
+
+    loop {
+        for log in handle.drain_logs() {
+            println!("{}", log);
+        }
+        for event in handle.drain_uploads() {
+            match event {
+                UploadEvent::Finished { path, .. } => println!("received {}", path.display()),
+                UploadEvent::Failed { error, .. } => println!("upload failed: {}", error),
+                _ => {}
+            }
+        }
+        thread::sleep(Duration::from_millis(200));
+    }
+}
+
+fn random_token() -> String {
+    let mut bytes = [0u8; 16];
+    if let Err(e) = rand_bytes(&mut bytes) {
+        fail(&format!("random token failed: {}", e));
+    }
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+fn fail(message: &str) -> ! {
+    eprintln!("{}", message);
+    process::exit(1);
+}
@@ -0,0 +1,366 @@
+use std::{
+    fs::{self, File, OpenOptions},
+    io::{Error, ErrorKind, Read, Result, Write},
+    net::SocketAddr,
+    path::{Path, PathBuf},
+    time::{Duration, Instant},
+};
+
+use openssl::rand::rand_bytes;
+
+use crate::http::{Request, Response, format_size, html_escape};
+
+const CHUNK_SIZE: usize = 64 * 1024;
+const PROGRESS_INTERVAL: Duration = Duration::from_millis(200);
+
+const UPLOAD_PAGE: &str = r#"<!DOCTYPE html>
+<html>
+<head>
+<meta charset="utf-8">
+<meta name="viewport" content="width=device-width, initial-scale=1">
+<title>上传文件</title>
+<style>
+body { font-family: -apple-system, sans-serif; margin: 2em; }
+button { margin-top: 1em; padding: 6px 24px; }
+#list div { padding: 6px 0; border-bottom: 1px solid #ddd; }
+</style>
+</head>
+<body>
+<h3>上传文件</h3>
+<input type="file" id="files" multiple>
+<br>
+<button id="upload">上传</button>
+<p>__LIMIT__</p>
+<div id="list"></div>
+<script>
+const MAX_SIZE = __MAX_SIZE__;
+const list = document.getElementById('list');
+document.getElementById('upload').onclick = async () => {
+  for (const file of document.getElementById('files').files) {
+    await upload(file);
+  }
+};
+function upload(file) {
+  return new Promise(resolve => {
+    const row = document.createElement('div');
+    const show = text => row.textContent = file.name + '  ' + text;
+    list.appendChild(row);
+    if (MAX_SIZE > 0 && file.size > MAX_SIZE) {
+      show('超过大小限制');
+      return resolve();
+    }
+    const xhr = new XMLHttpRequest();
+    xhr.open('POST', '__UPLOAD_URL__?name=' + encodeURIComponent(file.name));
+    xhr.upload.onprogress = e => e.lengthComputable && show(Math.floor(e.loaded * 100 / e.total) + '%');
+    xhr.onload = () => { show(xhr.status === 201 ? '上传完成' : '上传失败: ' + xhr.responseText); resolve(); };
+    xhr.onerror = () => { show('上传失败'); resolve(); };
+    show('0%');
+    xhr.send(file);
+  });
+}
+</script>
+</body>
+</html>
+"#;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum UploadEvent {
+    Started {
+        id: u64,
+        peer: SocketAddr,
+        name: String,
+        size: u64,
+    },
+    Progress {
+        id: u64,
+        received: u64,
+    },
+    Finished {
+        id: u64,
+        path: PathBuf,
+    },
+    Failed {
+        id: u64,
+        error: String,
+    },
+}
+
+#[derive(Debug, Clone)]
+pub(crate) struct Receive {
+    pub dir: PathBuf,
+    pub token: String,
+    pub max_size: Option<u64>,
+}
+
+impl Receive {
+    pub fn base_path(&self) -> String {
+        format!("/{}/", self.token)
+    }
+
+    pub fn upload_page(&self) -> Response {
+        let limit = match self.max_size {
+            Some(max) => format!("单个文件最大 {}", format_size(max)),
+            None => "不限制文件大小".to_string(),
+        };
+        let html = UPLOAD_PAGE
+            .replace("__LIMIT__", &html_escape(&limit))
+            .replace("__MAX_SIZE__", &self.max_size.unwrap_or(0).to_string())
+            .replace("__UPLOAD_URL__", &format!("{}upload", self.base_path()));
+        Response::html(200, html)
+    }
+
+    /// 接收请求体并写入目标目录，`emit` 用于上报进度
+    pub fn upload(
+        &self,
+        id: u64,
+        peer: SocketAddr,
+        request: &Request,
+        body: &mut impl Read,
+        emit: impl Fn(UploadEvent),
+    ) -> Response {
+        let Some(name) = request.query_param("name").and_then(|name| sanitize_name(&name)) else {
+            return Response::text(400, "invalid file name");
+        };
+        let Some(size) = request.header("Content-Length").and_then(|v| v.parse::<u64>().ok()) else {
+            return Response::text(411, "content length required");
+        };
+        if let Some(max) = self.max_size
+            && size > max
+        {
+            return Response::text(413, format!("file too large, limit is {}", format_size(max)));
+        }
+
+        emit(UploadEvent::Started {
+            id,
+            peer,
+            name: name.clone(),
+            size,
+        });
+        // 先写入临时文件，完成后再改名，避免留下不完整的文件
+        let result = create_part(&self.dir).and_then(|(part, file)| {
+            receive_to(file, size, body, |received| {
+                emit(UploadEvent::Progress { id, received })
+            })
+            .and_then(|_| {
+                let path = reserve_path(&self.dir, &name)?;
+                // 覆盖刚占位的空文件，失败时清理占位
+                fs::rename(&part, &path).inspect_err(|_| {
+                    let _ = fs::remove_file(&path);
+                })?;
+                Ok(path)
+            })
+            .inspect_err(|_| {
+                let _ = fs::remove_file(&part);
+            })
+        });
+
+        match result {
+            Ok(path) => {
+                emit(UploadEvent::Finished { id, path });
+                Response::text(201, "ok")
+            }
+            Err(e) => {
+                emit(UploadEvent::Failed {
+                    id,
+                    error: e.to_string(),
+                });
+                Response::text(500, e.to_string())
+            }
+        }
+    }
+}
+
+/// 以随机名称新建临时文件，不会截断同名的已有文件
+fn create_part(dir: &Path) -> Result<(PathBuf, File)> {
+    loop {
+        let mut suffix = [0u8; 8];
+        rand_bytes(&mut suffix).map_err(Error::other)?;
+        let suffix: String = suffix.iter().map(|b| format!("{:02x}", b)).collect();
+        let path = dir.join(format!(".sidecar-upload-{}.part", suffix));
+        match OpenOptions::new().write(true).create_new(true).open(&path) {
+            Ok(file) => return Ok((path, file)),
+            Err(e) if e.kind() == ErrorKind::AlreadyExists => continue,
+            Err(e) => return Err(e),
+        }
+    }
+}
+
+fn receive_to(
+    mut file: File,
+    size: u64,
+    body: &mut impl Read,
+    progress: impl Fn(u64),
+) -> Result<()> {
+    let mut body = body.take(size);
+    let mut buf = vec![0u8; CHUNK_SIZE];
+    let mut received = 0;
+    let mut reported = Instant::now();
+    loop {
+        let n = body.read(&mut buf)?;
+        if n == 0 {
+            break;
+        }
+        file.write_all(&buf[..n])?;
+        received += n as u64;
+        if reported.elapsed() >= PROGRESS_INTERVAL {
+            progress(received);
+            reported = Instant::now();
+        }
+    }
+    if received < size {
+        return Err(Error::new(
+            ErrorKind::UnexpectedEof,
+            format!("connection closed after {} of {} bytes", received, size),
+        ));
+    }
+    progress(received);
+    file.sync_all()
+}
+
+/// 只保留文件名部分，去掉路径和控制字符
+fn sanitize_name(name: &str) -> Option<String> {
+    let name: String = name
+        .rsplit(['/', '\\'])
+        .next()?
+        .chars()
+        .filter(|c| !c.is_control())
+        .collect();
+    let name = name.trim();
+    if name.is_empty() || name == "." || name == ".." {
+        return None;
+    }
+    Some(name.to_string())
+}
+
+/// 以 `create_new` 原子地占用目标文件名，已存在时追加序号，例如 `a (2).txt`
+fn reserve_path(
+    dir: &Path,
+    name: &str,
+) -> Result<PathBuf> {
+    let (stem, ext) = match name.rsplit_once('.') {
+        Some((stem, ext)) if !stem.is_empty() => (stem, format!(".{}", ext)),
+        _ => (name, String::new()),
+    };
+    let candidates = std::iter::once(dir.join(name)).chain((2..).map(|n| dir.join(format!("{} ({}){}", stem, n, ext))));
+    for path in candidates {
+        match OpenOptions::new().write(true).create_new(true).open(&path) {
+            Ok(_) => return Ok(path),
+            Err(e) if e.kind() == ErrorKind::AlreadyExists => continue,
+            Err(e) => return Err(e),
+        }
+    }
+    unreachable!("unbounded range")
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        io::{BufReader, Cursor},
+        net::{Ipv4Addr, SocketAddr},
+        sync::{Arc, Barrier},
+        thread,
+    };
+
+    use super::*;
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("sidecar-receive-{}-{}", std::process::id(), name));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    fn upload(
+        receive: &Receive,
+        name: &str,
+        body: &str,
+    ) -> (Response, Vec<UploadEvent>) {
+        let raw = format!(
+            "POST /t/upload?name={} HTTP/1.1\r\nContent-Length: {}\r\n\r\n{}",
+            name,
+            body.len(),
+            body
+        );
+        let mut reader = BufReader::new(Cursor::new(raw.into_bytes()));
+        let request = Request::read(&mut reader).unwrap();
+        let events = std::cell::RefCell::new(Vec::new());
+        let peer = SocketAddr::new(Ipv4Addr::LOCALHOST.into(), 1234);
+        let response = receive.upload(1, peer, &request, &mut reader, |event| events.borrow_mut().push(event));
+        (response, events.into_inner())
+    }
+
+    #[test]
+    fn reserve_path_appends_index() {
+        let dir = temp_dir("reserve");
+        assert_eq!(reserve_path(&dir, "a.txt").unwrap(), dir.join("a.txt"));
+        assert_eq!(reserve_path(&dir, "a.txt").unwrap(), dir.join("a (2).txt"));
+        assert_eq!(reserve_path(&dir, "a.txt").unwrap(), dir.join("a (3).txt"));
+        assert_eq!(reserve_path(&dir, ".env").unwrap(), dir.join(".env"));
+        assert_eq!(reserve_path(&dir, ".env").unwrap(), dir.join(".env (2)"));
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn concurrent_reservations_never_share_a_path() {
+        let dir = temp_dir("concurrent");
+        let barrier = Arc::new(Barrier::new(16));
+        let handles: Vec<_> = (0..16)
+            .map(|_| {
+                let dir = dir.clone();
+                let barrier = barrier.clone();
+                thread::spawn(move || {
+                    barrier.wait();
+                    reserve_path(&dir, "same.bin").unwrap()
+                })
+            })
+            .collect();
+        let mut paths: Vec<PathBuf> = handles.into_iter().map(|h| h.join().unwrap()).collect();
+        paths.sort();
+        paths.dedup();
+        assert_eq!(paths.len(), 16);
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn upload_keeps_existing_files() {
+        let dir = temp_dir("upload");
+        fs::write(dir.join("note.txt"), "old").unwrap();
+        let receive = Receive {
+            dir: dir.clone(),
+            token: "t".to_string(),
+            max_size: Some(16),
+        };
+
+        let (response, events) = upload(&receive, "note.txt", "new");
+        assert_eq!(response.status, 201);
+        assert_eq!(
+            events.last(),
+            Some(&UploadEvent::Finished {
+                id: 1,
+                path: dir.join("note (2).txt")
+            })
+        );
+        assert_eq!(fs::read_to_string(dir.join("note.txt")).unwrap(), "old");
+        assert_eq!(fs::read_to_string(dir.join("note (2).txt")).unwrap(), "new");
+        let parts = fs::read_dir(&dir)
+            .unwrap()
+            .filter(|entry| entry.as_ref().unwrap().file_name().to_string_lossy().ends_with(".part"))
+            .count();
+        assert_eq!(parts, 0);
+
+        let (response, _) = upload(&receive, "big.bin", "0123456789abcdefg");
+        assert_eq!(response.status, 413);
+        let (response, _) = upload(&receive, "..", "x");
+        assert_eq!(response.status, 400);
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn sanitize_names() {
+        assert_eq!(sanitize_name("../../etc/passwd").as_deref(), Some("passwd"));
+        assert_eq!(sanitize_name("C:\\tmp\\a.txt").as_deref(), Some("a.txt"));
+        assert_eq!(sanitize_name(" a\u{7}b ").as_deref(), Some("ab"));
+        assert_eq!(sanitize_name(".."), None);
+        assert_eq!(sanitize_name("dir/"), None);
+    }
+}
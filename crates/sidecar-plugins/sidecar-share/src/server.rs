@@ -0,0 +1,790 @@
+use std::{
+    fmt, fs,
+    io::{BufReader, Error, ErrorKind, Read, Result},
+    net::{IpAddr, Ipv4Addr, SocketAddr, TcpListener, TcpStream},
+    path::{Path, PathBuf},
+    sync::{
+        Arc, Mutex,
+        atomic::{AtomicBool, AtomicU64, AtomicUsize, Ordering},
+    },
+    thread::{self, JoinHandle},
+    time::{Duration, SystemTime},
+};
+
+use crate::{
+    access::{Access, ShareAuth},
+    http::{ByteRange, Request, Response, content_type, format_size, html_escape, parse_range, percent_encode},
+    receive::{Receive, UploadEvent},
+};
+
+pub const DEFAULT_PORT: u16 = 8000;
+
+const READ_TIMEOUT: Duration = Duration::from_secs(15);
+const WRITE_TIMEOUT: Duration = Duration::from_secs(60);
+const MAX_LOGS: usize = 1000;
+/// 同时处理的连接数上限，达到上限后暂停 accept，新连接留在内核队列中等待
+const MAX_CONNECTIONS: usize = 64;
+/// 监听线程检查停止标志的间隔，也是 `stop` 最长的等待时间
+const ACCEPT_INTERVAL: Duration = Duration::from_millis(50);
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ShareItem {
+    /// URL 中使用的名称，重名时自动追加序号
+    pub name: String,
+    pub path: PathBuf,
+    pub is_dir: bool,
+}
+
+#[derive(Debug, Clone)]
+pub struct AccessLog {
+    pub time: SystemTime,
+    pub peer: SocketAddr,
+    pub method: String,
+    pub path: String,
+    pub status: u16,
+    pub bytes: u64,
+}
+
+impl fmt::Display for AccessLog {
+    fn fmt(
+        &self,
+        f: &mut fmt::Formatter<'_>,
+    ) -> fmt::Result {
+        write!(
+            f,
+            "{} \"{} {}\" {} {}",
+            self.peer.ip(),
+            self.method,
+            self.path,
+            self.status,
+            self.bytes
+        )
+    }
+}
+
+#[derive(Debug, Clone)]
+enum Mode {
+    Serve(Vec<ShareItem>),
+    Receive(Receive),
+}
+
+struct State {
+    mode: Mode,
+    logs: Mutex<Vec<AccessLog>>,
+    uploads: Mutex<Vec<UploadEvent>>,
+    next_upload: AtomicU64,
+    access: Access,
+    connections: AtomicUsize,
+}
+
+impl State {
+    fn log(
+        &self,
+        log: AccessLog,
+    ) {
+        let mut logs = self.logs.lock().expect("logs lock poisoned");
+        if logs.len() >= MAX_LOGS {
+            logs.remove(0);
+        }
+        logs.push(log);
+    }
+
+    fn emit(
+        &self,
+        event: UploadEvent,
+    ) {
+        self.uploads.lock().expect("uploads lock poisoned").push(event);
+    }
+
+    /// 占用一个连接名额，已满时返回 `None`
+    fn acquire(self: &Arc<Self>) -> Option<ConnectionGuard> {
+        self.connections
+            .fetch_update(Ordering::AcqRel, Ordering::Acquire, |n| {
+                (n < MAX_CONNECTIONS).then_some(n + 1)
+            })
+            .ok()?;
+        Some(ConnectionGuard(self.clone()))
+    }
+}
+
+/// 连接处理结束时释放名额
+struct ConnectionGuard(Arc<State>);
+
+impl Drop for ConnectionGuard {
+    fn drop(&mut self) {
+        self.0.connections.fetch_sub(1, Ordering::AcqRel);
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct ShareServer {
+    mode: Mode,
+    addr: SocketAddr,
+    auth: ShareAuth,
+    expires_in: Option<Duration>,
+    max_downloads: Option<u64>,
+}
+
+impl ShareServer {
+    pub fn new(paths: impl IntoIterator<Item = PathBuf>) -> Result<Self> {
+        let mut items: Vec<ShareItem> = Vec::new();
+        for path in paths {
+            let meta = fs::metadata(&path)?;
+            let base = path
+                .file_name()
+                .map(|n| n.to_string_lossy().into_owned())
+                .unwrap_or_else(|| "root".to_string());
+            let mut name = base.clone();
+            let mut n = 2;
+            while items.iter().any(|item| item.name == name) {
+                name = format!("{} ({})", base, n);
+                n += 1;
+            }
+            items.push(ShareItem {
+                name,
+                path,
+                is_dir: meta.is_dir(),
+            });
+        }
+        if items.is_empty() {
+            return Err(Error::new(ErrorKind::InvalidInput, "nothing to share"));
+        }
+
+        Ok(Self {
+            mode: Mode::Serve(items),
+            addr: SocketAddr::new(IpAddr::V4(Ipv4Addr::UNSPECIFIED), DEFAULT_PORT),
+            auth: ShareAuth::None,
+            expires_in: None,
+            max_downloads: None,
+        })
+    }
+
+    /// 接收模式：提供上传页面，文件保存到 `dir`，访问路径需要带上 `token`
+    pub fn receive(
+        dir: PathBuf,
+        token: impl Into<String>,
+    ) -> Result<Self> {
+        if !fs::metadata(&dir)?.is_dir() {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                format!("not a directory: {}", dir.display()),
+            ));
+        }
+        let token = token.into();
+        if token.is_empty() || token.contains('/') {
+            return Err(Error::new(ErrorKind::InvalidInput, "invalid token"));
+        }
+
+        Ok(Self {
+            mode: Mode::Receive(Receive {
+                dir,
+                token,
+                max_size: None,
+            }),
+            addr: SocketAddr::new(IpAddr::V4(Ipv4Addr::UNSPECIFIED), DEFAULT_PORT),
+            auth: ShareAuth::None,
+            expires_in: None,
+            max_downloads: None,
+        })
+    }
+
+    /// 接收模式下单个文件的大小上限
+    pub fn max_upload_size(
+        mut self,
+        size: u64,
+    ) -> Self {
+        if let Mode::Receive(receive) = &mut self.mode {
+            receive.max_size = Some(size);
+        }
+        self
+    }
+
+    pub fn auth(
+        mut self,
+        auth: ShareAuth,
+    ) -> Self {
+        self.auth = auth;
+        self
+    }
+
+    /// 启动后经过 `duration` 失效，之后所有请求返回 410
+    pub fn expires_in(
+        mut self,
+        duration: Duration,
+    ) -> Self {
+        self.expires_in = Some(duration);
+        self
+    }
+
+    /// 文件下载次数上限，下载开始时计数，断点续传的请求同样计入
+    pub fn max_downloads(
+        mut self,
+        count: u64,
+    ) -> Self {
+        self.max_downloads = Some(count);
+        self
+    }
+
+    pub fn addr(
+        mut self,
+        addr: SocketAddr,
+    ) -> Self {
+        self.addr = addr;
+        self
+    }
+
+    pub fn port(
+        mut self,
+        port: u16,
+    ) -> Self {
+        self.addr.set_port(port);
+        self
+    }
+
+    pub fn items(&self) -> &[ShareItem] {
+        match &self.mode {
+            Mode::Serve(items) => items,
+            Mode::Receive(_) => &[],
+        }
+    }
+
+    pub fn start(self) -> Result<ShareHandle> {
+        let listener = TcpListener::bind(self.addr)?;
+        // 非阻塞 accept，停止时无需再连接自身来唤醒监听线程
+        listener.set_nonblocking(true)?;
+        let addr = listener.local_addr()?;
+        let state = Arc::new(State {
+            mode: self.mode,
+            logs: Mutex::new(Vec::new()),
+            uploads: Mutex::new(Vec::new()),
+            next_upload: AtomicU64::new(1),
+            access: Access::new(self.auth, self.expires_in, self.max_downloads),
+            connections: AtomicUsize::new(0),
+        });
+        let stopped = Arc::new(AtomicBool::new(false));
+
+        let thread = {
+            let state = state.clone();
+            let stopped = stopped.clone();
+            thread::spawn(move || {
+                while !stopped.load(Ordering::SeqCst) {
+                    let Some(guard) = state.acquire() else {
+                        thread::sleep(ACCEPT_INTERVAL);
+                        continue;
+                    };
+                    let stream = match listener.accept() {
+                        Ok((stream, _)) => stream,
+                        // 没有新连接，或文件描述符耗尽等错误时稍后重试
+                        Err(_) => {
+                            thread::sleep(ACCEPT_INTERVAL);
+                            continue;
+                        }
+                    };
+                    // 部分平台上 accept 得到的连接会继承非阻塞标志
+                    if stream.set_nonblocking(false).is_err() {
+                        continue;
+                    }
+                    thread::spawn(move || handle_connection(stream, &guard.0));
+                }
+            })
+        };
+
+        Ok(ShareHandle {
+            addr,
+            state,
+            stopped,
+            thread: Some(thread),
+        })
+    }
+}
+
+/// 运行中的分享服务，drop 时自动停止
+pub struct ShareHandle {
+    addr: SocketAddr,
+    state: Arc<State>,
+    stopped: Arc<AtomicBool>,
+    thread: Option<JoinHandle<()>>,
+}
+
+impl ShareHandle {
+    pub fn local_addr(&self) -> SocketAddr {
+        self.addr
+    }
+
+    /// 访问入口路径，接收模式下包含路径 token，一次性令牌认证时附带查询参数
+    pub fn entry_path(&self) -> String {
+        let path = match &self.state.mode {
+            Mode::Serve(_) => "/".to_string(),
+            Mode::Receive(receive) => receive.base_path(),
+        };
+        format!("{}{}", path, self.state.access.entry_query())
+    }
+
+    pub fn items(&self) -> &[ShareItem] {
+        match &self.state.mode {
+            Mode::Serve(items) => items,
+            Mode::Receive(_) => &[],
+        }
+    }
+
+    /// 撤销单个分享条目，之后访问该条目返回 410
+    pub fn revoke(
+        &self,
+        name: &str,
+    ) {
+        self.state.access.revoke(name);
+    }
+
+    pub fn restore(
+        &self,
+        name: &str,
+    ) {
+        self.state.access.restore(name);
+    }
+
+    pub fn is_revoked(
+        &self,
+        name: &str,
+    ) -> bool {
+        self.state.access.is_revoked(name)
+    }
+
+    pub fn downloads(&self) -> u64 {
+        self.state.access.downloads()
+    }
+
+    pub fn max_downloads(&self) -> Option<u64> {
+        self.state.access.max_downloads()
+    }
+
+    pub fn expires_at(&self) -> Option<SystemTime> {
+        self.state.access.expires_at()
+    }
+
+    pub fn is_expired(&self) -> bool {
+        self.state.access.is_expired()
+    }
+
+    /// 取出自上次调用以来的访问日志
+    pub fn drain_logs(&self) -> Vec<AccessLog> {
+        std::mem::take(&mut *self.state.logs.lock().expect("logs lock poisoned"))
+    }
+
+    /// 取出自上次调用以来的上传事件
+    pub fn drain_uploads(&self) -> Vec<UploadEvent> {
+        std::mem::take(&mut *self.state.uploads.lock().expect("uploads lock poisoned"))
+    }
+
+    /// 停止监听，最多等待一个 accept 轮询间隔；已建立的连接会继续处理完
+    pub fn stop(&mut self) {
+        let Some(thread) = self.thread.take() else {
+            return;
+        };
+        self.stopped.store(true, Ordering::SeqCst);
+        let _ = thread.join();
+    }
+}
+
+impl Drop for ShareHandle {
+    fn drop(&mut self) {
+        self.stop();
+    }
+}
+
+fn handle_connection(
+    stream: TcpStream,
+    state: &State,
+) {
+    let Ok(peer) = stream.peer_addr() else {
+        return;
+    };
+    let _ = stream.set_read_timeout(Some(READ_TIMEOUT));
+    let _ = stream.set_write_timeout(Some(WRITE_TIMEOUT));
+    let Ok(mut writer) = stream.try_clone() else {
+        return;
+    };
+    let mut reader = BufReader::new(stream);
+
+    let (method, path, response) = match Request::read(&mut reader) {
+        Ok(request) => {
+            let response = match state.access.check(&request) {
+                Some(response) => response,
+                None => match &state.mode {
+                    Mode::Serve(items) => route(&request, items, &state.access),
+                    Mode::Receive(receive) => route_receive(&request, &mut reader, peer, receive, state),
+                },
+            };
+            (request.method, request.path, response)
+        }
+        Err(e) if e.kind() == ErrorKind::UnexpectedEof => return,
+        Err(e) => ("-".to_string(), "-".to_string(), Response::text(400, e.to_string())),
+    };
+
+    let status = response.status;
+    let mut sent = 0;
+    let _ = response.write_to(&mut writer, method == "HEAD", &mut sent);
+    state.log(AccessLog {
+        time: SystemTime::now(),
+        peer,
+        method,
+        path,
+        status,
+        bytes: sent,
+    });
+}
+
+fn route_receive(
+    request: &Request,
+    body: &mut impl Read,
+    peer: SocketAddr,
+    receive: &Receive,
+    state: &State,
+) -> Response {
+    let segments: Vec<&str> = request.path.split('/').filter(|s| !s.is_empty()).collect();
+    match (request.method.as_str(), segments.as_slice()) {
+        ("GET" | "HEAD", [token]) if *token == receive.token => {
+            if request.path.ends_with('/') {
+                receive.upload_page()
+            } else {
+                Response::new(301).header("Location", receive.base_path())
+            }
+        }
+        ("POST", [token, "upload"]) if *token == receive.token => {
+            let id = state.next_upload.fetch_add(1, Ordering::Relaxed);
+            receive.upload(id, peer, request, body, |event| state.emit(event))
+        }
+        _ => Response::text(404, "not found"),
+    }
+}
+
+fn route(
+    request: &Request,
+    items: &[ShareItem],
+    access: &Access,
+) -> Response {
+    if request.method != "GET" && request.method != "HEAD" {
+        return Response::text(405, "method not allowed").header("Allow", "GET, HEAD");
+    }
+
+    let Some(segments) = split_path(&request.path) else {
+        return Response::text(400, "invalid path");
+    };
+    let response = match resolve(items, &segments) {
+        Target::Index => index_page(items, access),
+        Target::Path(item, _) if access.is_revoked(&item.name) => Response::text(410, "revoked"),
+        Target::Path(item, rel) => serve_path(request, &item.path, &rel),
+        Target::NotFound => Response::text(404, "not found"),
+    };
+    // Range 续传的后续分段不再计数，只有从头开始的下载占用次数
+    if request.method == "GET" && response.file_offset() == Some(0) && !access.take_download() {
+        return Response::text(410, "download limit reached");
+    }
+    response
+}
+
+/// 拆分请求路径，拒绝 `.`、`..` 等可能越界的片段
+fn split_path(path: &str) -> Option<Vec<String>> {
+    let mut segments = Vec::new();
+    for segment in path.split('/').filter(|s| !s.is_empty()) {
+        if segment == "." || segment == ".." || segment.contains('\\') || segment.contains('\0') {
+            return None;
+        }
+        segments.push(segment.to_string());
+    }
+    Some(segments)
+}
+
+enum Target<'a> {
+    Index,
+    /// 分享条目及其下的相对路径
+    Path(&'a ShareItem, PathBuf),
+    NotFound,
+}
+
+fn resolve<'a>(
+    items: &'a [ShareItem],
+    segments: &[String],
+) -> Target<'a> {
+    // 只分享了一个目录时直接以该目录作为根
+    if let [item] = items
+        && item.is_dir
+    {
+        return Target::Path(item, segments.iter().collect());
+    }
+
+    let Some((first, rest)) = segments.split_first() else {
+        return Target::Index;
+    };
+    let Some(item) = items.iter().find(|item| &item.name == first) else {
+        return Target::NotFound;
+    };
+    if item.is_dir {
+        Target::Path(item, rest.iter().collect())
+    } else if rest.is_empty() {
+        Target::Path(item, PathBuf::new())
+    } else {
+        Target::NotFound
+    }
+}
+
+fn serve_path(
+    request: &Request,
+    root: &Path,
+    rel: &Path,
+) -> Response {
+    // 空的相对路径不能 join，否则分享单个文件时会得到 `file/`
+    let path = if rel.as_os_str().is_empty() {
+        root.to_path_buf()
+    } else {
+        root.join(rel)
+    };
+    let (Ok(root), Ok(path)) = (root.canonicalize(), path.canonicalize()) else {
+        return Response::text(404, "not found");
+    };
+    // 防止符号链接指向分享目录之外
+    if !path.starts_with(&root) {
+        return Response::text(403, "forbidden");
+    }
+
+    let Ok(meta) = fs::metadata(&path) else {
+        return Response::text(404, "not found");
+    };
+    if meta.is_dir() {
+        if !request.path.ends_with('/') {
+            return Response::new(301).header("Location", format!("{}/", percent_encode(&request.path)));
+        }
+        return directory_page(&request.path, &path);
+    }
+    serve_file(request, &path, meta.len())
+}
+
+fn serve_file(
+    request: &Request,
+    path: &Path,
+    size: u64,
+) -> Response {
+    let Ok(file) = fs::File::open(path) else {
+        return Response::text(403, "forbidden");
+    };
+    let mime = content_type(path);
+
+    let range = request
+        .header("Range")
+        .map(|value| parse_range(value, size))
+        .unwrap_or(ByteRange::Full);
+    match range {
+        ByteRange::Full => Response::new(200)
+            .header("Accept-Ranges", "bytes")
+            .file(file, 0, size, mime),
+        ByteRange::Partial(start, end) => Response::new(206)
+            .header("Accept-Ranges", "bytes")
+            .header("Content-Range", format!("bytes {}-{}/{}", start, end, size))
+            .file(file, start, end - start + 1, mime),
+        ByteRange::Unsatisfiable => {
+            Response::text(416, "range not satisfiable").header("Content-Range", format!("bytes */{}", size))
+        }
+    }
+}
+
+struct Entry {
+    name: String,
+    is_dir: bool,
+    size: u64,
+}
+
+fn index_page(
+    items: &[ShareItem],
+    access: &Access,
+) -> Response {
+    let entries = items
+        .iter()
+        .filter(|item| !access.is_revoked(&item.name))
+        .map(|item| Entry {
+            name: item.name.clone(),
+            is_dir: item.is_dir,
+            size: fs::metadata(&item.path).map(|m| m.len()).unwrap_or(0),
+        })
+        .collect::<Vec<_>>();
+    Response::html(200, listing_html("/", &entries))
+}
+
+fn directory_page(
+    url_path: &str,
+    dir: &Path,
+) -> Response {
+    let Ok(read_dir) = fs::read_dir(dir) else {
+        return Response::text(403, "forbidden");
+    };
+    let mut entries: Vec<Entry> = read_dir
+        .flatten()
+        .filter_map(|entry| {
+            let meta = fs::metadata(entry.path()).ok()?;
+            Some(Entry {
+                name: entry.file_name().to_string_lossy().into_owned(),
+                is_dir: meta.is_dir(),
+                size: meta.len(),
+            })
+        })
+        .collect();
+    entries.sort_by(|a, b| b.is_dir.cmp(&a.is_dir).then_with(|| a.name.cmp(&b.name)));
+    Response::html(200, listing_html(url_path, &entries))
+}
+
+fn listing_html(
+    url_path: &str,
+    entries: &[Entry],
+) -> String {
+    let title = html_escape(url_path);
+    let mut rows = String::new();
+    if url_path != "/" {
+        rows.push_str("<tr><td><a href=\"../\">../</a></td><td></td></tr>\n");
+    }
+    for entry in entries {
+        let suffix = if entry.is_dir { "/" } else { "" };
+        let href = percent_encode(&format!("{}{}{}", url_path, entry.name, suffix));
+        let size = if entry.is_dir {
+            "-".to_string()
+        } else {
+            format_size(entry.size)
+        };
+        rows.push_str(&format!(
+            "<tr><td><a href=\"{}\">{}{}</a></td><td>{}</td></tr>\n",
+            href,
+            html_escape(&entry.name),
+            suffix,
+            size
+        ));
+    }
+
+    format!(
+        "<!DOCTYPE html>
+<html>
+<head>
+<meta charset=\"utf-8\">
+<meta name=\"viewport\" content=\"width=device-width, initial-scale=1\">
+<title>{title}</title>
+<style>
+body {{ font-family: -apple-system, sans-serif; margin: 2em; }}
+table {{ border-collapse: collapse; width: 100%; }}
+td {{ padding: 6px 12px; border-bottom: 1px solid #ddd; }}
+td:last-child {{ text-align: right; white-space: nowrap; }}
+a {{ text-decoration: none; }}
+</style>
+</head>
+<body>
+<h3>{title}</h3>
+<table>
+{rows}</table>
+</body>
+</html>
+"
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{io::Write, time::Instant};
+
+    use super::*;
+
+    fn start() -> ShareHandle {
+        let file = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("Cargo.toml");
+        ShareServer::new([file])
+            .unwrap()
+            .addr(SocketAddr::new(Ipv4Addr::LOCALHOST.into(), 0))
+            .start()
+            .unwrap()
+    }
+
+    fn get(
+        addr: SocketAddr,
+        path: &str,
+    ) -> String {
+        let mut stream = TcpStream::connect(addr).unwrap();
+        stream.set_read_timeout(Some(Duration::from_secs(5))).unwrap();
+        write!(stream, "GET {} HTTP/1.1\r\nHost: localhost\r\n\r\n", path).unwrap();
+        let mut response = String::new();
+        stream.read_to_string(&mut response).unwrap();
+        response
+    }
+
+    #[test]
+    fn serves_shared_file() {
+        let handle = start();
+        let response = get(handle.local_addr(), "/Cargo.toml");
+        assert!(response.starts_with("HTTP/1.1 200 OK"), "{}", response);
+        assert!(response.contains("sidecar-share"));
+        assert!(get(handle.local_addr(), "/missing").starts_with("HTTP/1.1 404"));
+    }
+
+    #[test]
+    fn range_requests_count_as_one_download() {
+        let file = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("Cargo.toml");
+        let handle = ShareServer::new([file])
+            .unwrap()
+            .addr(SocketAddr::new(Ipv4Addr::LOCALHOST.into(), 0))
+            .max_downloads(1)
+            .start()
+            .unwrap();
+        let addr = handle.local_addr();
+        let get_range = |range: &str| {
+            let mut stream = TcpStream::connect(addr).unwrap();
+            stream.set_read_timeout(Some(Duration::from_secs(5))).unwrap();
+            write!(
+                stream,
+                "GET /Cargo.toml HTTP/1.1\r\nHost: localhost\r\nRange: {}\r\n\r\n",
+                range
+            )
+            .unwrap();
+            let mut response = String::new();
+            stream.read_to_string(&mut response).unwrap();
+            response
+        };
+
+        let first = get_range("bytes=0-9");
+        assert!(first.starts_with("HTTP/1.1 206"), "{}", first);
+        let second = get_range("bytes=10-");
+        assert!(second.starts_with("HTTP/1.1 206"), "{}", second);
+        assert_eq!(handle.downloads(), 1);
+        assert!(get(addr, "/Cargo.toml").starts_with("HTTP/1.1 410"));
+    }
+
+    #[test]
+    fn stop_releases_port_without_waking_accept() {
+        let mut handle = start();
+        let addr = handle.local_addr();
+
+        let started = Instant::now();
+        handle.stop();
+        assert!(started.elapsed() < Duration::from_secs(1));
+        assert!(TcpListener::bind(addr).is_ok());
+    }
+
+    #[test]
+    fn connections_over_limit_wait_for_a_free_slot() {
+        let handle = start();
+        let addr = handle.local_addr();
+
+        // 占满连接名额，这些连接不发送请求，处理线程停在读取请求上
+        let idle: Vec<TcpStream> = (0..MAX_CONNECTIONS)
+            .map(|_| TcpStream::connect(addr).unwrap())
+            .collect();
+        let deadline = Instant::now() + Duration::from_secs(5);
+        while handle.state.connections.load(Ordering::Acquire) < MAX_CONNECTIONS && Instant::now() < deadline {
+            thread::sleep(Duration::from_millis(10));
+        }
+        assert_eq!(handle.state.connections.load(Ordering::Acquire), MAX_CONNECTIONS);
+
+        let mut waiting = TcpStream::connect(addr).unwrap();
+        write!(waiting, "GET /Cargo.toml HTTP/1.1\r\nHost: localhost\r\n\r\n").unwrap();
+        waiting.set_read_timeout(Some(Duration::from_millis(300))).unwrap();
+        let err = waiting.read(&mut [0u8; 1]).unwrap_err();
+        assert!(matches!(err.kind(), ErrorKind::WouldBlock | ErrorKind::TimedOut));
+
+        drop(idle);
+        waiting.set_read_timeout(Some(Duration::from_secs(5))).unwrap();
+        let mut response = String::new();
+        waiting.read_to_string(&mut response).unwrap();
+        assert!(response.starts_with("HTTP/1.1 200 OK"), "{}", response);
+    }
+}